@@ -1,7 +1,9 @@
+use common::address::{DeliveryAddress, address_payload, needs_delivery, parse_address_list};
 use common::cookie_manager::CookieManager;
 use common::gen_cp::CTokenGenerator;
 use common::http_utils::request_get;
 use common::login::QrCodeLoginStatus;
+use common::seat::{Seat, SeatMap, seat_payload};
 use common::ticket::*;
 use rand::{Rng, thread_rng};
 use reqwest::Client;
@@ -26,7 +28,7 @@ pub async fn get_countdown(
     // 获取网络时间 (秒级)
     let url = "https://api.bilibili.com/x/click-interface/click/now";
     let response = cookie_manager.get(url).await;
-    let now_sec = match response.send().await {
+    let now_sec = match cookie_manager.execute(response).await {
         Ok(data) => {
             let text = data.text().await.unwrap_or_default();
             log::debug!("API原始响应：{}", text);
@@ -76,14 +78,11 @@ pub async fn get_buyer_info(
     let req = cookie_manager
         .get("https://show.bilibili.com/api/ticket/buyer/list")
         .await;
-    let response = req.send().await;
+    let response = cookie_manager.execute(req).await;
     match response {
         Ok(resp) => {
             if resp.status().is_success() {
-                match tokio::task::block_in_place(|| {
-                    let rt = tokio::runtime::Runtime::new().unwrap();
-                    rt.block_on(resp.text())
-                }) {
+                match resp.text().await {
                     Ok(text) => {
                         log::debug!("获取购票人信息：{}", text);
                         match serde_json::from_str::<BuyerInfoResponse>(&text) {
@@ -110,6 +109,40 @@ pub async fn get_buyer_info(
     }
 }
 
+// 通过 nav 接口检查登录状态，已登录时返回 UID 和昵称，未登录或登录过期时返回 None
+pub async fn check_login(
+    cookie_manager: Arc<CookieManager>,
+) -> Result<Option<(i64, String)>, String> {
+    let req = cookie_manager
+        .get("https://api.bilibili.com/x/web-interface/nav")
+        .await;
+    let response = cookie_manager
+        .execute(req)
+        .await
+        .map_err(|e| format!("请求失败: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("请求失败: {}", response.status()));
+    }
+    let json: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("解析响应文本失败: {}", e))?;
+    let data = &json["data"];
+    if json["code"] == -101 || data["isLogin"] == false {
+        return Ok(None);
+    }
+    if json["code"] != 0 {
+        return Err(format!(
+            "获取账号信息失败: {}",
+            json["message"].as_str().unwrap_or("未知错误")
+        ));
+    }
+    Ok(Some((
+        data["mid"].as_i64().unwrap_or(0),
+        data["uname"].as_str().unwrap_or_default().to_string(),
+    )))
+}
+
 pub async fn get_project(
     cookie_manager: Arc<CookieManager>,
     project_id: &str,
@@ -123,14 +156,11 @@ pub async fn get_project(
             .as_str(),
         )
         .await;
-    let response = req.send().await;
+    let response = cookie_manager.execute(req).await;
     match response {
         Ok(resp) => {
             if resp.status().is_success() {
-                match tokio::task::block_in_place(|| {
-                    let rt = tokio::runtime::Runtime::new().unwrap();
-                    rt.block_on(resp.text())
-                }) {
+                match resp.text().await {
                     Ok(text) => {
                         log::debug!("获取项目详情：{}", text);
                         // 尝试常规解析
@@ -174,84 +204,89 @@ pub async fn poll_qrcode_login(qrcode_key: &str, user_agent: Option<&str>) -> Qr
 
     for attempt in 1..max_attempts {
         //轮询
-        let response = match request_get(
-            &client,
-            &format!(
-                "https://passport.bilibili.com/x/passport-login/web/qrcode/poll?qrcode_key={}",
-                qrcode_key
-            ),
-            None,
-        )
-        .await
-        {
-            Ok(resp) => resp,
-            Err(e) => return QrCodeLoginStatus::Failed(e.to_string()),
-        };
-
-        let mut all_cookies = Vec::new();
-        let cookie_headers = response.headers().get_all(reqwest::header::SET_COOKIE);
-        for value in cookie_headers {
-            if let Ok(cookie_str) = value.to_str() {
-                if let Some(end_pos) = cookie_str.find(';') {
-                    all_cookies.push(cookie_str[0..end_pos].to_string());
-                } else {
-                    all_cookies.push(cookie_str.to_string());
-                }
-            }
-        }
-
-        let json = match response.json::<serde_json::Value>().await {
-            Ok(j) => j,
-            Err(e) => return QrCodeLoginStatus::Failed(e.to_string()),
-        };
-
-        let code = json["data"]["code"].as_i64().unwrap_or(-1);
-        match code {
-            0 => {
-                //json获取cookie
-
-                if let Some(cookie_info) = json["data"]["cookie_info"].as_object() {
-                    for (key, value) in cookie_info {
-                        if let Some(val_str) = value["value"].as_str() {
-                            all_cookies.push(format!("{}={}", key, val_str));
-                        }
-                    }
-                }
-
-                if !all_cookies.is_empty() {
-                    return QrCodeLoginStatus::Success(all_cookies.join("; "));
-                } else {
-                    return QrCodeLoginStatus::Failed("无法获取Cookie信息".to_string());
-                }
-            }
-            86038 => return QrCodeLoginStatus::Expired,
-            86090 => {
+        match check_qrcode_login(&client, qrcode_key).await {
+            QrCodeLoginStatus::Scanning => {
                 log::info!(
                     "二维码已扫描，等待确认 (尝试 {} / {} 次)",
                     attempt,
                     max_attempts
                 );
-                //return QrCodeLoginStatus::Scanning;
             }
-            86101 => {
+            QrCodeLoginStatus::Pending => {
                 log::info!(
                     "二维码已生成，等待扫描 (尝试 {} / {} 次)",
                     attempt,
                     max_attempts
                 );
-                //return QrCodeLoginStatus::Pending
-            }
-            _ => {
-                let message = json["message"].as_str().unwrap_or("未知错误");
-
-                return QrCodeLoginStatus::Failed(message.to_string());
             }
+            status => return status,
         }
         tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
     }
     QrCodeLoginStatus::Expired
 }
 
+//查询一次二维码状态，未扫描返回 Pending，已扫描未确认返回 Scanning
+pub async fn check_qrcode_login(client: &Client, qrcode_key: &str) -> QrCodeLoginStatus {
+    let response = match request_get(
+        client,
+        &format!(
+            "https://passport.bilibili.com/x/passport-login/web/qrcode/poll?qrcode_key={}",
+            qrcode_key
+        ),
+        None,
+    )
+    .await
+    {
+        Ok(resp) => resp,
+        Err(e) => return QrCodeLoginStatus::Failed(e.to_string()),
+    };
+
+    let mut all_cookies = Vec::new();
+    let cookie_headers = response.headers().get_all(reqwest::header::SET_COOKIE);
+    for value in cookie_headers {
+        if let Ok(cookie_str) = value.to_str() {
+            if let Some(end_pos) = cookie_str.find(';') {
+                all_cookies.push(cookie_str[0..end_pos].to_string());
+            } else {
+                all_cookies.push(cookie_str.to_string());
+            }
+        }
+    }
+
+    let json = match response.json::<serde_json::Value>().await {
+        Ok(j) => j,
+        Err(e) => return QrCodeLoginStatus::Failed(e.to_string()),
+    };
+
+    let code = json["data"]["code"].as_i64().unwrap_or(-1);
+    match code {
+        0 => {
+            //json获取cookie
+            if let Some(cookie_info) = json["data"]["cookie_info"].as_object() {
+                for (key, value) in cookie_info {
+                    if let Some(val_str) = value["value"].as_str() {
+                        all_cookies.push(format!("{}={}", key, val_str));
+                    }
+                }
+            }
+
+            if !all_cookies.is_empty() {
+                QrCodeLoginStatus::Success(all_cookies.join("; "))
+            } else {
+                QrCodeLoginStatus::Failed("无法获取Cookie信息".to_string())
+            }
+        }
+        86038 => QrCodeLoginStatus::Expired,
+        86090 => QrCodeLoginStatus::Scanning,
+        86101 => QrCodeLoginStatus::Pending,
+        _ => {
+            let message = json["message"].as_str().unwrap_or("未知错误");
+            QrCodeLoginStatus::Failed(message.to_string())
+        }
+    }
+}
+
 pub async fn get_ticket_token(
     cookie_manager: Arc<CookieManager>,
     cpdd: Arc<Mutex<CTokenGenerator>>,
@@ -289,14 +324,12 @@ pub async fn get_ticket_token(
         "https://show.bilibili.com/api/ticket/order/prepare?project_id={}",
         project_id
     );
-    let response = cookie_manager.post(&url).await.json(&params).send().await;
+    let req = cookie_manager.post(&url).await.json(&params);
+    let response = cookie_manager.execute(req).await;
     match response {
         Ok(resp) => {
             if resp.status().is_success() {
-                match tokio::task::block_in_place(|| {
-                    let rt = tokio::runtime::Runtime::new().unwrap();
-                    rt.block_on(resp.json::<serde_json::Value>())
-                }) {
+                match resp.json::<serde_json::Value>().await {
                     Ok(json) => {
                         log::debug!("获取票token：{}", json);
                         let errno_value = json.get("errno").and_then(|v| v.as_i64()).unwrap_or(-1);
@@ -440,19 +473,93 @@ pub async fn get_ticket_token(
     }
 }
 
+// 账号保存的收货地址
+pub async fn get_address_list(
+    cookie_manager: Arc<CookieManager>,
+) -> Result<Vec<DeliveryAddress>, String> {
+    let url = "https://show.bilibili.com/api/ticket/addr/list";
+    let response = cookie_manager
+        .execute(cookie_manager.get(url).await)
+        .await
+        .map_err(|e| format!("请求失败: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("请求失败: {}", response.status()));
+    }
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("获取响应文本失败: {}", e))?;
+    let json: serde_json::Value =
+        serde_json::from_str(&text).map_err(|e| format!("解析响应文本失败: {}", e))?;
+    if json["errno"] != 0 && json["code"] != 0 {
+        return Err(format!(
+            "获取收货地址失败: {}",
+            json["msg"].as_str().unwrap_or("未知错误")
+        ));
+    }
+    let addresses = parse_address_list(&json["data"]);
+    // 地址包含姓名和电话，日志只记录地址ID
+    log::debug!(
+        "获取收货地址{}个：{:?}",
+        addresses.len(),
+        addresses.iter().map(|a| a.id.as_str()).collect::<Vec<_>>()
+    );
+    Ok(addresses)
+}
+
+// 选座项目的座位图
+pub async fn get_seat_map(
+    cookie_manager: Arc<CookieManager>,
+    project_id: &str,
+    screen_id: &str,
+    ticket_id: &str,
+) -> Result<SeatMap, String> {
+    let url = format!(
+        "https://show.bilibili.com/api/ticket/seat/getSeatPlan?project_id={}&screen_id={}&sku_id={}&requestSource=neul-next",
+        project_id, screen_id, ticket_id
+    );
+    let response = cookie_manager
+        .execute(cookie_manager.get(&url).await)
+        .await
+        .map_err(|e| format!("请求失败: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("请求失败: {}", response.status()));
+    }
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("获取响应文本失败: {}", e))?;
+    log::debug!("获取座位图：{}", text);
+    let json: serde_json::Value =
+        serde_json::from_str(&text).map_err(|e| format!("解析响应文本失败: {}", e))?;
+    if json["errno"] != 0 && json["code"] != 0 {
+        return Err(format!(
+            "获取座位图失败: {}",
+            json["msg"].as_str().unwrap_or("未知错误")
+        ));
+    }
+    Ok(SeatMap::from_value(&json["data"], screen_id))
+}
+
 pub async fn confirm_ticket_order(
     cookie_manager: Arc<CookieManager>,
     project_id: &str,
     token: &str,
+    seats: &[Seat],
 ) -> Result<ConfirmTicketResult, String> {
-    let url = format!(
+    let mut url = format!(
         "https://show.bilibili.com/api/ticket/order/confirmInfo?token={}&voucher=&project_id={}&requestSource=neul-next",
         token, project_id
     );
+    if !seats.is_empty() {
+        url = reqwest::Url::parse_with_params(&url, &[("seat_info", seat_payload(seats))])
+            .map_err(|e| format!("构造确认订单参数失败: {}", e))?
+            .to_string();
+    }
     let response = cookie_manager
-        .get(&url)
-        .await
-        .send()
+        .execute(cookie_manager.get(&url).await)
         .await
         .map_err(|e| format!("请求失败: {}", e))?;
 
@@ -487,6 +594,7 @@ pub async fn create_order(
     is_hot: bool,
     biliticket: &BilibiliTicket,
     buyer_info: &Vec<BuyerInfo>,
+    seats: &[Seat], //选座项目选中的座位，不选座时为空
     is_mobile: bool,
     need_retry: bool,
     fast_mode: bool,
@@ -549,7 +657,7 @@ pub async fn create_order(
     };
     let ticket_id_int = ticket_id.parse::<i64>().map_err(|_| 999)?;
 
-    let data = match biliticket.id_bind {
+    let mut data = match biliticket.id_bind {
         0 => {
             let no_bind_buyer_info = biliticket.no_bind_buyer_info.clone().unwrap();
 
@@ -615,17 +723,34 @@ pub async fn create_order(
         }
     };
 
-    log::debug!("抢票data ：{:?}", data);
-    let response = cookie_manager
+    if !seats.is_empty() {
+        data["seat_info"] = json!(seat_payload(seats));
+    }
+    // 只有快递配送的场次才提交收货地址，没有项目信息时无法判断，按已选择的地址提交
+    let needs_address = biliticket
+        .project_info
+        .as_ref()
+        .is_none_or(|info| needs_delivery(info, &biliticket.screen_id));
+    if let Some(address) = biliticket.deliver_info.as_ref().filter(|_| needs_address) {
+        data["deliver_info"] = json!(address_payload(address));
+    }
+
+    // 购票人和收货信息包含姓名、证件号和电话，不写入日志
+    let mut logged = data.clone();
+    for key in ["buyer_info", "deliver_info"] {
+        if logged.get(key).is_some() {
+            logged[key] = json!("<redacted>");
+        }
+    }
+    log::debug!("抢票data ：{}", logged);
+    let req = cookie_manager
         .post_with_headers(&url, input_risk_header)
         .await
-        .json(&data)
-        .send()
-        .await
-        .map_err(|e| {
-            log::error!("请求失败: {}", e);
-            412
-        })?;
+        .json(&data);
+    let response = cookie_manager.execute(req).await.map_err(|e| {
+        log::error!("请求失败: {}", e);
+        412
+    })?;
     if response.status() != 200 {
         log::error!("请求失败: {}", response.status());
         return Err(response.status().as_u16() as i32);
@@ -674,9 +799,7 @@ pub async fn check_fake_ticket(
     }
     log::debug!("check_fake_ticket_url: {}", url);
     let response = cookie_manager
-        .get(&url)
-        .await
-        .send()
+        .execute(cookie_manager.get(&url).await)
         .await
         .map_err(|e| format!("请求失败: {}", e))?;
     log::debug!("check_fake_ticket: {:?}", response);
@@ -0,0 +1,137 @@
+use std::sync::Arc;
+
+use common::account::Account;
+use common::address::{DeliveryAddress, needs_delivery, pick_address};
+use common::captcha::LocalCaptcha;
+use common::cookie_manager::CookieManager;
+use common::grab_profile::GrabProfile;
+use common::push::PushConfig;
+use common::retry::RetryPolicy;
+use common::taskmanager::{GrabTicketRequest, TaskStatus};
+use common::ticket::BilibiliTicket;
+use common::utility::CustomConfig;
+use common::utils::Config;
+
+use crate::api::{get_address_list, get_buyer_info, get_project};
+
+// 按项目、场次、票种和购票人ID构造抢票请求，未指定购票人时使用默认购票人
+pub async fn build_grab_request(
+    account: &Account,
+    cookie_manager: Arc<CookieManager>,
+    project_id: &str,
+    screen_id: &str,
+    ticket_id: &str,
+    buyer_ids: &[String],
+    grab_mode: u8,
+) -> Result<GrabTicketRequest, String> {
+    let info = get_project(cookie_manager.clone(), project_id).await?.data;
+    let all_buyers = get_buyer_info(cookie_manager.clone()).await?.data.list;
+    let buyers: Vec<_> = if buyer_ids.is_empty() {
+        all_buyers
+            .into_iter()
+            .filter(|b| b.is_default == 1)
+            .collect()
+    } else {
+        all_buyers
+            .into_iter()
+            .filter(|b| buyer_ids.contains(&b.id.to_string()))
+            .collect()
+    };
+
+    let config = Config::load_config().unwrap_or_else(|_| Config::new());
+    let push_config = serde_json::from_value::<PushConfig>(config["push_config"].clone())
+        .unwrap_or_else(|_| PushConfig::new());
+    let custom_config = serde_json::from_value::<CustomConfig>(config["custom_config"].clone())
+        .unwrap_or_else(|_| CustomConfig::new());
+
+    let mut biliticket = BilibiliTicket::new(
+        &0,
+        &custom_config.custom_ua,
+        &custom_config,
+        account,
+        &push_config,
+        &2,
+        project_id,
+    );
+    biliticket.cookie_manager = Some(cookie_manager.clone());
+    biliticket.screen_id = screen_id.to_string();
+    biliticket.select_ticket_id = Some(ticket_id.to_string());
+    biliticket.id_bind = info.id_bind;
+    biliticket.buyer_info = Some(buyers.clone());
+    biliticket.count = Some(buyers.len().max(1) as i32);
+    // 快递配送的场次默认使用账号的默认收货地址，获取失败时由提交前检查提示
+    if needs_delivery(&info, screen_id) {
+        match resolve_address(cookie_manager.clone(), None).await {
+            Ok(address) => biliticket.deliver_info = Some(address),
+            Err(e) => log::warn!("获取默认收货地址失败：{}", e),
+        }
+    }
+
+    Ok(GrabTicketRequest {
+        task_id: String::new(),
+        uid: account.uid,
+        project_id: project_id.to_string(),
+        screen_id: screen_id.to_string(),
+        ticket_id: ticket_id.to_string(),
+        count: buyers.len().max(1) as i16,
+        buyer_info: buyers,
+        cookie_manager,
+        is_hot: info.hot_project,
+        biliticket: BilibiliTicket {
+            project_info: Some(info),
+            ..biliticket
+        },
+        grab_mode,
+        status: TaskStatus::Pending,
+        start_time: None,
+        local_captcha: LocalCaptcha::new(),
+        skip_words: None,
+        max_total_price: None,
+        preferences: Vec::new(),
+        dry_run: false,
+        hooks: Default::default(),
+        retry_policy: RetryPolicy::load(&config, grab_mode),
+        seat_selection: Default::default(),
+    })
+}
+
+// 按抢票配置构造请求，购票人等信息每次重新获取
+pub async fn build_profile_request(
+    account: &Account,
+    cookie_manager: Arc<CookieManager>,
+    profile: &GrabProfile,
+) -> Result<GrabTicketRequest, String> {
+    profile.validate()?;
+    let mut request = build_grab_request(
+        account,
+        cookie_manager,
+        &profile.project_id,
+        &profile.screen_id,
+        &profile.ticket_id,
+        &profile.buyer_ids,
+        profile.grab_mode,
+    )
+    .await?;
+    profile.apply(&mut request);
+    if profile.address_id.is_some() {
+        let address = resolve_address(
+            request.cookie_manager.clone(),
+            profile.address_id.as_deref(),
+        )
+        .await?;
+        request.biliticket.deliver_info = Some(address);
+    }
+    log::info!("使用抢票配置: {}（ID: {}）", profile.name, profile.id);
+    Ok(request)
+}
+
+// 按地址ID获取收货地址，未指定时使用默认地址
+pub async fn resolve_address(
+    cookie_manager: Arc<CookieManager>,
+    address_id: Option<&str>,
+) -> Result<DeliveryAddress, String> {
+    let addresses = get_address_list(cookie_manager).await?;
+    let address = pick_address(&addresses, address_id)?;
+    log::info!("收货地址ID：{}", address.id);
+    Ok(address)
+}
@@ -1,3 +1,6 @@
 pub mod api;
+pub mod grab_request;
+pub mod sale_calendar;
 pub mod show_orderlist;
 pub mod taskmanager;
+pub mod tasks;
@@ -0,0 +1,99 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use common::account::Account;
+use common::cookie_manager::CookieManager;
+use common::notification::NotifyEvent;
+use common::push::PushConfig;
+use common::sale_calendar::{
+    SaleOpening, SaleReminderConfig, due_reminders, reminder_message, saved_project_ids,
+    upcoming_sales,
+};
+use common::utils::Config;
+
+use crate::api::get_project;
+
+// 重新获取项目开售信息的间隔
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10 * 60);
+// 检查提醒是否到期的间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+// 获取各项目尚未开售的票档，按开售时间排列
+pub async fn fetch_upcoming_sales(
+    cookie_manager: Arc<CookieManager>,
+    project_ids: &[String],
+) -> Vec<SaleOpening> {
+    let now = chrono::Local::now().timestamp();
+    let mut openings = Vec::new();
+    for project_id in project_ids {
+        match get_project(cookie_manager.clone(), project_id).await {
+            Ok(info) => openings.extend(upcoming_sales(&info.data, now)),
+            Err(e) => log::error!("获取项目{}开售信息失败：{}", project_id, e),
+        }
+    }
+    openings.sort_by(|a, b| {
+        a.sale_start
+            .cmp(&b.sale_start)
+            .then_with(|| a.project_id.cmp(&b.project_id))
+            .then_with(|| a.tier.cmp(&b.tier))
+    });
+    openings
+}
+
+// 查询开售信息使用的账号，优先启用的账号，没有则用第一个
+pub fn query_account(accounts: &[Account]) -> Option<&Account> {
+    accounts
+        .iter()
+        .find(|a| a.is_active)
+        .or_else(|| accounts.first())
+}
+
+// CookieManager::new 的 future 不是 Send，放到阻塞线程中执行
+async fn active_cookie_manager() -> Option<Arc<CookieManager>> {
+    tokio::task::spawn_blocking(|| {
+        let accounts = Config::load_all_accounts();
+        let mut account = query_account(&accounts).cloned()?;
+        tokio::runtime::Handle::current().block_on(account.ensure_client());
+        account.cookie_manager
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+// 后台运行开售提醒，每次刷新时重新读取配置，设置和项目列表的修改无需重启
+pub async fn run_sale_reminders() {
+    let mut sent = HashSet::new();
+    let mut openings: Vec<SaleOpening> = Vec::new();
+    let mut reminder_config = SaleReminderConfig::default();
+    let mut push_config = PushConfig::new();
+    let mut last_refresh: Option<tokio::time::Instant> = None;
+
+    loop {
+        if last_refresh.is_none_or(|t| t.elapsed() >= REFRESH_INTERVAL) {
+            last_refresh = Some(tokio::time::Instant::now());
+            let config = Config::load_config().unwrap_or_else(|_| Config::new());
+            reminder_config = SaleReminderConfig::load(&config);
+            push_config = serde_json::from_value(config["push_config"].clone())
+                .unwrap_or_else(|_| PushConfig::new());
+            let project_ids = saved_project_ids(&config);
+            if reminder_config.enabled && !project_ids.is_empty() {
+                if let Some(cookie_manager) = active_cookie_manager().await {
+                    openings = fetch_upcoming_sales(cookie_manager, &project_ids).await;
+                    log::debug!("已刷新开售日历，共{}个待开售票档", openings.len());
+                }
+            }
+        }
+
+        let now = chrono::Local::now().timestamp();
+        for (opening, offset) in due_reminders(&openings, &reminder_config, now, &mut sent) {
+            let (title, message) = reminder_message(&opening, offset);
+            log::info!("{}", title);
+            push_config
+                .notify_async(NotifyEvent::SaleReminder, &title, &message, &None)
+                .await;
+        }
+        tokio::time::sleep(CHECK_INTERVAL).await;
+    }
+}
@@ -1,21 +1,51 @@
+use common::account::Account;
+use common::ics::{CalendarEvent, build_calendar, order_event};
+use common::project_detail::VenueDesc;
 use common::show_orderlist::*;
 use common::{cookie_manager::CookieManager, http_utils::request_get};
 use serde_json;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+use crate::api::get_project;
+
+// 订单列表每页条数
+const ORDER_PAGE_SIZE: usize = 10;
+// 导出全部订单时最多翻页数
+const MAX_ORDER_PAGES: usize = 50;
+
+// 最近的订单（第一页）
 pub async fn get_orderlist(cookie_manager: Arc<CookieManager>) -> Result<OrderResponse, String> {
-    match cookie_manager
-        .get("https://show.bilibili.com/api/ticket/ordercenter/ticketList?page=0&page_size=10")
-        .await
-        .send()
-        .await
-    {
+    get_orderlist_page(cookie_manager, 0).await
+}
+
+// 逐页获取全部订单，直到取满 total 或某页为空
+pub async fn get_all_orders(cookie_manager: Arc<CookieManager>) -> Result<Vec<Order>, String> {
+    let mut orders = Vec::new();
+    for page in 0..MAX_ORDER_PAGES {
+        let response = get_orderlist_page(cookie_manager.clone(), page).await?;
+        let count = response.data.list.len();
+        orders.extend(response.data.list);
+        if count < ORDER_PAGE_SIZE || orders.len() >= response.data.total.max(0) as usize {
+            break;
+        }
+    }
+    Ok(orders)
+}
+
+pub async fn get_orderlist_page(
+    cookie_manager: Arc<CookieManager>,
+    page: usize,
+) -> Result<OrderResponse, String> {
+    let url = format!(
+        "https://show.bilibili.com/api/ticket/ordercenter/ticketList?page={}&page_size={}",
+        page, ORDER_PAGE_SIZE
+    );
+    let req = cookie_manager.get(&url).await;
+    match cookie_manager.execute(req).await {
         Ok(resp) => {
             if resp.status().is_success() {
-                match tokio::task::block_in_place(|| {
-                    let rt = tokio::runtime::Runtime::new().unwrap();
-                    rt.block_on(resp.text())
-                }) {
+                match resp.text().await {
                     Ok(text) => {
                         log::debug!("获取全部订单：{}", text);
                         match serde_json::from_str::<OrderResponse>(&text) {
@@ -44,3 +74,95 @@ pub async fn get_orderlist(cookie_manager: Arc<CookieManager>) -> Result<OrderRe
         }
     };
 }
+
+pub async fn get_order_detail(
+    cookie_manager: Arc<CookieManager>,
+    order_id: &str,
+) -> Result<OrderDetail, String> {
+    let url = format!(
+        "https://show.bilibili.com/api/ticket/order/info?order_id={}",
+        order_id
+    );
+    let resp = cookie_manager
+        .execute(cookie_manager.get(&url).await)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !resp.status().is_success() {
+        return Err(format!("获取订单详情不期待响应：{}", resp.status()));
+    }
+    let text = resp
+        .text()
+        .await
+        .map_err(|e| format!("获取data失败： {}", e))?;
+    log::debug!("获取订单详情：{}", text);
+    let json: serde_json::Value = serde_json::from_str(&text).map_err(|e| {
+        log::error!("获取订单详情json解析失败：{}", e);
+        format!("获取订单详情json解析失败：{}", e)
+    })?;
+
+    let errno = json["errno"]
+        .as_i64()
+        .or_else(|| json["code"].as_i64())
+        .unwrap_or(-1);
+    if errno != 0 || !json["data"].is_object() {
+        let msg = json["msg"]
+            .as_str()
+            .or_else(|| json["message"].as_str())
+            .unwrap_or("未知错误");
+        return Err(format!("获取订单详情失败：{}", msg));
+    }
+    Ok(OrderDetail::from_value(&json["data"]))
+}
+
+// 导出所有账号的已支付订单为 iCalendar，每个订单一个场次事件，返回日历文本和事件数
+pub async fn export_orders_calendar(accounts: &[Account]) -> Result<(String, usize), String> {
+    let mut events: Vec<CalendarEvent> = Vec::new();
+    let mut seen_orders = HashSet::new();
+    let mut venues: HashMap<i64, Option<VenueDesc>> = HashMap::new();
+    let mut failed = Vec::new();
+
+    for account in accounts {
+        let Some(cookie_manager) = account.cookie_manager.clone() else {
+            continue;
+        };
+        let orders = match get_all_orders(cookie_manager.clone()).await {
+            Ok(orders) => orders,
+            Err(e) => {
+                log::error!("获取账号{}的订单失败：{}", account.uid, e);
+                failed.push(format!("{}: {}", account.name, e));
+                continue;
+            }
+        };
+        for order in orders.iter().filter(|order| order.is_paid()) {
+            if !seen_orders.insert(order.order_id.clone()) {
+                continue;
+            }
+            if !venues.contains_key(&order.item_id) {
+                let venue =
+                    match get_project(cookie_manager.clone(), &order.item_id.to_string()).await {
+                        Ok(info) => info.data.project_detail().venue,
+                        Err(e) => {
+                            log::warn!("获取项目{}场馆信息失败：{}", order.item_id, e);
+                            None
+                        }
+                    };
+                venues.insert(order.item_id, venue);
+            }
+            let venue = venues.get(&order.item_id).and_then(Option::as_ref);
+            match order_event(order, &account.name, account.uid, venue) {
+                Some(event) => events.push(event),
+                None => log::warn!(
+                    "订单{}的场次时间无法解析：{}",
+                    order.order_id,
+                    order.item_info.screen_start_time
+                ),
+            }
+        }
+    }
+
+    if events.is_empty() && !failed.is_empty() {
+        return Err(failed.join("; "));
+    }
+    log::info!("已导出{}个订单到日历", events.len());
+    Ok((build_calendar(&events), events.len()))
+}
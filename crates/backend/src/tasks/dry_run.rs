@@ -0,0 +1,365 @@
+use std::sync::{Arc, Mutex};
+
+use common::address::{delivery_type_name, needs_delivery, screen_delivery_type};
+use common::gen_cp::CTokenGenerator;
+use common::i18n::{t, tr};
+use common::money::Money;
+use common::seat::{needs_seat_selection, pick_seats, seat_names};
+use common::task_handle::TaskContext;
+use common::taskmanager::{DryRunCheck, DryRunResult, GrabTicketRequest};
+
+use crate::api::{
+    check_login, confirm_ticket_order, get_address_list, get_buyer_info, get_project, get_seat_map,
+    get_ticket_token,
+};
+use crate::tasks::grab::new_ctoken_generator;
+
+pub async fn run(ctx: TaskContext<DryRunResult>, grab_ticket_req: GrabTicketRequest) {
+    ctx.start();
+    log::info!("试运行任务{}，只检查配置，不会下单", ctx.id());
+    let cpdd = new_ctoken_generator(
+        grab_ticket_req
+            .biliticket
+            .project_info
+            .as_ref()
+            .map(|info| info.sale_begin),
+    );
+    let checks = run_checks(&grab_ticket_req, cpdd).await;
+    ctx.finish(DryRunResult {
+        task_id: ctx.id().to_string(),
+        uid: grab_ticket_req.uid,
+        success: checks.iter().all(|c| c.passed),
+        checks,
+    });
+}
+
+// 试运行：依次检查账号、项目、场次、票种、购票人、token 与确认订单，不会调用 create_order
+async fn run_checks(
+    grab_ticket_req: &GrabTicketRequest,
+    cpdd: Arc<Mutex<CTokenGenerator>>,
+) -> Vec<DryRunCheck> {
+    let mut checks = Vec::new();
+    // name 为检查项的文案ID
+    let mut check = |name: &'static str, passed: bool, message: String| {
+        let name = t(name);
+        if passed {
+            log::info!("试运行检查[{}]通过：{}", name, message);
+        } else {
+            log::error!("试运行检查[{}]失败：{}", name, message);
+        }
+        checks.push(DryRunCheck {
+            name: name.to_string(),
+            passed,
+            message,
+        });
+    };
+    let cookie_manager = grab_ticket_req.cookie_manager.clone();
+    let id_bind = grab_ticket_req.biliticket.id_bind;
+
+    // 账号：登录状态和任务UID
+    match check_login(cookie_manager.clone()).await {
+        Ok(Some((uid, name))) => check(
+            "dry_run.check.account",
+            uid == grab_ticket_req.uid,
+            if uid == grab_ticket_req.uid {
+                tr("dry_run.account_ok", &[&name, &uid])
+            } else {
+                tr("dry_run.account_mismatch", &[&uid, &grab_ticket_req.uid])
+            },
+        ),
+        Ok(None) => check(
+            "dry_run.check.account",
+            false,
+            t("dry_run.account_not_login").to_string(),
+        ),
+        Err(e) => check(
+            "dry_run.check.account",
+            false,
+            tr("dry_run.account_failed", &[&e]),
+        ),
+    }
+
+    // 项目、场次、票种
+    let mut is_hot = grab_ticket_req.is_hot;
+    let mut project_info = grab_ticket_req.biliticket.project_info.clone();
+    match get_project(cookie_manager.clone(), &grab_ticket_req.project_id).await {
+        Ok(project) => {
+            let info = project.data;
+            is_hot = info.hot_project;
+            check(
+                "dry_run.check.project",
+                true,
+                tr("dry_run.name_status", &[&info.name, &info.sale_flag]),
+            );
+            check(
+                "dry_run.check.id_bind",
+                info.id_bind == id_bind,
+                tr("dry_run.id_bind", &[&info.id_bind, &id_bind]),
+            );
+            match info
+                .screen_list
+                .iter()
+                .find(|s| s.id.to_string() == grab_ticket_req.screen_id)
+            {
+                Some(screen) => {
+                    check(
+                        "dry_run.check.screen",
+                        true,
+                        tr(
+                            "dry_run.name_status",
+                            &[&screen.name, &screen.sale_flag.display_name],
+                        ),
+                    );
+                    match screen
+                        .ticket_list
+                        .iter()
+                        .find(|t| t.id.to_string() == grab_ticket_req.ticket_id)
+                    {
+                        Some(ticket) => check(
+                            "dry_run.check.ticket",
+                            true,
+                            tr(
+                                "dry_run.ticket_ok",
+                                &[
+                                    &ticket.desc,
+                                    &Money::from_fen(ticket.price as i64),
+                                    &ticket.sale_flag.display_name,
+                                ],
+                            ),
+                        ),
+                        None => check(
+                            "dry_run.check.ticket",
+                            false,
+                            tr("dry_run.ticket_missing", &[&grab_ticket_req.ticket_id]),
+                        ),
+                    }
+                }
+                None => {
+                    check(
+                        "dry_run.check.screen",
+                        false,
+                        tr("dry_run.screen_missing", &[&grab_ticket_req.screen_id]),
+                    );
+                }
+            }
+            project_info = Some(info);
+        }
+        Err(e) => check("dry_run.check.project", false, e),
+    }
+
+    // 购票人
+    match id_bind {
+        0 => match &grab_ticket_req.biliticket.no_bind_buyer_info {
+            Some(buyer) if !buyer.name.is_empty() && !buyer.tel.is_empty() => check(
+                "dry_run.check.buyer",
+                true,
+                tr("dry_run.no_bind_buyer", &[&buyer.name]),
+            ),
+            _ => check(
+                "dry_run.check.buyer",
+                false,
+                t("validate.no_bind_buyer_missing").to_string(),
+            ),
+        },
+        1 | 2 => {
+            let buyers = grab_ticket_req.resolved_buyers();
+            if buyers.is_empty() {
+                check(
+                    "dry_run.check.buyer",
+                    false,
+                    t("validate.buyer_missing").to_string(),
+                );
+            } else {
+                match get_buyer_info(cookie_manager.clone()).await {
+                    Ok(resp) => {
+                        let missing: Vec<String> = buyers
+                            .iter()
+                            .filter(|b| !resp.data.list.iter().any(|l| l.id == b.id))
+                            .map(|b| b.name.clone())
+                            .collect();
+                        if missing.is_empty() {
+                            let names: Vec<String> =
+                                buyers.iter().map(|b| b.name.clone()).collect();
+                            check(
+                                "dry_run.check.buyer",
+                                true,
+                                tr("dry_run.buyers", &[&names.join("、")]),
+                            );
+                        } else {
+                            check(
+                                "dry_run.check.buyer",
+                                false,
+                                tr("dry_run.buyers_missing", &[&missing.join("、")]),
+                            );
+                        }
+                    }
+                    Err(e) => check(
+                        "dry_run.check.buyer",
+                        false,
+                        tr("dry_run.buyer_list_failed", &[&e]),
+                    ),
+                }
+                check(
+                    "dry_run.check.count",
+                    buyers.len() == grab_ticket_req.count as usize,
+                    tr("dry_run.count", &[&grab_ticket_req.count, &buyers.len()]),
+                );
+            }
+        }
+        _ => check(
+            "dry_run.check.buyer",
+            false,
+            tr("validate.buyer_type_unknown", &[&id_bind]),
+        ),
+    }
+
+    // 选座：获取座位图并按选座设置试选
+    let pick_seat = project_info
+        .as_ref()
+        .is_some_and(|info| needs_seat_selection(info, &grab_ticket_req.screen_id));
+    let mut seats = Vec::new();
+    if pick_seat {
+        match get_seat_map(
+            cookie_manager.clone(),
+            &grab_ticket_req.project_id,
+            &grab_ticket_req.screen_id,
+            &grab_ticket_req.ticket_id,
+        )
+        .await
+        {
+            Ok(seat_map) => match pick_seats(
+                &seat_map,
+                &grab_ticket_req.seat_selection,
+                grab_ticket_req.seat_count(),
+            ) {
+                Ok(picked) => {
+                    check(
+                        "dry_run.check.seat",
+                        true,
+                        tr(
+                            "dry_run.seats",
+                            &[&seat_names(&picked), &seat_map.available_count()],
+                        ),
+                    );
+                    seats = picked;
+                }
+                Err(e) => check("dry_run.check.seat", false, e),
+            },
+            Err(e) => check(
+                "dry_run.check.seat",
+                false,
+                tr("dry_run.seat_map_failed", &[&e]),
+            ),
+        }
+    }
+
+    // 收货地址：快递配送的场次确认地址仍在账号的地址列表中
+    if let Some(info) = project_info
+        .as_ref()
+        .filter(|info| needs_delivery(info, &grab_ticket_req.screen_id))
+    {
+        let delivery = tr(
+            "dry_run.delivery",
+            &[
+                &delivery_type_name(screen_delivery_type(info, &grab_ticket_req.screen_id)),
+                &Money::from_fen(info.express_fee as i64),
+            ],
+        );
+        match &grab_ticket_req.biliticket.deliver_info {
+            Some(address) => match get_address_list(cookie_manager.clone()).await {
+                Ok(addresses) => check(
+                    "dry_run.check.address",
+                    addresses.iter().any(|a| a.id == address.id),
+                    // 检查结果会写入日志，只显示地址ID
+                    tr("dry_run.address", &[&delivery, &address.id]),
+                ),
+                Err(e) => check(
+                    "dry_run.check.address",
+                    false,
+                    tr("dry_run.address_list_failed", &[&e]),
+                ),
+            },
+            None => check(
+                "dry_run.check.address",
+                false,
+                tr("dry_run.address_not_chosen", &[&delivery]),
+            ),
+        }
+    }
+
+    // token 与确认订单
+    match get_ticket_token(
+        cookie_manager.clone(),
+        cpdd,
+        &grab_ticket_req.project_id,
+        &grab_ticket_req.screen_id,
+        &grab_ticket_req.ticket_id,
+        grab_ticket_req.count,
+        is_hot,
+    )
+    .await
+    {
+        Ok((token, _ptoken)) => {
+            check(
+                "dry_run.check.token",
+                true,
+                t("dry_run.token_ok").to_string(),
+            );
+            match confirm_ticket_order(
+                cookie_manager.clone(),
+                &grab_ticket_req.project_id,
+                &token,
+                &seats,
+            )
+            .await
+            {
+                Ok(confirm_result) => {
+                    let express_fee = project_info
+                        .as_ref()
+                        .map(|info| Money::from_fen(info.express_fee as i64))
+                        .unwrap_or(Money::ZERO);
+                    let total = confirm_result.total_with_express(express_fee);
+                    check(
+                        "dry_run.check.confirm",
+                        true,
+                        tr(
+                            "dry_run.confirm_ok",
+                            &[
+                                &confirm_result.screen_name,
+                                &confirm_result.ticket_info.name,
+                                &confirm_result.count,
+                                &total,
+                            ],
+                        ),
+                    );
+                    if let Some(max_total_price) = grab_ticket_req.max_total_price {
+                        if project_info.is_some() {
+                            check(
+                                "dry_run.check.budget",
+                                total <= max_total_price,
+                                tr("dry_run.budget", &[&total, &max_total_price]),
+                            );
+                        } else {
+                            check(
+                                "dry_run.check.budget",
+                                false,
+                                t("grab.budget_no_project").to_string(),
+                            );
+                        }
+                    }
+                }
+                Err(e) => check("dry_run.check.confirm", false, e),
+            }
+        }
+        Err(risk_param) => check(
+            "dry_run.check.token",
+            false,
+            tr(
+                "dry_run.token_failed",
+                &[&risk_param.code, &risk_param.message],
+            ),
+        ),
+    }
+
+    checks
+}
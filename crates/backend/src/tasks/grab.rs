@@ -0,0 +1,1212 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use common::captcha::handle_risk_verification;
+use common::cookie_manager::CookieManager;
+use common::diagnostics;
+use common::gen_cp::CTokenGenerator;
+use common::hooks::{self, HookPayload};
+use common::i18n::{t, tr};
+use common::money::Money;
+use common::notification::NotifyEvent;
+use common::push::PushConfig;
+use common::retry::{RetryAttempts, RetryPolicy};
+use common::seat::{Seat, needs_seat_selection, pick_seats, seat_names};
+use common::task_handle::TaskContext;
+use common::taskmanager::{GrabTicketRequest, GrabTicketResult};
+use common::ticket::*;
+use common::ticket_preference::rank_candidates;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use serde_json::json;
+
+use crate::api::*;
+use crate::show_orderlist::get_orderlist;
+
+// 下单失败达到此次数后，后续请求标记为重试下单
+const ORDER_RETRY_FLAG_AFTER: u32 = 3;
+
+// 单次抢票任务的重试计数和截止时间
+struct RetryState {
+    policy: RetryPolicy,
+    attempts: RetryAttempts,
+    deadline: Option<Instant>,
+}
+
+impl RetryState {
+    fn new(policy: &RetryPolicy) -> Self {
+        Self {
+            policy: policy.clone(),
+            attempts: RetryAttempts::default(),
+            deadline: None,
+        }
+    }
+
+    // 开始抢票时计时，定时模式从开售后开始计算
+    fn start(&mut self) {
+        self.deadline = self.policy.deadline_from(Instant::now());
+    }
+
+    fn expired(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+// 上报结果时附带各阶段尝试次数
+fn finish(ctx: &TaskContext<GrabTicketResult>, retry: &RetryState, result: GrabTicketResult) {
+    log::info!(
+        "抢票任务{}结束，获取token {}次，确认订单 {}次，下单 {}次",
+        ctx.id(),
+        retry.attempts.token,
+        retry.attempts.confirm,
+        retry.attempts.order
+    );
+    ctx.finish(GrabTicketResult {
+        attempts: retry.attempts,
+        ..result
+    });
+}
+
+// 创建 ctoken 生成器，未获取到开售时间时以当前时间为准
+pub fn new_ctoken_generator(sale_begin: Option<i64>) -> Arc<Mutex<CTokenGenerator>> {
+    let mut rng = StdRng::from_entropy();
+    let base_time = sale_begin.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64
+    });
+    Arc::new(Mutex::new(CTokenGenerator::new(
+        base_time,
+        0,
+        rng.gen_range(2000..10000),
+    )))
+}
+
+pub async fn run(ctx: &TaskContext<GrabTicketResult>, mut grab_ticket_req: GrabTicketRequest) {
+    ctx.start();
+    log::debug!("开始分析抢票任务：{}", ctx.id());
+    // 未携带项目信息时先获取，开售时间、快递费和预算检查都依赖项目信息
+    if grab_ticket_req.biliticket.project_info.is_none() {
+        match get_project(
+            grab_ticket_req.cookie_manager.clone(),
+            &grab_ticket_req.project_id,
+        )
+        .await
+        {
+            Ok(project) => grab_ticket_req.biliticket.project_info = Some(project.data),
+            Err(e) => {
+                log::warn!("获取项目信息失败：{}", e);
+                if logged_out(grab_ticket_req.cookie_manager.clone()).await {
+                    let retry = RetryState::new(&grab_ticket_req.retry_policy);
+                    session_expired(ctx, &grab_ticket_req, &retry).await;
+                    return;
+                }
+            }
+        }
+    }
+    let cpdd = new_ctoken_generator(
+        grab_ticket_req
+            .biliticket
+            .project_info
+            .as_ref()
+            .map(|info| info.sale_begin),
+    );
+
+    let mut retry = RetryState::new(&grab_ticket_req.retry_policy);
+
+    match grab_ticket_req.grab_mode {
+        0 => {
+            log::debug!("定时抢票模式");
+            if wait_for_sale(ctx, &grab_ticket_req).await {
+                log::info!("开始抢票！");
+                ctx.progress(t("progress.started"));
+                retry.start();
+                grab_direct(ctx, &grab_ticket_req, cpdd, &mut retry).await;
+            }
+        }
+        1 => {
+            log::debug!("直接抢票模式");
+            retry.start();
+            grab_direct(ctx, &grab_ticket_req, cpdd, &mut retry).await;
+        }
+        2 => {
+            log::debug!("捡漏模式");
+            retry.start();
+            grab_watch(ctx, &grab_ticket_req, &mut retry).await;
+            log::info!("捡漏模式任务已退出");
+        }
+        _ => {
+            log::error!("未知模式");
+        }
+    }
+
+    if ctx.is_cancelled() {
+        log::info!("抢票任务{}已取消", ctx.id());
+        finish(
+            ctx,
+            &retry,
+            GrabTicketResult::failed(
+                ctx.id(),
+                grab_ticket_req.uid,
+                t("task.cancelled").to_string(),
+            ),
+        );
+    }
+}
+
+// 定时模式等待开售，返回 false 表示获取倒计时失败或任务已取消
+async fn wait_for_sale(
+    ctx: &TaskContext<GrabTicketResult>,
+    grab_ticket_req: &GrabTicketRequest,
+) -> bool {
+    let mut countdown = match get_countdown(
+        grab_ticket_req.cookie_manager.clone(),
+        grab_ticket_req.biliticket.project_info.clone(),
+    )
+    .await
+    {
+        Ok(countdown) => countdown,
+        Err(e) => {
+            log::error!("获取倒计时失败: {}", e);
+            ctx.finish(GrabTicketResult::failed(
+                ctx.id(),
+                grab_ticket_req.uid,
+                tr("grab.countdown_failed", &[&e]),
+            ));
+            return false;
+        }
+    };
+
+    if countdown > 0.0 {
+        log::info!("距离抢票时间还有{}秒", countdown);
+        ctx.progress(tr("progress.countdown", &[&countdown]));
+        loop {
+            if countdown <= 20.0 {
+                break;
+            }
+            countdown = countdown - 15.0;
+            tokio::select! {
+                _ = tokio::time::sleep(tokio::time::Duration::from_secs(15)) => {}
+                _ = ctx.cancelled() => return false,
+            }
+            log::info!("距离抢票时间还有{}秒", countdown);
+            ctx.progress(tr("progress.countdown", &[&countdown]));
+        }
+        loop {
+            if countdown <= 1.3 {
+                //按道理来说countdown是1秒，为了保险多设置几秒
+                tokio::time::sleep(tokio::time::Duration::from_secs_f32(0.8)).await;
+                break;
+            }
+            log::info!("距离抢票时间还有{}秒", countdown);
+            countdown = countdown - 1.0;
+            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+            if ctx.is_cancelled() {
+                return false;
+            }
+        }
+    }
+    !ctx.is_cancelled()
+}
+
+// 定时模式和直接模式：抢指定场次的指定票种
+async fn grab_direct(
+    ctx: &TaskContext<GrabTicketResult>,
+    grab_ticket_req: &GrabTicketRequest,
+    cpdd: Arc<Mutex<CTokenGenerator>>,
+    retry: &mut RetryState,
+) {
+    let cookie_manager = grab_ticket_req.cookie_manager.clone();
+    let buyer_info = grab_ticket_req.resolved_buyers();
+    let uid = grab_ticket_req.uid;
+    let policy = retry.policy.clone();
+    let mut token_failures = 0;
+
+    //抢票主循环
+    while !ctx.is_cancelled() {
+        if retry.expired() {
+            let _ = grab_failed(ctx, grab_ticket_req, retry, t("grab.deadline_exceeded")).await;
+            break;
+        }
+        retry.attempts.token += 1;
+        let token_result = get_ticket_token(
+            cookie_manager.clone(),
+            cpdd.clone(),
+            &grab_ticket_req.project_id,
+            &grab_ticket_req.screen_id,
+            &grab_ticket_req.ticket_id,
+            grab_ticket_req.count,
+            grab_ticket_req.is_hot,
+        )
+        .await;
+        match token_result {
+            Ok((token, ptoken)) => {
+                //获取token成功！
+                log::info!("获取抢票token成功！:{} ptoken:{}", token, ptoken);
+                ctx.progress(t("progress.token_ok"));
+                let mut confirm_failures = 0;
+                let mut seats = None; // 同一token只选一次座
+
+                //尝试下单
+                while !ctx.is_cancelled() {
+                    retry.attempts.confirm += 1;
+                    let (success, retry_limit) = handle_grab_ticket(
+                        ctx,
+                        cookie_manager.clone(),
+                        cpdd.clone(),
+                        &grab_ticket_req.project_id,
+                        &token,
+                        &ptoken,
+                        grab_ticket_req.is_hot,
+                        grab_ticket_req,
+                        &buyer_info,
+                        &mut seats,
+                        retry,
+                    )
+                    .await;
+                    // 定时模式遇到token失效时继续用当前token重试，直接模式直接结束
+                    if success && (!retry_limit || grab_ticket_req.grab_mode == 1) {
+                        log::info!("抢票流程结束");
+                        break; //成功或致命错误，跳出循环
+                    }
+
+                    confirm_failures += 1;
+                    if confirm_failures >= policy.confirm.max_attempts {
+                        log::error!("确认订单失败，已达最大重试次数");
+                        let _ = grab_failed(
+                            ctx,
+                            grab_ticket_req,
+                            retry,
+                            t("grab.confirm_retry_exhausted"),
+                        )
+                        .await;
+                        break;
+                    }
+                    if retry.expired() {
+                        let _ =
+                            grab_failed(ctx, grab_ticket_req, retry, t("grab.deadline_exceeded"))
+                                .await;
+                        break;
+                    }
+                    tokio::time::sleep(policy.delay(policy.confirm, confirm_failures)).await;
+                }
+
+                break; // 跳出token获取循环
+            }
+            Err(risk_param) => {
+                diagnostics::record_errno("order/prepare", risk_param.code as i64);
+                //获取token失败！分析原因
+                if risk_param.code == -101 {
+                    session_expired(ctx, grab_ticket_req, retry).await;
+                    break;
+                }
+                if risk_param.code == -401 || risk_param.code == 401 {
+                    //需要处理验证码
+                    log::warn!("需要验证码，开始处理验证码...");
+                    ctx.progress(t("progress.captcha"));
+                    match handle_risk_verification(
+                        cookie_manager.clone(),
+                        risk_param,
+                        &grab_ticket_req.biliticket.config,
+                        &grab_ticket_req.biliticket.account.csrf,
+                        grab_ticket_req.local_captcha.clone(),
+                    )
+                    .await
+                    {
+                        Ok(()) => {
+                            //验证码处理成功，继续抢票
+                            log::info!("验证码处理成功！");
+                        }
+                        Err(e) => {
+                            //验证码失败
+                            log::error!("验证码处理失败: {}", e);
+                            token_failures += 1;
+                            if token_failures >= policy.token.max_attempts {
+                                finish(
+                                    ctx,
+                                    retry,
+                                    GrabTicketResult::failed(
+                                        ctx.id(),
+                                        uid,
+                                        tr("grab.captcha_exhausted", &[&e]),
+                                    ),
+                                );
+                                break;
+                            }
+                        }
+                    }
+                } else {
+                    log_token_error(risk_param.code);
+                    token_failures += 1;
+                    if token_failures >= policy.token.max_attempts {
+                        finish(
+                            ctx,
+                            retry,
+                            GrabTicketResult::failed(
+                                ctx.id(),
+                                uid,
+                                tr(
+                                    "grab.token_exhausted",
+                                    &[&risk_param.code, &risk_param.message],
+                                ),
+                            ),
+                        );
+                        break;
+                    }
+                }
+                tokio::time::sleep(policy.delay(policy.token, token_failures)).await;
+            }
+        }
+    }
+}
+
+// 人为导致无法重试的错误
+fn log_token_error(code: i32) {
+    match code {
+        100080 | 100082 => {
+            log::error!("抢票失败，场次/项目/日期选择有误，请重新提交任务");
+        }
+        100039 => {
+            log::error!("抢票失败，该场次已停售，请重新提交任务");
+        }
+        _ => {
+            log::error!("抢票失败，未知错误，请重新提交任务");
+        }
+    }
+}
+
+// 捡漏模式：轮询项目，按偏好顺序尝试当前可售的票种
+async fn grab_watch(
+    ctx: &TaskContext<GrabTicketResult>,
+    grab_ticket_req: &GrabTicketRequest,
+    retry: &mut RetryState,
+) {
+    let cookie_manager = grab_ticket_req.cookie_manager.clone();
+    let project_id = &grab_ticket_req.project_id;
+    let buyer_info = grab_ticket_req.resolved_buyers();
+    let count = grab_ticket_req.count;
+    let mut local_grab_request = grab_ticket_req.clone();
+    let mut rng = StdRng::from_entropy();
+    let policy = retry.policy.clone();
+    let mut token_failures = 0;
+    let mut last_available: Option<Vec<(usize, usize)>> = None; //首轮只记录，不推送
+
+    // 外层循环，一旦抢票成功或遇到致命错误就退出
+    'main_loop: while !ctx.is_cancelled() {
+        if retry.expired() {
+            let _ = grab_failed(ctx, grab_ticket_req, retry, t("grab.deadline_exceeded")).await;
+            break 'main_loop;
+        }
+        log::debug!(
+            "project_id: {}, screen_id: {}, ticket_id: {}",
+            project_id,
+            grab_ticket_req.screen_id,
+            grab_ticket_req.ticket_id
+        );
+
+        // 获取项目数据
+        let project_data = match get_project(cookie_manager.clone(), project_id).await {
+            Ok(data) => data,
+            Err(e) => {
+                log::error!("获取项目数据失败，原因：{}", e);
+                if logged_out(cookie_manager.clone()).await {
+                    session_expired(ctx, grab_ticket_req, retry).await;
+                    break 'main_loop;
+                }
+                tokio::time::sleep(policy.project_retry_interval()).await;
+                continue;
+            }
+        };
+        let is_hot = project_data.data.hot_project;
+
+        if ![1, 2].contains(&project_data.data.id_bind) {
+            log::error!("暂不支持抢非实名票捡漏模式");
+            finish(
+                ctx,
+                retry,
+                GrabTicketResult::failed(
+                    ctx.id(),
+                    grab_ticket_req.uid,
+                    t("grab.watch_no_bind_unsupported").to_string(),
+                ),
+            );
+            break 'main_loop;
+        }
+        local_grab_request.biliticket.id_bind = project_data.data.id_bind;
+        local_grab_request.biliticket.project_info = Some(project_data.data.clone());
+        let candidates =
+            rank_candidates(&grab_ticket_req.preferences, &project_data.data.screen_list);
+        if candidates.is_empty() {
+            log::info!("当前没有符合偏好的可售票种");
+        }
+        // 出现新的可售票种时推送回流通知
+        let available: Vec<(usize, usize)> = candidates.iter().map(|(s, t)| (s.id, t.id)).collect();
+        let appeared: Vec<String> = candidates
+            .iter()
+            .filter(|(s, t)| {
+                last_available
+                    .as_ref()
+                    .is_some_and(|last| !last.contains(&(s.id, t.id)))
+            })
+            .map(|(s, t)| format!("{} {}", s.name, t.desc))
+            .collect();
+        if !appeared.is_empty() {
+            ctx.progress(tr("progress.tickets_appeared", &[&appeared.join("、")]));
+            grab_ticket_req
+                .biliticket
+                .push_self
+                .notify_async(
+                    NotifyEvent::WatcherChange,
+                    t("push.watcher_change.title"),
+                    &tr(
+                        "push.watcher_change.body",
+                        &[&project_data.data.name, &appeared.join("\n")],
+                    ),
+                    &None,
+                )
+                .await;
+        }
+        last_available = Some(available);
+
+        // 按偏好顺序遍历候选票种
+        'ticket_loop: for (screen_data, ticket_data) in candidates {
+            if ctx.is_cancelled() {
+                break 'main_loop;
+            }
+            local_grab_request.screen_id = screen_data.id.to_string();
+            local_grab_request.biliticket.screen_id = screen_data.id.to_string();
+            if let Some(skip_words) = &grab_ticket_req.skip_words {
+                // 检查标题是否包含需要过滤的关键词
+                let title = ticket_data.screen_name.to_lowercase();
+                if skip_words
+                    .iter()
+                    .any(|word| title.contains(&word.to_lowercase()))
+                {
+                    log::info!("跳过包含过滤关键词的场次: {}", ticket_data.screen_name);
+                    continue; // 跳过这个场次
+                }
+                let ticket_title = ticket_data.desc.to_lowercase();
+                if skip_words
+                    .iter()
+                    .any(|word| ticket_title.contains(&word.to_lowercase()))
+                {
+                    log::info!("跳过包含过滤关键词的票种: {}", ticket_data.screen_name);
+                    continue; // 跳过这个票种
+                }
+            }
+            if let Some(max_total_price) = local_grab_request.max_total_price {
+                let total = Money::from_fen(ticket_data.price as i64) * count as i64
+                    + Money::from_fen(project_data.data.express_fee as i64);
+                if total > max_total_price {
+                    log::info!(
+                        "跳过超出预算的票种: {} {}，总价{}超出上限{}",
+                        ticket_data.screen_name,
+                        ticket_data.desc,
+                        total,
+                        max_total_price
+                    );
+                    continue;
+                }
+            }
+
+            log::info!(
+                "当前{} {}票种可售，开始抢票！",
+                ticket_data.screen_name,
+                ticket_data.desc
+            );
+            ctx.progress(tr(
+                "progress.ticket_available",
+                &[&ticket_data.screen_name, &ticket_data.desc],
+            ));
+            local_grab_request.ticket_id = ticket_data.id.to_string();
+            local_grab_request.biliticket.select_ticket_id = Some(ticket_data.id.to_string());
+            let cpdd = Arc::new(Mutex::new(CTokenGenerator::new(
+                project_data.data.sale_begin as i64,
+                0,
+                rng.gen_range(2000..10000),
+            )));
+            // 获取token
+            retry.attempts.token += 1;
+            let token_result = get_ticket_token(
+                cookie_manager.clone(),
+                cpdd.clone(),
+                project_id,
+                &local_grab_request.screen_id,
+                &local_grab_request.ticket_id,
+                count,
+                is_hot,
+            )
+            .await;
+            match token_result {
+                Ok((token, ptoken)) => {
+                    //获取token成功！
+                    log::info!("获取抢票token成功！:{} ptoken:{}", token, ptoken);
+                    let mut confirm_failures = 0;
+                    let mut seats = None; // 同一token只选一次座
+
+                    loop {
+                        if ctx.is_cancelled() {
+                            break 'main_loop;
+                        }
+                        retry.attempts.confirm += 1;
+                        let (success, retry_limit) = handle_grab_ticket(
+                            ctx,
+                            cookie_manager.clone(),
+                            cpdd.clone(),
+                            project_id,
+                            &token,
+                            &ptoken,
+                            is_hot,
+                            &local_grab_request,
+                            &buyer_info,
+                            &mut seats,
+                            retry,
+                        )
+                        .await;
+                        if success {
+                            log::info!("抢票流程结束，退出捡漏模式");
+
+                            break 'main_loop;
+                        }
+                        if retry_limit {
+                            log::info!("该票种已达到最大重试次数，恢复捡漏模式，尝试其他票种");
+                            break 'ticket_loop;
+                        }
+
+                        confirm_failures += 1;
+                        if confirm_failures >= policy.confirm.max_attempts {
+                            log::error!("确认订单失败，已达最大重试次数，尝试其他票种");
+                            break; // 只跳出当前票种的重试循环
+                        }
+
+                        tokio::time::sleep(policy.delay(policy.confirm, confirm_failures)).await;
+                    }
+                }
+                Err(risk_param) => {
+                    diagnostics::record_errno("order/prepare", risk_param.code as i64);
+                    if risk_param.code == -101 {
+                        session_expired(ctx, grab_ticket_req, retry).await;
+                        break 'main_loop;
+                    }
+                    // 捡漏模式不因获取token失败结束任务，只上报进度后换下一轮
+                    if risk_param.code == -401 || risk_param.code == 401 {
+                        //需要处理验证码
+                        log::warn!("需要验证码，开始处理验证码...");
+                        match handle_risk_verification(
+                            cookie_manager.clone(),
+                            risk_param,
+                            &grab_ticket_req.biliticket.config,
+                            &grab_ticket_req.biliticket.account.csrf,
+                            grab_ticket_req.local_captcha.clone(),
+                        )
+                        .await
+                        {
+                            Ok(()) => {
+                                //验证码处理成功，继续抢票
+                                log::info!("验证码处理成功！");
+                            }
+                            Err(e) => {
+                                //验证码失败
+                                log::error!("验证码处理失败: {}", e);
+                                token_failures += 1;
+                                if token_failures >= policy.token.max_attempts {
+                                    ctx.progress(tr("grab.captcha_exhausted", &[&e]));
+                                    break;
+                                }
+                            }
+                        }
+                    } else {
+                        log_token_error(risk_param.code);
+                        token_failures += 1;
+                        if token_failures >= policy.token.max_attempts {
+                            ctx.progress(tr(
+                                "grab.token_exhausted",
+                                &[&risk_param.code, &risk_param.message],
+                            ));
+                            break;
+                        }
+                    }
+                    tokio::time::sleep(policy.delay(policy.token, token_failures)).await;
+                }
+            }
+        }
+
+        // 本轮所有场次和票种都检查完毕，休息后继续下一轮
+        let interval = policy.poll_interval();
+        log::info!(
+            "所有场次和票种检查完毕，等待{:.1}秒后重新检查",
+            interval.as_secs_f32()
+        );
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = ctx.cancelled() => break 'main_loop,
+        }
+    }
+}
+
+async fn handle_grab_ticket(
+    ctx: &TaskContext<GrabTicketResult>,
+    cookie_manager: Arc<CookieManager>,
+    cpdd: Arc<Mutex<CTokenGenerator>>,
+    project_id: &str,
+    token: &str,
+    ptoken: &str,
+    is_hot: bool,
+    grab_ticket_req: &GrabTicketRequest,
+    buyer_info: &Vec<BuyerInfo>,
+    seats: &mut Option<Vec<Seat>>,
+    retry: &mut RetryState,
+) -> (bool, bool) {
+    // 选座失败时下次确认订单前重新选座
+    if seats.is_none() {
+        match resolve_seats(cookie_manager.clone(), grab_ticket_req).await {
+            Ok(picked) => *seats = Some(picked),
+            Err(e) => {
+                log::error!("选座失败，原因：{}  正在重试...", e);
+                ctx.progress(tr("progress.seat_failed", &[&e]));
+                return (false, false);
+            }
+        }
+    }
+    let seats = seats.as_deref().unwrap_or_default();
+
+    // 确认订单
+    match confirm_ticket_order(cookie_manager.clone(), project_id, token, seats).await {
+        Ok(confirm_result) => {
+            log::info!("确认订单成功！准备下单");
+            ctx.progress(t("progress.confirm_ok"));
+
+            // 预算检查，超出上限直接放弃下单
+            if let Some(max_total_price) = grab_ticket_req.max_total_price {
+                let over_budget = match grab_ticket_req.express_fee() {
+                    Some(express_fee) => {
+                        let total = confirm_result.total_with_express(express_fee);
+                        (total > max_total_price).then(|| {
+                            tr(
+                                "grab.over_budget",
+                                &[&total, &express_fee, &max_total_price],
+                            )
+                        })
+                    }
+                    // 没有项目信息无法计算快递费，不冒险下单
+                    None => Some(t("grab.budget_no_project").to_string()),
+                };
+                if let Some(message) = over_budget {
+                    log::error!("{}", message);
+                    if grab_ticket_req.grab_mode == 2 {
+                        return (false, true); // 捡漏模式跳过该票种
+                    }
+                    let _ = grab_failed(ctx, grab_ticket_req, retry, &message).await;
+                    return (true, false);
+                }
+            }
+
+            if let Some((success, retry_limit)) = try_create_order(
+                ctx,
+                cookie_manager.clone(),
+                cpdd.clone(),
+                project_id,
+                token,
+                ptoken,
+                &confirm_result,
+                is_hot.clone(),
+                grab_ticket_req,
+                buyer_info,
+                seats,
+                retry,
+            )
+            .await
+            {
+                return (success, retry_limit);
+            }
+
+            (true, false) // 订单流程已完成
+        }
+        Err(e) => {
+            if logged_out(cookie_manager.clone()).await {
+                session_expired(ctx, grab_ticket_req, retry).await;
+                return (true, false);
+            }
+            log::error!("确认订单失败，原因：{}  正在重试...", e);
+            (false, false) // 需要继续重试
+        }
+    }
+}
+
+// 选座项目获取座位图并按购票人数选座，不选座的项目返回空列表
+async fn resolve_seats(
+    cookie_manager: Arc<CookieManager>,
+    grab_ticket_req: &GrabTicketRequest,
+) -> Result<Vec<Seat>, String> {
+    let pick_seat = grab_ticket_req
+        .biliticket
+        .project_info
+        .as_ref()
+        .is_some_and(|info| needs_seat_selection(info, &grab_ticket_req.screen_id));
+    if !pick_seat {
+        return Ok(Vec::new());
+    }
+    let seat_map = get_seat_map(
+        cookie_manager,
+        &grab_ticket_req.project_id,
+        &grab_ticket_req.screen_id,
+        &grab_ticket_req.ticket_id,
+    )
+    .await?;
+    let seats = pick_seats(
+        &seat_map,
+        &grab_ticket_req.seat_selection,
+        grab_ticket_req.seat_count(),
+    )?;
+    log::info!("已选座位：{}", seat_names(&seats));
+    Ok(seats)
+}
+
+// 处理创建订单逻辑
+async fn try_create_order(
+    ctx: &TaskContext<GrabTicketResult>,
+    cookie_manager: Arc<CookieManager>,
+    cpdd: Arc<Mutex<CTokenGenerator>>,
+    project_id: &str,
+    token: &str,
+    ptoken: &str,
+    confirm_result: &ConfirmTicketResult,
+    is_hot: bool,
+    grab_ticket_req: &GrabTicketRequest,
+    buyer_info: &Vec<BuyerInfo>,
+    seats: &[Seat],
+    retry: &mut RetryState,
+) -> Option<(
+    bool,
+    bool, // 第二个参数标记是因为达到重试上限
+)> {
+    let policy = retry.policy.clone();
+    let mut order_failures = 0;
+    let mut need_retry = false;
+
+    // 下单循环
+    loop {
+        if ctx.is_cancelled() {
+            finish(
+                ctx,
+                retry,
+                GrabTicketResult::failed(
+                    ctx.id(),
+                    grab_ticket_req.uid,
+                    t("task.cancelled").to_string(),
+                ),
+            );
+            return Some((true, false));
+        }
+        if order_failures >= ORDER_RETRY_FLAG_AFTER {
+            need_retry = true;
+        }
+        retry.attempts.order += 1;
+
+        match create_order(
+            cookie_manager.clone(),
+            cpdd.clone(),
+            project_id,
+            token,
+            ptoken,
+            confirm_result,
+            is_hot.clone(),
+            &grab_ticket_req.biliticket,
+            buyer_info,
+            seats,
+            true,
+            need_retry,
+            false,
+            None,
+        )
+        .await
+        {
+            Ok(order_result) => {
+                diagnostics::record_errno("order/create", 0);
+                log::info!("下单成功！订单信息{:?}", order_result);
+                let empty_json = json!({});
+                let order_data = order_result.get("data").unwrap_or(&empty_json);
+
+                let zero_json = json!(0);
+                let order_id = order_data
+                    .get("orderId")
+                    .unwrap_or(&zero_json)
+                    .as_i64()
+                    .unwrap_or(0);
+
+                let empty_string_json = json!("");
+                let pay_token = order_data
+                    .get("token")
+                    .unwrap_or(&empty_string_json)
+                    .as_str()
+                    .unwrap_or("");
+
+                log::info!("下单成功！正在检测是否假票！");
+                // 检测假票
+                let check_result = match check_fake_ticket(
+                    cookie_manager.clone(),
+                    project_id,
+                    pay_token,
+                    order_id,
+                )
+                .await
+                {
+                    Ok(result) => result,
+                    Err(e) => {
+                        log::error!("检测假票失败，原因：{}，请前往订单列表查看是否下单成功", e);
+                        continue; // 继续重试
+                    }
+                };
+                let errno = check_result
+                    .get("errno")
+                    .unwrap_or(&zero_json)
+                    .as_i64()
+                    .unwrap_or(0);
+                if errno != 0 {
+                    log::error!("假票，继续抢票");
+                    continue;
+                }
+                let analyze_result =
+                    match serde_json::from_value::<CheckFakeResult>(check_result.clone()) {
+                        Ok(result) => result,
+                        Err(e) => {
+                            log::error!("解析假票结果失败，原因：{}", e);
+                            continue; // 继续重试
+                        }
+                    };
+
+                let pay_result = analyze_result.data.pay_param;
+                // 通知成功
+                ctx.finish(GrabTicketResult {
+                    task_id: ctx.id().to_string(),
+                    uid: grab_ticket_req.uid,
+                    success: true,
+                    message: t("grab.success").to_string(),
+                    order_id: Some(order_id.clone().to_string()),
+                    pay_token: Some(pay_token.to_string()),
+                    confirm_result: Some(confirm_result.clone()),
+                    pay_result: Some(pay_result.clone()),
+                    attempts: retry.attempts,
+                });
+
+                //修复由于挂在后台egui不运行导致任务管理器不加载导致不推送
+                // APP深链供手机推送使用，桌面通知会换成其中的网页订单详情
+                let jump_url = Some(format!(
+                    "bilibili://mall/web?url=https://mall.bilibili.com/neul-next/ticket/orderDetail.html?order_id={}",
+                    order_id.to_string()
+                ));
+                let pay_url = pay_result.code_url.clone();
+                let title = tr("push.grab_success.title", &[&confirm_result.project_name]);
+                let message = tr(
+                    "push.grab_success.body",
+                    &[
+                        &confirm_result.project_name,
+                        &confirm_result.screen_name,
+                        &confirm_result.ticket_info.name,
+                        &pay_url,
+                        &confirm_result
+                            .total_with_express(
+                                grab_ticket_req.express_fee().unwrap_or(Money::ZERO),
+                            )
+                            .yuan_string(),
+                    ],
+                );
+
+                hooks::fire(
+                    &grab_ticket_req.hooks,
+                    NotifyEvent::GrabSuccess,
+                    HookPayload {
+                        project_name: confirm_result.project_name.clone(),
+                        screen_name: confirm_result.screen_name.clone(),
+                        ticket_name: confirm_result.ticket_info.name.clone(),
+                        order_id: Some(order_id.to_string()),
+                        pay_url: Some(pay_url.clone()),
+                        ..hook_payload(ctx, grab_ticket_req, &message)
+                    },
+                );
+                grab_ticket_req
+                    .biliticket
+                    .push_self
+                    .notify_async(NotifyEvent::GrabSuccess, &title, &message, &jump_url)
+                    .await;
+                spawn_unpaid_reminder(
+                    cookie_manager.clone(),
+                    grab_ticket_req.biliticket.push_self.clone(),
+                    order_id.to_string(),
+                    confirm_result.project_name.clone(),
+                    jump_url,
+                );
+                return Some((true, false)); // 成功，不需要继续重试
+                //有个问题：取的是缓存里的pushconfig，动态修改的新的推不了
+            }
+
+            Err(e) => {
+                diagnostics::record_errno("order/create", e as i64);
+                // 处理错误情况
+                match e {
+                    //需要继续重试的临时错误
+                    100001 | 429 | 900001 => log::info!("b站限速，正常现象"),
+                    100009 => {
+                        log::info!("当前票种库存不足");
+                        //再次降速，不给b站服务器带来压力
+                        tokio::time::sleep(tokio::time::Duration::from_secs_f32(0.6)).await;
+                    }
+                    211 => {
+                        log::info!("很遗憾，差一点点抢到票，继续加油吧！");
+                    }
+
+                    //需要暂停的情况
+                    3 => {
+                        log::info!("抢票速度过快，即将被硬控5秒");
+                        log::info!("暂停4.8秒");
+                        tokio::time::sleep(tokio::time::Duration::from_secs_f32(4.8)).await;
+                    }
+
+                    //需要重新获取token的情况
+                    100041 | 100050 | 900002 => {
+                        log::info!("token失效，即将重新获取token");
+                        return Some((true, true)); // 需要重新获取token
+                    }
+
+                    //需要终止抢票的致命错误
+                    -101 => {
+                        session_expired(ctx, grab_ticket_req, retry).await;
+                        return Some((true, false));
+                    }
+                    100017 | 100016 => {
+                        log::info!("当前项目/类型/场次已停售");
+                        return grab_failed(ctx, grab_ticket_req, retry, t("grab.fatal.sold_out"))
+                            .await;
+                    }
+                    1 => {
+                        log::error!(
+                            "超人 请慢一点，这是仅限1人抢票的项目，或抢票格式有误，请重新提交任务"
+                        );
+                        return grab_failed(
+                            ctx,
+                            grab_ticket_req,
+                            retry,
+                            t("grab.fatal.single_buyer_project"),
+                        )
+                        .await;
+                    }
+                    83000004 => {
+                        log::error!("没有配置购票人信息！请重新配置");
+                        return grab_failed(ctx, grab_ticket_req, retry, t("grab.fatal.no_buyer"))
+                            .await;
+                    }
+                    100079 | 100003 => {
+                        log::error!("购票人存在待付款订单，请前往支付或取消后重新下单");
+                        return grab_failed(
+                            ctx,
+                            grab_ticket_req,
+                            retry,
+                            t("grab.fatal.unpaid_order"),
+                        )
+                        .await;
+                    }
+                    100039 => {
+                        log::error!("活动收摊啦,下次要快点哦");
+                        return grab_failed(
+                            ctx,
+                            grab_ticket_req,
+                            retry,
+                            t("grab.fatal.event_ended"),
+                        )
+                        .await;
+                    }
+
+                    209001 => {
+                        log::error!("当前项目只能选择一个购票人！不支持多选，请重新提交任务");
+                        return grab_failed(
+                            ctx,
+                            grab_ticket_req,
+                            retry,
+                            t("grab.fatal.one_buyer_only"),
+                        )
+                        .await;
+                    }
+                    737 => {
+                        log::error!(
+                            "B站传了一个NUll回来，请看一下上一行的message提示信息，自行决定是否继续，如果取消请关闭重新打开该应用"
+                        );
+                    }
+
+                    999 => {
+                        log::error!("程序内部错误！传参错误")
+                    }
+                    919 => {
+                        log::error!(
+                            "程序内部错误！该项目区分绑定非绑定项目错误，传入意外值，请尝试重新下单以及提出issue"
+                        );
+                        return grab_failed(ctx, grab_ticket_req, retry, t("grab.fatal.id_bind"))
+                            .await;
+                    }
+
+                    //未知错误
+                    _ => log::error!("下单失败，未知错误码：{} 可以提出issue修复该问题", e),
+                }
+            }
+        }
+
+        // 增加重试计数并等待
+        order_failures += 1;
+        if policy
+            .order_limit(grab_ticket_req.grab_mode)
+            .is_some_and(|limit| order_failures >= limit)
+        {
+            if grab_ticket_req.grab_mode == 2 {
+                log::error!(
+                    "捡漏模式下单失败，已达最大重试次数，放弃该票种抢票，准备检测其他票种继续捡漏"
+                );
+                return Some((false, true)); // 捡漏模式下单失败，放弃该票种抢票
+            }
+            log::error!("下单失败，已达最大重试次数");
+            return grab_failed(ctx, grab_ticket_req, retry, t("grab.order_retry_exhausted")).await;
+        }
+        if retry.expired() {
+            return grab_failed(ctx, grab_ticket_req, retry, t("grab.deadline_exceeded")).await;
+        }
+        //降低速度，不带来b站服务器压力
+        tokio::time::sleep(policy.delay(policy.order, order_failures)).await;
+    }
+}
+
+// 致命错误终止抢票：上报失败结果并推送失败通知
+async fn grab_failed(
+    ctx: &TaskContext<GrabTicketResult>,
+    grab_ticket_req: &GrabTicketRequest,
+    retry: &RetryState,
+    reason: &str,
+) -> Option<(bool, bool)> {
+    finish(
+        ctx,
+        retry,
+        GrabTicketResult::failed(ctx.id(), grab_ticket_req.uid, reason.to_string()),
+    );
+    hooks::fire(
+        &grab_ticket_req.hooks,
+        NotifyEvent::GrabFailed,
+        hook_payload(ctx, grab_ticket_req, reason),
+    );
+    grab_ticket_req
+        .biliticket
+        .push_self
+        .notify_async(
+            NotifyEvent::GrabFailed,
+            t("push.grab_failed.title"),
+            &tr("push.grab_failed.body", &[&reason]),
+            &None,
+        )
+        .await;
+    Some((true, false))
+}
+
+// 登录失效终止抢票：触发钩子、推送通知并上报失败结果
+async fn session_expired(
+    ctx: &TaskContext<GrabTicketResult>,
+    grab_ticket_req: &GrabTicketRequest,
+    retry: &RetryState,
+) {
+    log::error!("账号登录已失效，请重新登录");
+    let message = tr(
+        "push.session_expired.body",
+        &[&grab_ticket_req.biliticket.account.name],
+    );
+    hooks::fire(
+        &grab_ticket_req.hooks,
+        NotifyEvent::SessionExpired,
+        hook_payload(ctx, grab_ticket_req, &message),
+    );
+    grab_ticket_req
+        .biliticket
+        .push_self
+        .notify_async(
+            NotifyEvent::SessionExpired,
+            t("push.session_expired.title"),
+            &message,
+            &None,
+        )
+        .await;
+    finish(
+        ctx,
+        retry,
+        GrabTicketResult::failed(ctx.id(), grab_ticket_req.uid, message),
+    );
+}
+
+// 接口请求失败时通过 nav 接口确认是否已退出登录
+async fn logged_out(cookie_manager: Arc<CookieManager>) -> bool {
+    matches!(check_login(cookie_manager).await, Ok(None))
+}
+
+// 钩子事件数据，场次和票种名称从项目信息中查找
+fn hook_payload(
+    ctx: &TaskContext<GrabTicketResult>,
+    grab_ticket_req: &GrabTicketRequest,
+    message: &str,
+) -> HookPayload {
+    let project = grab_ticket_req.biliticket.project_info.as_ref();
+    let screen = project.and_then(|info| {
+        info.screen_list
+            .iter()
+            .find(|screen| screen.id.to_string() == grab_ticket_req.screen_id)
+    });
+    let ticket = screen.and_then(|screen| {
+        screen
+            .ticket_list
+            .iter()
+            .find(|ticket| ticket.id.to_string() == grab_ticket_req.ticket_id)
+    });
+    HookPayload {
+        task_id: ctx.id().to_string(),
+        uid: grab_ticket_req.uid,
+        project_id: grab_ticket_req.project_id.clone(),
+        project_name: project.map(|info| info.name.clone()).unwrap_or_default(),
+        screen_id: grab_ticket_req.screen_id.clone(),
+        screen_name: screen.map(|s| s.name.clone()).unwrap_or_default(),
+        ticket_id: grab_ticket_req.ticket_id.clone(),
+        ticket_name: ticket.map(|t| t.desc.clone()).unwrap_or_default(),
+        message: message.to_string(),
+        ..Default::default()
+    }
+}
+
+// 下单成功后一段时间仍未支付时提醒
+const UNPAID_REMIND_DELAY_SECS: u64 = 5 * 60;
+
+fn spawn_unpaid_reminder(
+    cookie_manager: Arc<CookieManager>,
+    push_config: PushConfig,
+    order_id: String,
+    project_name: String,
+    jump_url: Option<String>,
+) {
+    tokio::spawn(async move {
+        tokio::time::sleep(tokio::time::Duration::from_secs(UNPAID_REMIND_DELAY_SECS)).await;
+        let orders = match get_orderlist(cookie_manager).await {
+            Ok(orders) => orders,
+            Err(e) => {
+                log::error!("检查订单{}支付状态失败：{}", order_id, e);
+                return;
+            }
+        };
+        let unpaid = orders
+            .data
+            .list
+            .iter()
+            .any(|order| order.order_id == order_id && order.is_unpaid());
+        if unpaid {
+            log::info!("订单{}仍未支付，发送提醒", order_id);
+            push_config
+                .notify_async(
+                    NotifyEvent::UnpaidOrder,
+                    t("push.unpaid_order.title"),
+                    &tr("push.unpaid_order.body", &[&project_name, &order_id]),
+                    &jump_url,
+                )
+                .await;
+        }
+    });
+}
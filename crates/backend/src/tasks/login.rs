@@ -0,0 +1,91 @@
+use common::login::QrCodeLoginStatus;
+use common::task_handle::TaskContext;
+use common::taskmanager::{
+    LoginSmsRequest, LoginSmsRequestResult, QrCodeLoginRequest, SubmitLoginSmsRequest,
+    SubmitSmsLoginResult, TaskQrCodeLoginResult,
+};
+
+use crate::api::poll_qrcode_login;
+
+pub async fn qrcode_login(ctx: TaskContext<TaskQrCodeLoginResult>, request: QrCodeLoginRequest) {
+    ctx.start();
+    let status = poll_qrcode_login(&request.qrcode_key, request.user_agent.as_deref()).await;
+
+    let (cookie, error) = match &status {
+        QrCodeLoginStatus::Success(cookie) => (Some(cookie.clone()), None),
+        QrCodeLoginStatus::Failed(err) => (None, Some(err.clone())),
+        _ => (None, None),
+    };
+
+    ctx.finish(TaskQrCodeLoginResult {
+        task_id: ctx.id().to_string(),
+        status,
+        cookie,
+        error,
+    });
+}
+
+pub async fn send_sms(ctx: TaskContext<LoginSmsRequestResult>, request: LoginSmsRequest) {
+    ctx.start();
+    log::info!("开始发送短信验证码 ID: {}", ctx.id());
+    let response = common::login::send_loginsms(
+        &request.phone,
+        &request.client,
+        request.custom_config,
+        request.local_captcha,
+    )
+    .await;
+    let success = response.is_ok();
+    let message = match response {
+        Ok(msg) => msg,
+        Err(err) => {
+            log::error!("发送短信验证码失败: {}", err);
+            err.to_string()
+        }
+    };
+    log::info!(
+        "发送短信任务完成 ID: {}, 结果: {}",
+        ctx.id(),
+        if success { "成功" } else { "失败" }
+    );
+
+    ctx.finish(LoginSmsRequestResult {
+        task_id: ctx.id().to_string(),
+        phone: request.phone,
+        success,
+        message,
+    });
+}
+
+pub async fn sms_login(ctx: TaskContext<SubmitSmsLoginResult>, request: SubmitLoginSmsRequest) {
+    ctx.start();
+    log::info!("短信验证码登录进行中 ID: {}", ctx.id());
+    let response = common::login::sms_login(
+        &request.phone,
+        &request.code,
+        &request.captcha_key,
+        &request.client,
+    )
+    .await;
+    let success = response.is_ok();
+    let (message, cookie) = match response {
+        Ok(cookie) => (cookie.clone(), Some(cookie)),
+        Err(err) => {
+            log::error!("提交短信验证码失败: {}", err);
+            (err.to_string(), None)
+        }
+    };
+    log::info!(
+        "提交短信任务完成 ID: {}, 结果: {}",
+        ctx.id(),
+        if success { "成功" } else { "失败" }
+    );
+
+    ctx.finish(SubmitSmsLoginResult {
+        task_id: ctx.id().to_string(),
+        phone: request.phone,
+        success,
+        message,
+        cookie,
+    });
+}
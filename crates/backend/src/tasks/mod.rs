@@ -0,0 +1,7 @@
+// 各类任务的处理函数，由任务管理器在运行时中启动
+pub mod dry_run;
+pub mod grab;
+pub mod login;
+pub mod order;
+pub mod project;
+pub mod push;
@@ -0,0 +1,30 @@
+use common::i18n::tr;
+use common::task_handle::TaskContext;
+use common::taskmanager::{GetAllorderRequest, GetAllorderRequestResult};
+
+use crate::show_orderlist::get_orderlist;
+
+pub async fn get_orders(ctx: TaskContext<GetAllorderRequestResult>, request: GetAllorderRequest) {
+    ctx.start();
+    log::info!("正在获取全部订单 ID: {}", ctx.id());
+    let (success, message, order_info) = match get_orderlist(request.cookie_manager).await {
+        Ok(order_resp) => (
+            true,
+            tr("task.orders_ok", &[&order_resp.data.total]),
+            Some(order_resp),
+        ),
+        Err(err) => {
+            log::error!("获取全部订单失败: {}", err);
+            (false, err.to_string(), None)
+        }
+    };
+
+    ctx.finish(GetAllorderRequestResult {
+        task_id: ctx.id().to_string(),
+        account_id: request.account_id,
+        success,
+        message,
+        order_info,
+        timestamp: std::time::Instant::now(),
+    });
+}
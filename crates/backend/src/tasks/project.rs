@@ -0,0 +1,54 @@
+use common::i18n::{t, tr};
+use common::task_handle::TaskContext;
+use common::taskmanager::{
+    GetBuyerInfoRequest, GetBuyerInfoResult, GetTicketInfoRequest, GetTicketInfoResult,
+};
+
+use crate::api::{get_buyer_info as fetch_buyer_info, get_project};
+
+pub async fn get_ticket_info(ctx: TaskContext<GetTicketInfoResult>, request: GetTicketInfoRequest) {
+    ctx.start();
+    log::debug!("正在获取project{}", ctx.id());
+    let (success, message, ticket_info) =
+        match get_project(request.cookie_manager, &request.project_id).await {
+            Ok(info) => {
+                // 检查数据是否有效
+                if info.data.screen_list.is_empty() {
+                    log::warn!("项目信息获取成功但场次列表为空，可能是API格式变化");
+                }
+                (true, tr("task.project_ok", &[&info.errno]), Some(info))
+            }
+            Err(e) => {
+                log::error!("获取项目时失败，原因：{}", e);
+                (false, e.to_string(), None)
+            }
+        };
+
+    ctx.finish(GetTicketInfoResult {
+        task_id: ctx.id().to_string(),
+        uid: request.uid,
+        ticket_info,
+        success,
+        message,
+    });
+}
+
+pub async fn get_buyer_info(ctx: TaskContext<GetBuyerInfoResult>, request: GetBuyerInfoRequest) {
+    ctx.start();
+    log::debug!("正在获取购票人信息{}", ctx.id());
+    let (success, message, buyer_info) = match fetch_buyer_info(request.cookie_manager).await {
+        Ok(info) => (true, t("task.buyers_ok").to_string(), Some(info)),
+        Err(e) => {
+            log::error!("获取购票人信息失败，原因：{}", e);
+            (false, e.to_string(), None)
+        }
+    };
+
+    ctx.finish(GetBuyerInfoResult {
+        task_id: ctx.id().to_string(),
+        uid: request.uid,
+        buyer_info,
+        success,
+        message,
+    });
+}
@@ -0,0 +1,47 @@
+use common::push::summarize_results;
+use common::task_handle::TaskContext;
+use common::taskmanager::{PushRequest, PushRequestResult};
+
+pub async fn run(ctx: TaskContext<PushRequestResult>, request: PushRequest) {
+    ctx.start();
+    log::info!(
+        "开始处理推送任务 ID: {}, 类型: {:?}",
+        ctx.id(),
+        request.push_type
+    );
+
+    let PushRequest {
+        title,
+        message,
+        jump_url,
+        push_config,
+        push_type,
+        event,
+    } = request;
+    let channels = match event {
+        Some(event) => {
+            push_config
+                .notify_async(event, &title, &message, &jump_url)
+                .await
+        }
+        None => {
+            push_config
+                .push_channels(&push_type, &title, &message, &jump_url)
+                .await
+        }
+    };
+    let (success, result_message) = summarize_results(&channels);
+    log::info!(
+        "推送任务 ID: {} 完成, 结果: {}",
+        ctx.id(),
+        if success { "成功" } else { "失败" }
+    );
+
+    ctx.finish(PushRequestResult {
+        task_id: ctx.id().to_string(),
+        success,
+        message: result_message,
+        push_type,
+        channels,
+    });
+}
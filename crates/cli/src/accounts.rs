@@ -0,0 +1,117 @@
+use common::cookie_import::{export_accounts, import_accounts};
+use common::i18n::{t, tr};
+use common::utils::Config;
+
+use crate::user_agent;
+
+// cli accounts list | import <文件> | export <文件> [UID...]
+pub async fn run(args: &[String]) -> Result<(), String> {
+    match args.first().map(String::as_str) {
+        Some("list") | None => list(),
+        Some("import") => {
+            let path = args
+                .get(1)
+                .ok_or_else(|| t("cli.missing_file").to_string())?;
+            import(path).await
+        }
+        Some("export") => {
+            let path = args
+                .get(1)
+                .ok_or_else(|| t("cli.missing_file").to_string())?;
+            let uids = args[2..]
+                .iter()
+                .map(|uid| {
+                    uid.parse::<i64>()
+                        .map_err(|_| tr("cli.invalid_uid", &[uid]))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            export(path, &uids)
+        }
+        Some(other) => Err(tr("cli.unknown_subcommand", &[&other])),
+    }
+}
+
+fn list() -> Result<(), String> {
+    let accounts = Config::load_all_accounts();
+    if accounts.is_empty() {
+        println!("{}", t("cli.no_account"));
+        return Ok(());
+    }
+    for account in &accounts {
+        println!(
+            "{:<12} {:<20} Lv{:<3} {}",
+            account.uid,
+            account.name,
+            account.level,
+            if account.is_active {
+                t("cli.account.active")
+            } else {
+                t("cli.account.inactive")
+            }
+        );
+    }
+    Ok(())
+}
+
+async fn import(path: &str) -> Result<(), String> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| tr("cli.read_file_failed", &[&path, &e]))?;
+    let mut config = Config::load_config().unwrap_or_else(|_| Config::new());
+    let mut accounts = config.load_accounts().unwrap_or_default();
+    let ua = user_agent(&config);
+
+    let client = reqwest::Client::new();
+    let reports = import_accounts(&content, &client, &ua, &mut config, &mut accounts).await?;
+    for report in &reports {
+        let status = if report.success {
+            t("cli.import.ok")
+        } else {
+            t("cli.import.failed")
+        };
+        match report.uid {
+            Some(uid) => println!(
+                "[{}] {} {} ({}): {}",
+                status, report.source, report.name, uid, report.message
+            ),
+            None => println!("[{}] {}: {}", status, report.source, report.message),
+        }
+    }
+    let succeeded = reports.iter().filter(|r| r.success).count();
+    println!(
+        "{}",
+        tr(
+            "cli.import.summary",
+            &[&succeeded, &(reports.len() - succeeded)]
+        )
+    );
+    if succeeded == 0 {
+        return Err(t("import.no_session").to_string());
+    }
+    Ok(())
+}
+
+fn export(path: &str, uids: &[i64]) -> Result<(), String> {
+    let accounts = Config::load_all_accounts();
+    let content = export_accounts(&accounts, uids)?;
+    write_private(path, &content).map_err(|e| tr("cli.write_file_failed", &[&path, &e]))?;
+    println!("{}", tr("cli.export.done", &[&path]));
+    Ok(())
+}
+
+// 导出文件包含cookie，仅允许当前用户读写
+fn write_private(path: &str, content: &str) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // 已存在的文件不受 mode 影响，需要单独收紧权限
+        if std::path::Path::new(path).exists() {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    options.open(path)?.write_all(content.as_bytes())
+}
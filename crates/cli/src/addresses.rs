@@ -0,0 +1,34 @@
+use backend::api::get_address_list;
+use common::i18n::t;
+
+use crate::active_account;
+
+// cli addresses：列出启用账号保存的收货地址，地址ID用于 profile add --address
+pub async fn run() -> Result<(), String> {
+    let account = active_account().await?;
+    let cookie_manager = account
+        .cookie_manager
+        .clone()
+        .ok_or_else(|| t("cli.account_uninitialized").to_string())?;
+    let addresses = get_address_list(cookie_manager).await?;
+    if addresses.is_empty() {
+        println!("{}", t("cli.addresses.empty"));
+        return Ok(());
+    }
+    for address in &addresses {
+        let default = if address.is_default {
+            t("cli.addresses.default")
+        } else {
+            ""
+        };
+        println!(
+            "{:<12} {:<6} {:<10} {:<12} {}",
+            address.id,
+            default,
+            address.name,
+            address.tel,
+            address.full_address()
+        );
+    }
+    Ok(())
+}
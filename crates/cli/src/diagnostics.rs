@@ -0,0 +1,18 @@
+use common::diagnostics::build_bundle;
+use common::i18n::{current_lang, tr};
+use common::utils::Config;
+use serde_json::json;
+
+// cli diagnostics [输出文件]
+pub async fn run(args: &[String]) -> Result<(), String> {
+    let config = Config::load_config().unwrap_or_else(|_| Config::new());
+    let app_info = json!({
+        "app": "BTR CLI",
+        "version": env!("CARGO_PKG_VERSION"),
+        "language": current_lang().code(),
+        "http_record_session": common::http_record::current_session(),
+    });
+    let path = build_bundle(app_info, &config, args.first().map(String::as_str))?;
+    println!("{}", tr("cli.diagnostics.done", &[&path]));
+    Ok(())
+}
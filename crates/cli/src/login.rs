@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use backend::api::check_qrcode_login;
+use common::account::add_account;
+use common::i18n::{t, tr};
+use common::login::{QrCodeLoginStatus, qrcode_login};
+use common::utils::Config;
+use qrcode::QrCode;
+use qrcode::render::unicode::Dense1x2;
+use reqwest::Client;
+
+use crate::user_agent;
+
+// 轮询间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+// 二维码过期后最多重新生成的次数
+const MAX_REFRESH: usize = 5;
+
+// cli login [--invert]
+pub async fn run(args: &[String]) -> Result<(), String> {
+    // 默认按深色背景终端渲染，浅色背景加 --invert
+    let invert = args.iter().any(|arg| arg == "--invert");
+    let mut config = Config::load_config().unwrap_or_else(|_| Config::new());
+    let ua = user_agent(&config);
+    let client = Client::builder()
+        .user_agent(&ua)
+        .build()
+        .unwrap_or_default();
+
+    for _ in 0..=MAX_REFRESH {
+        let qrcode_key = qrcode_login(&client).await?;
+        let qrcode_url = format!(
+            "https://passport.bilibili.com/h5-app/passport/login/scan?qrcode_key={}",
+            qrcode_key
+        );
+        print_qrcode(&qrcode_url, invert)?;
+
+        match wait_for_scan(&client, &qrcode_key).await {
+            QrCodeLoginStatus::Success(cookie) => {
+                println!("{}", t("cli.login.confirming"));
+                return save_account(&cookie, &client, &ua, &mut config).await;
+            }
+            QrCodeLoginStatus::Expired => println!("{}", t("cli.login.expired")),
+            QrCodeLoginStatus::Failed(e) => return Err(tr("cli.login.failed", &[&e])),
+            _ => {}
+        }
+    }
+    Err(t("cli.login.too_many_refresh").to_string())
+}
+
+fn print_qrcode(url: &str, invert: bool) -> Result<(), String> {
+    let code = QrCode::new(url.as_bytes()).map_err(|e| format!("生成二维码失败: {}", e))?;
+    let mut renderer = code.render::<Dense1x2>();
+    if !invert {
+        renderer
+            .dark_color(Dense1x2::Light)
+            .light_color(Dense1x2::Dark);
+    }
+    println!();
+    println!("{}", renderer.quiet_zone(true).build());
+    println!("{}", t("cli.login.scan_hint"));
+    println!("{}", url);
+    Ok(())
+}
+
+// 轮询到成功、失败或过期为止，状态变化时输出提示
+async fn wait_for_scan(client: &Client, qrcode_key: &str) -> QrCodeLoginStatus {
+    let mut last_status = None;
+    loop {
+        let status = check_qrcode_login(client, qrcode_key).await;
+        match status {
+            QrCodeLoginStatus::Pending | QrCodeLoginStatus::Scanning => {
+                if last_status.as_ref() != Some(&status) {
+                    println!(
+                        "{}",
+                        if status == QrCodeLoginStatus::Pending {
+                            t("cli.login.pending")
+                        } else {
+                            t("cli.login.scanning")
+                        }
+                    );
+                    last_status = Some(status);
+                }
+            }
+            status => return status,
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+// 校验登录结果并保存账号，已存在的账号更新cookie
+async fn save_account(
+    cookie: &str,
+    client: &Client,
+    ua: &str,
+    config: &mut Config,
+) -> Result<(), String> {
+    let account = add_account(cookie, client, ua).await?;
+    let updated = config.update_account(&account).map_err(|e| e.to_string())?;
+    if !updated {
+        config.add_account(&account).map_err(|e| e.to_string())?;
+    }
+    config.save_config().map_err(|e| e.to_string())?;
+    println!(
+        "{}",
+        tr("cli.login.success", &[&account.name, &account.uid])
+    );
+    Ok(())
+}
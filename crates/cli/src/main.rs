@@ -1,3 +1,93 @@
-fn main() {
-    println!("Hello, world!");
+mod accounts;
+mod addresses;
+mod diagnostics;
+mod login;
+mod orders;
+mod profile;
+mod project;
+mod sales;
+mod seats;
+mod validate;
+
+use common::account::Account;
+use common::i18n::{Lang, set_lang, t, tr};
+use common::utility::CustomConfig;
+use common::utils::Config;
+
+const DEFAULT_UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/134.0.0.0 Safari/537.36 Edg/134.0.0.0";
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+    init_lang();
+    common::http_record::init_from_env();
+    init_pacing();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("login") => login::run(&args[1..]).await,
+        Some("orders") => orders::run(&args[1..]).await,
+        Some("accounts") => accounts::run(&args[1..]).await,
+        Some("addresses") => addresses::run().await,
+        Some("project") => project::run(&args[1..]).await,
+        Some("profile") => profile::run(&args[1..]).await,
+        Some("sales") => sales::run(&args[1..]).await,
+        Some("seats") => seats::run(&args[1..]).await,
+        Some("validate") => validate::run(&args[1..]).await,
+        Some("diagnostics") => diagnostics::run(&args[1..]).await,
+        _ => {
+            println!("{}", t("cli.usage"));
+            Ok(())
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("{}", tr("cli.error", &[&e]));
+        std::process::exit(1);
+    }
+}
+
+// 取第一个启用的账号（没有启用的则取第一个）并初始化请求客户端
+pub async fn active_account() -> Result<Account, String> {
+    let accounts = Config::load_all_accounts();
+    let mut account = accounts
+        .iter()
+        .find(|a| a.is_active)
+        .or_else(|| accounts.first())
+        .cloned()
+        .ok_or_else(|| t("cli.no_account").to_string())?;
+    account.ensure_client().await;
+    Ok(account)
+}
+
+// 开启自定义UA时使用配置中的UA
+pub fn user_agent(config: &Config) -> String {
+    let custom_config = serde_json::from_value::<CustomConfig>(config["custom_config"].clone())
+        .unwrap_or_else(|_| CustomConfig::new());
+    if custom_config.open_custom_ua && !custom_config.custom_ua.is_empty() {
+        custom_config.custom_ua
+    } else {
+        DEFAULT_UA.to_string()
+    }
+}
+
+// 请求节流沿用图形界面保存的设置
+fn init_pacing() {
+    let config = Config::load_config().unwrap_or_else(|_| Config::new());
+    common::pacer::set_config(common::pacer::PacerConfig::load(&config));
+}
+
+// 环境变量 BTR_LANG 优先，其次使用配置文件中的界面语言
+fn init_lang() {
+    let lang = std::env::var("BTR_LANG")
+        .ok()
+        .and_then(|code| Lang::parse(&code))
+        .or_else(|| {
+            Config::load_config()
+                .ok()
+                .and_then(|config| config["language"].as_str().and_then(Lang::parse))
+        });
+    if let Some(lang) = lang {
+        set_lang(lang);
+    }
 }
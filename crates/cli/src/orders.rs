@@ -0,0 +1,138 @@
+use backend::show_orderlist::{export_orders_calendar, get_order_detail, get_orderlist};
+use common::i18n::{t, tr};
+use common::money::Money;
+use common::project_detail::format_timestamp;
+use common::show_orderlist::OrderDetail;
+use common::utils::Config;
+
+use crate::active_account;
+
+// cli orders [list] | show <订单号> | ics <文件>
+pub async fn run(args: &[String]) -> Result<(), String> {
+    if args.first().map(String::as_str) == Some("ics") {
+        let path = args
+            .get(1)
+            .ok_or_else(|| t("cli.missing_file").to_string())?;
+        return export_calendar(path).await;
+    }
+
+    let account = active_account().await?;
+    let cookie_manager = account
+        .cookie_manager
+        .clone()
+        .ok_or_else(|| t("cli.account_uninitialized").to_string())?;
+
+    match args.first().map(String::as_str) {
+        Some("list") | None => {
+            let orders = get_orderlist(cookie_manager).await?;
+            for order in &orders.data.list {
+                println!(
+                    "{:<22} {:<10} {:>10}  {} {}",
+                    order.order_id,
+                    order.sub_status_name,
+                    Money::from_fen(order.pay_money),
+                    order.item_info.name,
+                    order.item_info.screen_name
+                );
+            }
+            Ok(())
+        }
+        Some("show") => {
+            let order_id = args
+                .get(1)
+                .ok_or_else(|| t("cli.missing_order_id").to_string())?;
+            let detail = get_order_detail(cookie_manager, order_id).await?;
+            print_order_detail(&detail);
+            Ok(())
+        }
+        Some(other) => Err(tr("cli.unknown_subcommand", &[&other])),
+    }
+}
+
+// 导出所有账号的已支付订单
+async fn export_calendar(path: &str) -> Result<(), String> {
+    let mut accounts = Config::load_all_accounts();
+    if accounts.is_empty() {
+        return Err(t("cli.no_account").to_string());
+    }
+    for account in &mut accounts {
+        account.ensure_client().await;
+    }
+    let (content, count) = export_orders_calendar(&accounts).await?;
+    std::fs::write(path, content).map_err(|e| tr("cli.write_file_failed", &[&path, &e]))?;
+    println!("{}", tr("cli.calendar.done", &[&count, &path]));
+    Ok(())
+}
+
+fn print_order_detail(detail: &OrderDetail) {
+    println!(
+        "{}",
+        tr("cli.order.title", &[&detail.order_id, &detail.status_name])
+    );
+    println!("{} {}", detail.project_name, detail.screen_name);
+    if !detail.screen_start_time.is_empty() {
+        println!("{}", tr("cli.show_time", &[&detail.screen_start_time]));
+    }
+    println!(
+        "{}",
+        tr(
+            "cli.order.ticket",
+            &[&detail.ticket_name, &detail.unit_price, &detail.count]
+        )
+    );
+    println!(
+        "{}",
+        tr(
+            "cli.order.amount",
+            &[&detail.total_money, &detail.pay_money]
+        )
+    );
+    if detail.express_fee.fen() > 0 {
+        println!("{}", tr("cli.express_fee", &[&detail.express_fee]));
+    }
+    println!("{}", tr("cli.order.ctime", &[&detail.ctime]));
+    if let Some(deadline) = detail.pay_deadline {
+        println!(
+            "{}",
+            tr("cli.order.pay_deadline", &[&format_timestamp(deadline)])
+        );
+    }
+
+    if !detail.buyers.is_empty() {
+        println!();
+        println!("{}", t("cli.order.buyers"));
+        for buyer in &detail.buyers {
+            println!("  {} {} {}", buyer.name, buyer.personal_id, buyer.tel);
+        }
+    }
+    if !detail.seats.is_empty() {
+        println!();
+        println!("{}", t("cli.order.seats"));
+        for seat in &detail.seats {
+            println!("  {} {}", seat.area, seat.seat);
+        }
+    }
+    if let Some(delivery) = &detail.delivery {
+        println!();
+        println!("{}", t("cli.order.delivery"));
+        println!("  {} {} {}", delivery.name, delivery.tel, delivery.address);
+        if !delivery.tracking_no.is_empty() {
+            println!(
+                "  {} {} {}",
+                delivery.express_company, delivery.tracking_no, delivery.express_status
+            );
+        }
+    }
+
+    println!();
+    let refund = if detail.refund.refundable {
+        t("cli.order.refundable")
+    } else {
+        t("cli.order.not_refundable")
+    };
+    if detail.refund.reason.is_empty() {
+        println!("{}", refund);
+    } else {
+        println!("{} ({})", refund, detail.refund.reason);
+    }
+}
@@ -0,0 +1,276 @@
+use backend::grab_request::build_profile_request;
+use common::account::Account;
+use common::grab_profile::{
+    GrabProfile, find_profile, load_profiles, remove_profile, upsert_profile,
+};
+use common::i18n::{t, tr};
+use common::money::Money;
+use common::retry::RetryPolicy;
+use common::ticket::NoBindBuyerInfo;
+use common::ticket_preference::TicketPreference;
+use common::utils::Config;
+use common::validate::validate_task;
+use serde_json::json;
+use std::str::FromStr;
+
+use crate::active_account;
+use crate::seats::split_list;
+use crate::validate::print_report;
+
+// cli profile [list] | add <名称> <项目ID> <场次ID> <票种ID> [选项] [购票人ID...]
+//   | update <配置ID> [选项] [购票人ID...] | check <配置ID> | delete <配置ID>
+pub async fn run(args: &[String]) -> Result<(), String> {
+    match args.first().map(String::as_str) {
+        Some("list") | None => {
+            let config = Config::load_config().unwrap_or_else(|_| Config::new());
+            let profiles = load_profiles(&config);
+            if profiles.is_empty() {
+                println!("{}", t("cli.profile.empty"));
+            }
+            for profile in &profiles {
+                print_profile(profile);
+            }
+            Ok(())
+        }
+        Some("add") => add(&args[1..]),
+        Some("update") => update(profile_id(args)?, &args[2..]),
+        Some("check") => check(profile_id(args)?).await,
+        Some("delete") => {
+            let id = profile_id(args)?;
+            let mut config = Config::load_config().map_err(|e| e.to_string())?;
+            remove_profile(&mut config, id)?;
+            config.save_config().map_err(|e| e.to_string())?;
+            println!("{}", tr("cli.profile.deleted", &[&id]));
+            Ok(())
+        }
+        Some(other) => Err(tr("cli.unknown_subcommand", &[&other])),
+    }
+}
+
+fn profile_id(args: &[String]) -> Result<&str, String> {
+    args.get(1)
+        .map(String::as_str)
+        .ok_or_else(|| t("cli.missing_profile_id").to_string())
+}
+
+fn print_profile(profile: &GrabProfile) {
+    let account = profile
+        .uid
+        .map(|uid| uid.to_string())
+        .unwrap_or_else(|| t("cli.profile.any_account").to_string());
+    let budget = profile
+        .max_total_price
+        .map(|price| price.to_string())
+        .unwrap_or_else(|| "-".to_string());
+    println!(
+        "{:<14} {:<16} UID:{:<12} {}/{}/{}  {}  {}",
+        profile.id,
+        profile.name,
+        account,
+        profile.project_id,
+        profile.screen_id,
+        profile.ticket_id,
+        tr("cli.profile.mode", &[&profile.grab_mode]),
+        budget
+    );
+}
+
+fn add(args: &[String]) -> Result<(), String> {
+    let mut config = Config::load_config().map_err(|e| e.to_string())?;
+    let mut profile = GrabProfile::default();
+    let positional = apply_options(&mut profile, args, &config)?;
+    let [name, project_id, screen_id, ticket_id, buyer_ids @ ..] = positional.as_slice() else {
+        return Err(t("cli.missing_profile_args").to_string());
+    };
+    profile.name = name.clone();
+    profile.project_id = project_id.clone();
+    profile.screen_id = screen_id.clone();
+    profile.ticket_id = ticket_id.clone();
+    profile.buyer_ids = buyer_ids.to_vec();
+
+    let profile = upsert_profile(&mut config, profile)?;
+    config.save_config().map_err(|e| e.to_string())?;
+    println!("{}", tr("cli.profile.saved", &[&profile.name, &profile.id]));
+    Ok(())
+}
+
+// 只修改指定的字段，给出购票人ID时替换原有的购票人
+fn update(id: &str, args: &[String]) -> Result<(), String> {
+    let mut config = Config::load_config().map_err(|e| e.to_string())?;
+    let mut profile = find_profile(&config, id)?;
+    let buyer_ids = apply_options(&mut profile, args, &config)?;
+    if !buyer_ids.is_empty() {
+        profile.buyer_ids = buyer_ids;
+    }
+
+    let profile = upsert_profile(&mut config, profile)?;
+    config.save_config().map_err(|e| e.to_string())?;
+    println!("{}", tr("cli.profile.saved", &[&profile.name, &profile.id]));
+    Ok(())
+}
+
+// 解析 add/update 共用的选项，返回其余的位置参数
+fn apply_options(
+    profile: &mut GrabProfile,
+    args: &[String],
+    config: &Config,
+) -> Result<Vec<String>, String> {
+    let mut positional = Vec::new();
+    let mut preferences = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--name" => profile.name = iter.next().cloned().unwrap_or_default(),
+            "--project" => profile.project_id = iter.next().cloned().unwrap_or_default(),
+            "--screen" => profile.screen_id = iter.next().cloned().unwrap_or_default(),
+            "--ticket" => profile.ticket_id = iter.next().cloned().unwrap_or_default(),
+            "--mode" => {
+                profile.grab_mode = iter
+                    .next()
+                    .and_then(|m| m.parse().ok())
+                    .ok_or_else(|| t("cli.invalid_mode").to_string())?;
+            }
+            "--uid" => {
+                let uid = iter.next().map(String::as_str).unwrap_or_default();
+                profile.uid = Some(uid.parse().map_err(|_| tr("cli.invalid_uid", &[&uid]))?);
+            }
+            "--budget" => {
+                let amount = iter.next().map(String::as_str).unwrap_or_default();
+                profile.max_total_price = Some(Money::parse_yuan(amount)?);
+            }
+            "--skip" => {
+                profile.skip_words = iter
+                    .next()
+                    .map(|words| words.split(',').map(str::to_string).collect())
+                    .unwrap_or_default();
+            }
+            "--seats" => profile.seats.seat_ids = split_list(iter.next()),
+            "--areas" => profile.seats.areas = split_list(iter.next()),
+            "--address" => profile.address_id = iter.next().cloned(),
+            "--buyer-type" => {
+                profile.buyer_type = match iter.next().map(String::as_str) {
+                    Some("auto") => None,
+                    Some(value @ ("0" | "1" | "2")) => value.parse().ok(),
+                    _ => return Err(t("cli.invalid_buyer_type").to_string()),
+                };
+            }
+            "--no-bind-buyer" => {
+                let value = iter.next().map(String::as_str).unwrap_or_default();
+                let (name, tel) = value
+                    .split_once(',')
+                    .map(|(name, tel)| (name.trim(), tel.trim()))
+                    .filter(|(name, tel)| !name.is_empty() && !tel.is_empty())
+                    .ok_or_else(|| t("cli.invalid_no_bind_buyer").to_string())?;
+                profile.no_bind_buyer = Some(NoBindBuyerInfo {
+                    name: name.to_string(),
+                    tel: tel.to_string(),
+                    uid: 0, // 非实名购票人没有uid
+                });
+            }
+            "--prefer" => preferences.push(parse_preference(
+                iter.next().map(String::as_str).unwrap_or_default(),
+            )?),
+            "--token-attempts" => {
+                retry_policy(profile, config).token.max_attempts = number(arg, iter.next())?;
+            }
+            "--confirm-attempts" => {
+                retry_policy(profile, config).confirm.max_attempts = number(arg, iter.next())?;
+            }
+            "--order-attempts" => {
+                retry_policy(profile, config).order.max_attempts = number(arg, iter.next())?;
+            }
+            "--backoff" => {
+                let backoff = iter.next().map(String::as_str).unwrap_or_default();
+                retry_policy(profile, config).backoff = serde_json::from_value(json!(backoff))
+                    .map_err(|_| t("cli.invalid_backoff").to_string())?;
+            }
+            "--deadline" => {
+                retry_policy(profile, config).deadline_secs = number(arg, iter.next())?;
+            }
+            _ => positional.push(arg.clone()),
+        }
+    }
+    // 给出 --prefer 时整体替换原有偏好
+    if !preferences.is_empty() {
+        profile.preferences = preferences;
+    }
+    Ok(positional)
+}
+
+// 配置没有单独的重试策略时，以设置页的重试策略为基础修改
+fn retry_policy<'a>(profile: &'a mut GrabProfile, config: &Config) -> &'a mut RetryPolicy {
+    profile
+        .retry_policy
+        .get_or_insert_with(|| RetryPolicy::load(config, profile.grab_mode))
+}
+
+fn number<T: FromStr>(flag: &str, value: Option<&String>) -> Result<T, String> {
+    value
+        .and_then(|v| v.trim().parse().ok())
+        .ok_or_else(|| tr("cli.invalid_number", &[&flag]))
+}
+
+// 场次ID:票种ID:最高价:关键词1+关键词2，留空或 * 表示不限
+fn parse_preference(spec: &str) -> Result<TicketPreference, String> {
+    let invalid = || tr("cli.invalid_preference", &[&spec]);
+    let mut parts = spec.splitn(4, ':').map(str::trim);
+    let mut id = || match parts.next() {
+        None | Some("") | Some("*") => Ok(None),
+        Some(id) => id.parse().map(Some).map_err(|_| invalid()),
+    };
+    let screen_id = id()?;
+    let ticket_id = id()?;
+    let max_price = match parts.next() {
+        None | Some("") | Some("*") => None,
+        Some(price) => Some(Money::parse_yuan(price).map_err(|_| invalid())?),
+    };
+    let keywords = parts
+        .next()
+        .map(|words| {
+            words
+                .split('+')
+                .map(str::trim)
+                .filter(|word| !word.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(TicketPreference {
+        screen_id,
+        ticket_id,
+        max_price,
+        keywords,
+    })
+}
+
+// 按配置构造抢票请求并检查，不会下单
+async fn check(id: &str) -> Result<(), String> {
+    let config = Config::load_config().unwrap_or_else(|_| Config::new());
+    let profile = find_profile(&config, id)?;
+    let account = profile_account(profile.uid).await?;
+    let cookie_manager = account
+        .cookie_manager
+        .clone()
+        .ok_or_else(|| t("cli.account_uninitialized").to_string())?;
+
+    let request = build_profile_request(&account, cookie_manager, &profile).await?;
+    let report = validate_task(&request);
+    print_report(&report);
+    if report.has_errors() {
+        return Err(t("cli.validate.failed").to_string());
+    }
+    Ok(())
+}
+
+// 配置指定的账号，未指定时取启用的账号
+async fn profile_account(uid: Option<i64>) -> Result<Account, String> {
+    let Some(uid) = uid else {
+        return active_account().await;
+    };
+    let mut account = Config::load_all_accounts()
+        .into_iter()
+        .find(|a| a.uid == uid)
+        .ok_or_else(|| tr("cli.invalid_uid", &[&uid]))?;
+    account.ensure_client().await;
+    Ok(account)
+}
@@ -0,0 +1,100 @@
+use backend::api::get_project;
+use common::i18n::{t, tr};
+use common::money::Money;
+use common::project_detail::ProjectDetail;
+use common::ticket::BaseInfoItem;
+use common::validate::id_bind_name;
+
+use crate::active_account;
+
+pub async fn run(args: &[String]) -> Result<(), String> {
+    let project_id = args
+        .first()
+        .ok_or_else(|| t("cli.missing_project_id").to_string())?;
+    let account = active_account().await?;
+    let cookie_manager = account
+        .cookie_manager
+        .clone()
+        .ok_or_else(|| t("cli.account_uninitialized").to_string())?;
+
+    let info = get_project(cookie_manager, project_id).await?;
+    print_project_detail(&info.data.project_detail());
+    Ok(())
+}
+
+fn print_project_detail(detail: &ProjectDetail) {
+    println!("{} (ID: {})", detail.name, detail.id);
+    println!("{}", tr("cli.show_time", &[&detail.show_time]));
+    match &detail.venue {
+        Some(venue) if !venue.address.is_empty() => println!(
+            "{}",
+            tr("cli.venue_with_address", &[&venue.name, &venue.address])
+        ),
+        Some(venue) => println!("{}", tr("cli.venue", &[&venue.name])),
+        None => println!("{}", tr("cli.venue", &[&t("common.unknown")])),
+    }
+    println!("{}", tr("cli.sale_time", &[&detail.sale_time]));
+    println!("{}", tr("cli.id_bind", &[&id_bind_name(detail.id_bind)]));
+    if detail.express_fee > 0 {
+        println!(
+            "{}",
+            tr(
+                "cli.express_fee",
+                &[&Money::from_fen(detail.express_fee as i64)]
+            )
+        );
+    }
+
+    print_section(t("cli.section.base_info"), &detail.base_info);
+    print_section(t("cli.section.purchase_notes"), &detail.purchase_notes);
+    print_section(t("cli.section.real_name_rules"), &detail.real_name_rules);
+    print_section(t("cli.section.refund_policy"), &detail.refund_policy);
+
+    for screen in &detail.screens {
+        println!();
+        println!(
+            "{}",
+            tr(
+                "cli.screen",
+                &[
+                    &screen.name,
+                    &screen.screen_id,
+                    &screen.start_time,
+                    &screen.sale_flag,
+                    &screen.delivery
+                ]
+            )
+        );
+        println!(
+            "  {:<10} {:>10}  {:<8} {}",
+            t("cli.column.ticket_id"),
+            t("cli.column.price"),
+            t("cli.column.status"),
+            t("cli.column.ticket")
+        );
+        for ticket in &screen.tickets {
+            println!(
+                "  {:<10} {:>10}  {:<8} {}",
+                ticket.ticket_id,
+                format!("¥{}", ticket.price_text),
+                ticket.sale_flag,
+                ticket.desc
+            );
+        }
+    }
+}
+
+fn print_section(title: &str, items: &[BaseInfoItem]) {
+    if items.is_empty() {
+        return;
+    }
+    println!();
+    println!("[{}]", title);
+    for item in items {
+        if item.title.is_empty() {
+            println!("  {}", item.content);
+        } else {
+            println!("  {}: {}", item.title, item.content);
+        }
+    }
+}
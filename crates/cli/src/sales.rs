@@ -0,0 +1,55 @@
+use backend::sale_calendar::fetch_upcoming_sales;
+use common::i18n::{t, tr};
+use common::project_detail::format_timestamp;
+use common::sale_calendar::{SaleOpening, sale_events, saved_project_ids};
+use common::utils::Config;
+
+use crate::active_account;
+
+// cli sales [list] | ics <文件>
+pub async fn run(args: &[String]) -> Result<(), String> {
+    let openings = load_openings().await?;
+    match args.first().map(String::as_str) {
+        Some("list") | None => {
+            if openings.is_empty() {
+                println!("{}", t("cli.sales.empty"));
+            }
+            for opening in &openings {
+                println!(
+                    "{:<20} {:>14}  {} {}  ({})",
+                    format_timestamp(opening.sale_start),
+                    opening.price_range(),
+                    opening.project_name,
+                    opening.tier,
+                    opening.screens.join(", ")
+                );
+            }
+            Ok(())
+        }
+        Some("ics") => {
+            let path = args
+                .get(1)
+                .ok_or_else(|| t("cli.missing_file").to_string())?;
+            let content = common::ics::build_calendar(&sale_events(&openings));
+            std::fs::write(path, content).map_err(|e| tr("cli.write_file_failed", &[&path, &e]))?;
+            println!("{}", tr("cli.sales.done", &[&openings.len(), &path]));
+            Ok(())
+        }
+        Some(other) => Err(tr("cli.unknown_subcommand", &[&other])),
+    }
+}
+
+// 项目列表中保存的项目尚未开售的票档
+async fn load_openings() -> Result<Vec<SaleOpening>, String> {
+    let config = Config::load_config().unwrap_or_else(|_| Config::new());
+    let project_ids = saved_project_ids(&config);
+    if project_ids.is_empty() {
+        return Err(t("cli.sales.no_project").to_string());
+    }
+    let account = active_account().await?;
+    let cookie_manager = account
+        .cookie_manager
+        .clone()
+        .ok_or_else(|| t("cli.account_uninitialized").to_string())?;
+    Ok(fetch_upcoming_sales(cookie_manager, &project_ids).await)
+}
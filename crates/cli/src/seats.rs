@@ -0,0 +1,82 @@
+use backend::api::get_seat_map;
+use common::i18n::{t, tr};
+use common::seat::{SeatSelection, pick_seats, seat_names};
+
+use crate::active_account;
+
+// cli seats <项目ID> <场次ID> <票种ID> [--areas 区域1,区域2] [--count N]
+pub async fn run(args: &[String]) -> Result<(), String> {
+    let mut selection = SeatSelection::default();
+    let mut count = None;
+    let mut positional = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--areas" => selection.areas = split_list(iter.next()),
+            "--count" => {
+                let value = iter.next().map(String::as_str).unwrap_or_default();
+                count = Some(
+                    value
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| tr("cli.seats.invalid_count", &[&value]))?,
+                );
+            }
+            _ => positional.push(arg.as_str()),
+        }
+    }
+    let [project_id, screen_id, ticket_id] = positional.as_slice() else {
+        return Err(t("cli.missing_seats_args").to_string());
+    };
+
+    let account = active_account().await?;
+    let cookie_manager = account
+        .cookie_manager
+        .clone()
+        .ok_or_else(|| t("cli.account_uninitialized").to_string())?;
+    let seat_map = get_seat_map(cookie_manager, project_id, screen_id, ticket_id).await?;
+    if seat_map.areas.is_empty() {
+        println!("{}", t("cli.seats.empty"));
+        return Ok(());
+    }
+
+    // 只列出可选座位，括号中为指定座位时使用的座位ID
+    for area in &seat_map.areas {
+        println!(
+            "{}",
+            tr(
+                "cli.seats.area",
+                &[&area.name, &area.id, &area.available_count()]
+            )
+        );
+        for row in &area.rows {
+            let seats: Vec<String> = row
+                .seats
+                .iter()
+                .filter(|seat| seat.available)
+                .map(|seat| format!("{}({})", seat.col, seat.id))
+                .collect();
+            if !seats.is_empty() {
+                println!("  {:<6} {}", row.name, seats.join(" "));
+            }
+        }
+    }
+
+    if let Some(count) = count {
+        let seats = pick_seats(&seat_map, &selection, count)?;
+        println!("{}", tr("cli.seats.picked", &[&seat_names(&seats)]));
+    }
+    Ok(())
+}
+
+pub fn split_list(value: Option<&String>) -> Vec<String> {
+    value
+        .map(|list| {
+            list.split(',')
+                .map(|item| item.trim().to_string())
+                .filter(|item| !item.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,71 @@
+use backend::grab_request::build_grab_request;
+use common::i18n::{t, tr};
+use common::validate::{ValidationLevel, ValidationReport, validate_task};
+
+use crate::active_account;
+
+// cli validate <项目ID> <场次ID> <票种ID> [--mode N] [购票人ID...]
+pub async fn run(args: &[String]) -> Result<(), String> {
+    let mut grab_mode = 0u8;
+    let mut positional = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--mode" {
+            grab_mode = iter
+                .next()
+                .and_then(|m| m.parse().ok())
+                .ok_or_else(|| t("cli.invalid_mode").to_string())?;
+        } else {
+            positional.push(arg.as_str());
+        }
+    }
+    let [project_id, screen_id, ticket_id, buyer_ids @ ..] = positional.as_slice() else {
+        return Err(t("cli.missing_validate_args").to_string());
+    };
+
+    let account = active_account().await?;
+    let cookie_manager = account
+        .cookie_manager
+        .clone()
+        .ok_or_else(|| t("cli.account_uninitialized").to_string())?;
+
+    let buyer_ids: Vec<String> = buyer_ids.iter().map(|id| id.to_string()).collect();
+    let request = build_grab_request(
+        &account,
+        cookie_manager,
+        project_id,
+        screen_id,
+        ticket_id,
+        &buyer_ids,
+        grab_mode,
+    )
+    .await?;
+
+    let report = validate_task(&request);
+    print_report(&report);
+    if report.has_errors() {
+        return Err(t("cli.validate.failed").to_string());
+    }
+    Ok(())
+}
+
+pub fn print_report(report: &ValidationReport) {
+    if report.issues.is_empty() {
+        println!("{}", t("cli.validate.passed"));
+        return;
+    }
+    for issue in &report.issues {
+        let tag = match issue.level {
+            ValidationLevel::Error => t("cli.validate.error"),
+            ValidationLevel::Warning => t("cli.validate.warning"),
+        };
+        println!("[{}] {}: {}", tag, issue.field, issue.message);
+    }
+    println!(
+        "{}",
+        tr(
+            "cli.validate.summary",
+            &[&report.errors().count(), &report.warnings().count()]
+        )
+    );
+}
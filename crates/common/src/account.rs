@@ -1,8 +1,5 @@
 use crate::cookie_manager::CookieManager;
-use crate::{
-    cookie_manager,
-    http_utils::{request_get_sync, request_post_sync},
-};
+use crate::{cookie_manager, http_utils::request_get};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json;
@@ -40,24 +37,20 @@ impl std::fmt::Debug for Account {
     }
 }
 
-pub fn add_account(cookie: &str, client: &Client, ua: &str) -> Result<Account, String> {
-    log::info!("添加账号: {}", cookie);
-    let response = request_get_sync(
+pub async fn add_account(cookie: &str, client: &Client, ua: &str) -> Result<Account, String> {
+    let response = request_get(
         client,
         "https://api.bilibili.com/x/web-interface/nav",
-        Some(ua.to_string()),
         Some(cookie),
     )
+    .await
     .map_err(|e| e.to_string())?;
 
-    // 创建一个临时的运行时来执行异步代码
-    let rt = tokio::runtime::Runtime::new().unwrap();
-    let json = rt
-        .block_on(async { response.json::<serde_json::Value>().await })
+    let json = response
+        .json::<serde_json::Value>()
+        .await
         .map_err(|e| e.to_string())?;
-    let cookie_manager = Arc::new(
-        rt.block_on(async { cookie_manager::CookieManager::new(cookie, Some(ua), 0).await }),
-    );
+    let cookie_manager = Arc::new(cookie_manager::CookieManager::new(cookie, Some(ua), 0).await);
     log::debug!("获取账号信息: {:?}", json);
     match json.get("code") {
         Some(code) if code.as_i64() == Some(0) => {} // 成功
@@ -83,30 +76,29 @@ pub fn add_account(cookie: &str, client: &Client, ua: &str) -> Result<Account, S
             avatar_url: Some(data["face"].as_str().unwrap_or("").to_string()),
             cookie_manager: Some(cookie_manager),
         };
-        account.ensure_client();
+        account.ensure_client().await;
+        log::info!("添加账号: {}", account.uid);
         Ok(account)
     } else {
         Err("无法获取用户信息".to_string())
     }
 }
 
-pub fn signout_account(account: &Account) -> Result<bool, String> {
+pub async fn signout_account(account: &Account) -> Result<bool, String> {
     let data = serde_json::json!({
         "biliCSRF" : account.csrf,
 
     });
-    let rt = tokio::runtime::Runtime::new().unwrap();
-    let response = rt.block_on(async {
-        account
-            .cookie_manager
-            .clone()
-            .unwrap()
-            .post("https://passport.bilibili.com/login/exit/v2")
-            .await
-            .json(&data)
-            .send()
-            .await
-    });
+    let cookie_manager = match account.cookie_manager.clone() {
+        Some(cm) => cm,
+        None => return Err("账号未初始化请求客户端".to_string()),
+    };
+    let response = cookie_manager
+        .post("https://passport.bilibili.com/login/exit/v2")
+        .await
+        .json(&data)
+        .send()
+        .await;
 
     let resp = match response {
         Ok(res) => res,
@@ -142,13 +134,9 @@ fn extract_csrf(cookie: &str) -> String {
 }
 impl Account {
     // 确保每个账号都有自己的 client
-    pub fn ensure_client(&mut self) {
-        let rt = tokio::runtime::Runtime::new().unwrap();
+    pub async fn ensure_client(&mut self) {
         if self.cookie_manager.is_none() {
-            rt.block_on(async {
-                self.cookie_manager =
-                    Some(Arc::new(CookieManager::new(&self.cookie, None, 0).await))
-            });
+            self.cookie_manager = Some(Arc::new(CookieManager::new(&self.cookie, None, 0).await));
         }
     }
 }
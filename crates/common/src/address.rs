@@ -0,0 +1,101 @@
+use crate::i18n::{t, tr};
+use crate::show_orderlist::{array_field, bool_field, str_field};
+use crate::ticket::TicketInfo;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+// 账号保存的收货地址，纸质票快递配送时使用
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeliveryAddress {
+    pub id: String,
+    pub name: String, //收货人
+    pub tel: String,
+    pub prov: String,
+    pub city: String,
+    pub area: String,
+    pub addr: String, //详细地址
+    #[serde(default)]
+    pub is_default: bool,
+}
+
+impl DeliveryAddress {
+    pub fn from_value(value: &Value) -> Self {
+        DeliveryAddress {
+            id: str_field(value, &["id", "addr_id"]),
+            name: str_field(value, &["name", "receiver"]),
+            tel: str_field(value, &["phone", "tel"]),
+            prov: str_field(value, &["prov", "province"]),
+            city: str_field(value, &["city"]),
+            area: str_field(value, &["area", "district"]),
+            addr: str_field(value, &["addr", "address", "detail"]),
+            is_default: bool_field(value, &["def", "is_default", "default"]),
+        }
+    }
+
+    pub fn full_address(&self) -> String {
+        format!("{}{}{}{}", self.prov, self.city, self.area, self.addr)
+    }
+}
+
+// 地址列表接口直接返回数组，或放在 list/addr_list 中
+pub fn parse_address_list(data: &Value) -> Vec<DeliveryAddress> {
+    let list = match data.as_array() {
+        Some(list) => list.as_slice(),
+        None => array_field(data, &["list", "addr_list"]),
+    };
+    list.iter().map(DeliveryAddress::from_value).collect()
+}
+
+// 按地址ID选择，未指定时取默认地址，没有默认地址时取第一个
+pub fn pick_address(
+    addresses: &[DeliveryAddress],
+    address_id: Option<&str>,
+) -> Result<DeliveryAddress, String> {
+    let address = match address_id.filter(|id| !id.is_empty()) {
+        Some(id) => addresses
+            .iter()
+            .find(|address| address.id == id)
+            .ok_or_else(|| tr("address.not_found", &[&id]))?,
+        None => addresses
+            .iter()
+            .find(|address| address.is_default)
+            .or_else(|| addresses.first())
+            .ok_or_else(|| t("address.empty").to_string())?,
+    };
+    Ok(address.clone())
+}
+
+// 场次配送方式，1 电子票，2 快递纸质票，3 现场取票
+pub fn delivery_type_name(delivery_type: usize) -> String {
+    match delivery_type {
+        1 => t("delivery.electronic").to_string(),
+        2 => t("delivery.express").to_string(),
+        3 => t("delivery.on_site").to_string(),
+        other => tr("delivery.unknown", &[&other]),
+    }
+}
+
+// 场次的配送方式，未找到场次时为 0
+pub fn screen_delivery_type(info: &TicketInfo, screen_id: &str) -> usize {
+    info.screen_list
+        .iter()
+        .find(|screen| screen.id.to_string() == screen_id)
+        .map(|screen| screen.delivery_type)
+        .unwrap_or(0)
+}
+
+// 有快递费或场次为快递配送时下单需要收货地址
+pub fn needs_delivery(info: &TicketInfo, screen_id: &str) -> bool {
+    info.express_fee > 0 || screen_delivery_type(info, screen_id) == 2
+}
+
+// 下单接口中的收货信息，与 buyer_info 一样以 JSON 字符串提交
+pub fn address_payload(address: &DeliveryAddress) -> String {
+    json!({
+        "name": address.name,
+        "tel": address.tel,
+        "addr_id": address.id.parse::<i64>().unwrap_or(0),
+        "addr": address.full_address(),
+    })
+    .to_string()
+}
@@ -188,11 +188,12 @@ pub async fn handle_risk_verification(
     };
     log::debug!("风控参数: {:?}", risk_params_value);
     let url = "https://api.bilibili.com/x/gaia-vgate/v1/register";
-    let response = cookie_manager
+    let request = cookie_manager
         .post(url)
         .await
-        .json(&json!(risk_params_value))
-        .send()
+        .json(&json!(risk_params_value));
+    let response = cookie_manager
+        .execute(request)
         .await
         .map_err(|e| format!("发送风控请求失败: {}", e))?;
     if !response.status().is_success() {
@@ -260,11 +261,9 @@ pub async fn handle_risk_verification(
 
             log::debug!("发送验证请求: {:?}", params);
             let validate_url = "https://api.bilibili.com/x/gaia-vgate/v1/validate";
+            let validate_request = cookie_manager.post(validate_url).await.json(&params);
             let validate_response = cookie_manager
-                .post(validate_url)
-                .await
-                .json(&params)
-                .send()
+                .execute(validate_request)
                 .await
                 .map_err(|e| format!("发送验证请求失败: {}", e))?;
 
@@ -0,0 +1,285 @@
+use crate::account::{Account, add_account};
+use crate::i18n::{t, tr};
+use crate::utils::Config;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// 批量导出文件的格式版本
+const EXPORT_VERSION: i64 = 1;
+
+// 只导入这些域名下的cookie
+const BILIBILI_DOMAIN: &str = "bilibili.com";
+
+// 从导入文件中解析出的一个登录会话
+#[derive(Clone, Debug)]
+pub struct CookieSession {
+    pub source: String, //来源描述，用于报告，如 "#1"、"uid 123"
+    pub cookie: String, //k=v; k=v 格式
+}
+
+// 单个会话的导入结果
+#[derive(Clone, Debug, Serialize)]
+pub struct ImportReport {
+    pub source: String,
+    pub success: bool,
+    pub uid: Option<i64>,
+    pub name: String,
+    pub updated: bool, //账号已存在，更新了cookie
+    pub message: String,
+}
+
+// 批量导出文件中的账号
+#[derive(Serialize, Deserialize)]
+struct ExportedAccount {
+    uid: i64,
+    name: String,
+    cookie: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ExportFile {
+    version: i64,
+    accounts: Vec<ExportedAccount>,
+}
+
+// 解析导入文件，支持：
+// 1. 本程序导出的账号文件
+// 2. 浏览器插件导出的JSON（cookie对象数组，或多个数组组成的数组）
+// 3. Netscape cookies.txt
+// 4. k=v; k=v 格式的cookie字符串，每行一个账号
+pub fn parse_cookie_export(content: &str) -> Result<Vec<CookieSession>, String> {
+    let content = content.trim_start_matches('\u{feff}').trim();
+    if content.is_empty() {
+        return Err(t("import.empty").to_string());
+    }
+
+    let sessions = if content.starts_with('{') || content.starts_with('[') {
+        let json: Value = serde_json::from_str(content)
+            .map_err(|e| tr("import.invalid_json", &[&e.to_string()]))?;
+        parse_json_export(&json)?
+    } else if is_netscape(content) {
+        vec![parse_netscape(content)]
+    } else {
+        content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| line.to_string())
+            .collect()
+    };
+
+    let sessions: Vec<CookieSession> = sessions
+        .into_iter()
+        .enumerate()
+        .map(|(index, cookie)| CookieSession {
+            source: format!("#{}", index + 1),
+            cookie,
+        })
+        .collect();
+    if sessions.is_empty() {
+        return Err(t("import.no_session").to_string());
+    }
+    Ok(sessions)
+}
+
+fn parse_json_export(json: &Value) -> Result<Vec<String>, String> {
+    // 本程序导出的账号文件
+    if let Ok(file) = serde_json::from_value::<ExportFile>(json.clone()) {
+        return Ok(file
+            .accounts
+            .into_iter()
+            .map(|account| account.cookie)
+            .collect());
+    }
+
+    match json {
+        // 部分插件把cookie放在 cookies 字段里
+        Value::Object(map) => match map.get("cookies") {
+            Some(Value::Array(cookies)) => Ok(vec![join_cookie_objects(cookies)]),
+            _ => Err(t("import.unknown_format").to_string()),
+        },
+        Value::Array(items) if items.iter().all(Value::is_array) => Ok(items
+            .iter()
+            .filter_map(Value::as_array)
+            .map(|cookies| join_cookie_objects(cookies))
+            .collect()),
+        Value::Array(items) => Ok(vec![join_cookie_objects(items)]),
+        _ => Err(t("import.unknown_format").to_string()),
+    }
+}
+
+// 插件导出的cookie对象：{"domain": ".bilibili.com", "name": "SESSDATA", "value": "..."}
+fn join_cookie_objects(cookies: &[Value]) -> String {
+    let pairs = cookies.iter().filter_map(|cookie| {
+        // 没有domain的cookie无法确认来源，直接跳过
+        let domain = cookie["domain"].as_str()?;
+        let name = cookie["name"].as_str()?;
+        let value = cookie["value"].as_str()?;
+        Some((domain, name, value))
+    });
+    join_bilibili_cookies(pairs)
+}
+
+fn is_netscape(content: &str) -> bool {
+    content.starts_with("# Netscape HTTP Cookie File")
+        || content.starts_with("# HTTP Cookie File")
+        || content.lines().any(|line| line.split('\t').count() == 7)
+}
+
+// Netscape格式每行7列：domain flag path secure expires name value
+fn parse_netscape(content: &str) -> String {
+    let pairs = content.lines().filter_map(|line| {
+        // curl 等工具用 #HttpOnly_ 前缀标记 HttpOnly cookie
+        let line = line
+            .trim()
+            .strip_prefix("#HttpOnly_")
+            .unwrap_or(line.trim());
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != 7 {
+            return None;
+        }
+        Some((fields[0], fields[5], fields[6]))
+    });
+    join_bilibili_cookies(pairs)
+}
+
+// 过滤出B站域名下的cookie并拼接，同名cookie保留第一个
+fn join_bilibili_cookies<'a>(pairs: impl Iterator<Item = (&'a str, &'a str, &'a str)>) -> String {
+    let mut seen = Vec::new();
+    let mut parts = Vec::new();
+    for (domain, name, value) in pairs {
+        let domain = domain.trim_start_matches('.');
+        if !(domain == BILIBILI_DOMAIN || domain.ends_with(".bilibili.com")) {
+            continue;
+        }
+        if name.is_empty() || seen.contains(&name) {
+            continue;
+        }
+        seen.push(name);
+        parts.push(format!("{}={}", name, value));
+    }
+    parts.join("; ")
+}
+
+fn has_cookie(cookie: &str, name: &str) -> bool {
+    cookie.split(';').any(|part| {
+        part.trim()
+            .split_once('=')
+            .is_some_and(|(key, value)| key == name && !value.is_empty())
+    })
+}
+
+// 逐个会话通过nav接口校验并生成账号，失败的会话不影响其他会话
+pub async fn import_sessions(
+    sessions: Vec<CookieSession>,
+    client: &Client,
+    ua: &str,
+) -> Vec<(ImportReport, Option<Account>)> {
+    let mut results = Vec::new();
+    for session in sessions {
+        let mut report = ImportReport {
+            source: session.source.clone(),
+            success: false,
+            uid: None,
+            name: String::new(),
+            updated: false,
+            message: String::new(),
+        };
+        if !has_cookie(&session.cookie, "SESSDATA") {
+            report.message = t("import.missing_sessdata").to_string();
+            results.push((report, None));
+            continue;
+        }
+        match add_account(&session.cookie, client, ua).await {
+            Ok(account) if account.uid > 0 => {
+                report.success = true;
+                report.uid = Some(account.uid);
+                report.name = account.name.clone();
+                report.message = t("import.success").to_string();
+                results.push((report, Some(account)));
+            }
+            Ok(_) => {
+                report.message = t("import.not_logged_in").to_string();
+                results.push((report, None));
+            }
+            Err(e) => {
+                report.message = tr("import.validate_failed", &[&e]);
+                results.push((report, None));
+            }
+        }
+    }
+    results
+}
+
+// 将导入成功的账号写入配置和账号列表，已存在的账号只更新cookie
+pub fn apply_imported(
+    config: &mut Config,
+    accounts: &mut Vec<Account>,
+    results: Vec<(ImportReport, Option<Account>)>,
+) -> Result<Vec<ImportReport>, String> {
+    let mut reports = Vec::new();
+    for (mut report, account) in results {
+        let Some(account) = account else {
+            reports.push(report);
+            continue;
+        };
+        if let Some(existing) = accounts.iter_mut().find(|a| a.uid == account.uid) {
+            existing.cookie = account.cookie.clone();
+            existing.csrf = account.csrf.clone();
+            existing.name = account.name.clone();
+            existing.is_login = true;
+            existing.cookie_manager = account.cookie_manager.clone();
+            config.update_account(existing).map_err(|e| e.to_string())?;
+            report.updated = true;
+            report.message = t("import.updated").to_string();
+        } else {
+            config.add_account(&account).map_err(|e| e.to_string())?;
+            accounts.push(account);
+        }
+        reports.push(report);
+    }
+    config.save_config().map_err(|e| e.to_string())?;
+    log::info!(
+        "导入账号完成，成功{}个，共{}个",
+        reports.iter().filter(|r| r.success).count(),
+        reports.len()
+    );
+    Ok(reports)
+}
+
+// 解析、校验并保存，供界面和命令行共用
+pub async fn import_accounts(
+    content: &str,
+    client: &Client,
+    ua: &str,
+    config: &mut Config,
+    accounts: &mut Vec<Account>,
+) -> Result<Vec<ImportReport>, String> {
+    let sessions = parse_cookie_export(content)?;
+    let results = import_sessions(sessions, client, ua).await;
+    apply_imported(config, accounts, results)
+}
+
+// 导出账号，uids 为空时导出全部
+pub fn export_accounts(accounts: &[Account], uids: &[i64]) -> Result<String, String> {
+    let file = ExportFile {
+        version: EXPORT_VERSION,
+        accounts: accounts
+            .iter()
+            .filter(|account| uids.is_empty() || uids.contains(&account.uid))
+            .map(|account| ExportedAccount {
+                uid: account.uid,
+                name: account.name.clone(),
+                cookie: account.cookie.clone(),
+            })
+            .collect(),
+    };
+    if file.accounts.is_empty() {
+        return Err(t("import.nothing_to_export").to_string());
+    }
+    serde_json::to_string_pretty(&file).map_err(|e| e.to_string())
+}
@@ -1,3 +1,5 @@
+use crate::http_record::{self, HttpExchange};
+use crate::pacer::RequestPacer;
 use crate::web_ck_obfuscated::*;
 use cookie::Cookie;
 use rand::seq::SliceRandom;
@@ -36,6 +38,7 @@ pub struct CookieManager {
     app_data: Option<AppData>,
     pub web_data: Option<WebData>,
     pub cookies: CookiesData,
+    pub pacer: Arc<RequestPacer>, //同一账号的请求共用节流状态
 }
 
 #[derive(Debug, Clone)]
@@ -312,6 +315,7 @@ impl CookieManager {
                     app_data: None,
                     web_data: Some(web_data),
                     cookies: cookies,
+                    pacer: Arc::default(),
                 }
             }
 
@@ -329,6 +333,7 @@ impl CookieManager {
                     app_data: None,
                     web_data: None,
                     cookies: cookies,
+                    pacer: Arc::default(),
                 }
             }
         }
@@ -380,6 +385,7 @@ impl CookieManager {
             app_data: None,
             web_data: None,
             cookies: cookies,
+            pacer: Arc::default(),
         }
     }
 
@@ -525,22 +531,88 @@ impl CookieManager {
         builder
     }
 
-    //处理响应中的 cookie
+    //发送请求并处理响应中的 cookie，开启录制/回放时在此记录或返回录制的响应
     pub async fn execute(
         &self,
         request: reqwest::RequestBuilder,
     ) -> Result<reqwest::Response, reqwest::Error> {
-        let response = request.send().await?;
+        let (client, request) = request.build_split();
+        let request = request?;
+        let method = request.method().to_string();
+        let url = request.url().to_string();
+
+        if http_record::is_replaying() {
+            return Ok(match http_record::take_replay(&method, &url) {
+                Some(exchange) => http_record::to_response(&exchange),
+                None => http_record::missing_response(&method, &url),
+            });
+        }
 
-        // 从响应中提取并更新 cookies
+        self.pacer.acquire().await;
+
+        let recording = http_record::is_recording();
+        let mut exchange = recording.then(|| HttpExchange {
+            seq: 0,
+            timestamp: chrono::Local::now()
+                .format("%Y-%m-%d %H:%M:%S%.3f")
+                .to_string(),
+            method: method.clone(),
+            url: http_record::redact_url(&url),
+            request_headers: http_record::redact_headers(request.headers()),
+            request_body: request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .map(|bytes| http_record::redact_body(&String::from_utf8_lossy(bytes))),
+            status: 0,
+            response_headers: Default::default(),
+            response_body: String::new(),
+            elapsed_ms: 0,
+            error: None,
+        });
+        let started = std::time::Instant::now();
+
+        let response = match client.execute(request).await {
+            Ok(response) => response,
+            Err(e) => {
+                if let Some(mut exchange) = exchange {
+                    exchange.elapsed_ms = started.elapsed().as_millis() as u64;
+                    exchange.error = Some(e.to_string());
+                    http_record::record(exchange);
+                }
+                return Err(e);
+            }
+        };
+        self.pacer.on_status(response.status().as_u16(), &url);
+
+        // 从响应中提取并更新 cookies，只取 name=value 部分
         let cookies = response.headers().get_all(reqwest::header::SET_COOKIE);
         for cookie_header in cookies {
             if let Ok(cookie_str) = cookie_header.to_str() {
                 log::debug!("从响应中获取到 cookie: {}", cookie_str);
-                self.update_cookies(cookie_str);
+                let pair = cookie_str.split(';').next().unwrap_or_default();
+                self.update_cookies(pair);
             }
         }
 
-        Ok(response)
+        let Some(exchange) = exchange.as_mut() else {
+            return Ok(response);
+        };
+
+        // 录制时需要读取响应体，读取后重新组装响应交给调用方
+        let status = response.status();
+        let version = response.version();
+        let headers = response.headers().clone();
+        let body = response.bytes().await?;
+        exchange.status = status.as_u16();
+        exchange.response_headers = http_record::redact_headers(&headers);
+        exchange.response_body = http_record::redact_body(&String::from_utf8_lossy(&body));
+        exchange.elapsed_ms = started.elapsed().as_millis() as u64;
+        http_record::record(exchange.clone());
+
+        let mut rebuilt = http::Response::new(body);
+        *rebuilt.status_mut() = status;
+        *rebuilt.version_mut() = version;
+        *rebuilt.headers_mut() = headers;
+        Ok(reqwest::Response::from(rebuilt))
     }
 }
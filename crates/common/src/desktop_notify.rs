@@ -0,0 +1,86 @@
+use crate::i18n::tr;
+use crate::notification::NotifyPriority;
+use notify_rust::Notification;
+
+const APP_NAME: &str = "BTR";
+
+// 显示本机桌面通知，Linux 通过 freedesktop 通知 D-Bus 接口，Windows/macOS 使用系统通知中心
+// 有跳转链接时点击通知打开链接，只在支持通知动作的 Linux 桌面可用，其他平台把链接附在正文后
+// 会阻塞到通知发出，需在阻塞线程中调用
+pub fn show(
+    title: &str,
+    message: &str,
+    priority: NotifyPriority,
+    jump_url: Option<&str>,
+) -> Result<(), String> {
+    let mut notification = Notification::new();
+    notification.appname(APP_NAME).summary(title);
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        use notify_rust::Urgency;
+        let urgency = match priority {
+            NotifyPriority::Passive => Urgency::Low,
+            NotifyPriority::Active => Urgency::Normal,
+            NotifyPriority::TimeSensitive => Urgency::Critical,
+        };
+        notification.body(message).urgency(urgency);
+        if jump_url.is_some() {
+            notification.action("default", crate::i18n::t("push.desktop.open"));
+        }
+        let handle = notification
+            .show()
+            .map_err(|e| tr("push.desktop.failed", &[&e]))?;
+        if let Some(url) = jump_url {
+            // 等待点击或关闭，不阻塞推送流程
+            let url = url.to_string();
+            std::thread::spawn(move || {
+                handle.wait_for_action(|action| {
+                    if action == "default" {
+                        open_url(&url);
+                    }
+                });
+            });
+        }
+    }
+
+    #[cfg(not(all(unix, not(target_os = "macos"))))]
+    {
+        let _ = priority;
+        let body = match jump_url {
+            Some(url) => format!("{}\n{}", message, url),
+            None => message.to_string(),
+        };
+        notification.body(&body);
+        notification
+            .show()
+            .map_err(|e| tr("push.desktop.failed", &[&e]))?;
+    }
+
+    Ok(())
+}
+
+// B站APP深链前缀，只有手机能打开
+const APP_LINK_PREFIX: &str = "bilibili://mall/web?url=";
+
+// 桌面通知点击时用浏览器打开网页，APP深链取出其中的网页地址
+pub fn web_url(url: &str) -> &str {
+    url.strip_prefix(APP_LINK_PREFIX).unwrap_or(url)
+}
+
+// 用系统默认浏览器打开链接
+pub fn open_url(url: &str) {
+    // 不经过 cmd 解析，避免链接中的 & 等字符被当作命令分隔符
+    #[cfg(target_os = "windows")]
+    let result = std::process::Command::new("rundll32")
+        .args(["url.dll,FileProtocolHandler", url])
+        .spawn();
+    #[cfg(target_os = "macos")]
+    let result = std::process::Command::new("open").arg(url).spawn();
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let result = std::process::Command::new("xdg-open").arg(url).spawn();
+
+    if let Err(e) = result {
+        log::error!("打开链接失败: {}: {}", url, e);
+    }
+}
@@ -0,0 +1,307 @@
+use crate::utils::Config;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use zip::CompressionMethod;
+use zip::write::{FileOptions, ZipWriter};
+
+// 诊断包默认输出目录
+const DIAGNOSTICS_DIR: &str = "Diagnostics";
+const LOG_DIR: &str = "Log";
+// 打包最近的日志文件数
+const MAX_LOG_FILES: usize = 5;
+// 保留的任务记录条数
+const MAX_TASK_HISTORY: usize = 200;
+const REDACTED: &str = "<redacted>";
+
+// 日志中需要脱敏的字段
+const SECRET_FIELDS: &[&str] = &[
+    "SESSDATA",
+    "bili_jct",
+    "DedeUserID__ckMd5",
+    "sid",
+    "csrf",
+    "biliCSRF",
+    "access_key",
+    "refresh_token",
+    "token",
+    "ptoken",
+    "personal_id",
+    "tel",
+    "phone",
+];
+
+// 配置中键名包含这些词的字符串值会被脱敏
+const SECRET_CONFIG_WORDS: &[&str] = &[
+    "token",
+    "password",
+    "secret",
+    "key",
+    "cookie",
+    "csrf",
+    "url",
+    "username",
+    "smtp_from",
+    "smtp_to",
+    "phone",
+    "tel",
+];
+
+// 已结束任务的记录
+#[derive(Clone, Debug, Serialize)]
+pub struct TaskRecord {
+    pub task_id: String,
+    pub kind: String,
+    pub status: String,
+    pub started_at: String,
+    pub finished_at: String,
+    pub elapsed_ms: u64,
+}
+
+// 接口错误码统计
+#[derive(Clone, Debug, Serialize)]
+pub struct ErrnoStat {
+    pub api: String,
+    pub errno: i64,
+    pub count: u64,
+    pub last_seen: String,
+}
+
+static TASK_HISTORY: Lazy<Mutex<VecDeque<TaskRecord>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(MAX_TASK_HISTORY)));
+static ERRNO_STATS: Lazy<Mutex<BTreeMap<(String, i64), ErrnoStat>>> =
+    Lazy::new(|| Mutex::new(BTreeMap::new()));
+
+pub fn now_string() -> String {
+    chrono::Local::now()
+        .format("%Y-%m-%d %H:%M:%S%.3f")
+        .to_string()
+}
+
+pub fn record_task(record: TaskRecord) {
+    if let Ok(mut history) = TASK_HISTORY.lock() {
+        if history.len() >= MAX_TASK_HISTORY {
+            history.pop_front();
+        }
+        history.push_back(record);
+    }
+}
+
+pub fn task_history() -> Vec<TaskRecord> {
+    TASK_HISTORY
+        .lock()
+        .map(|history| history.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+// 记录一次接口返回的错误码，0 表示成功
+pub fn record_errno(api: &str, errno: i64) {
+    if let Ok(mut stats) = ERRNO_STATS.lock() {
+        let stat = stats
+            .entry((api.to_string(), errno))
+            .or_insert_with(|| ErrnoStat {
+                api: api.to_string(),
+                errno,
+                count: 0,
+                last_seen: String::new(),
+            });
+        stat.count += 1;
+        stat.last_seen = now_string();
+    }
+}
+
+// 按出现次数从多到少排列
+pub fn errno_stats() -> Vec<ErrnoStat> {
+    let mut stats: Vec<ErrnoStat> = ERRNO_STATS
+        .lock()
+        .map(|stats| stats.values().cloned().collect())
+        .unwrap_or_default();
+    stats.sort_by(|a, b| b.count.cmp(&a.count));
+    stats
+}
+
+// 脱敏日志文本中的 cookie、token、证件号等
+pub fn redact_text(text: &str) -> String {
+    let mut result = text.to_string();
+    for field in SECRET_FIELDS {
+        for marker in [
+            format!("{}=", field),
+            format!("\"{}\":\"", field),
+            format!("\"{}\": \"", field),
+            format!("{}: \"", field),
+            format!("{}:", field),
+            format!("{}: ", field),
+        ] {
+            result = redact_after(&result, &marker);
+        }
+    }
+    result
+}
+
+// 将 marker 之后直到分隔符的内容替换为 <redacted>，marker 前须为单词边界
+fn redact_after(text: &str, marker: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find(marker) {
+        let start = pos + marker.len();
+        let at_boundary = !rest[..pos]
+            .chars()
+            .last()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_');
+        result.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        let end = tail
+            .find(|c: char| {
+                matches!(
+                    c,
+                    ';' | '&' | '"' | '\'' | ',' | ' ' | ')' | '}' | '\n' | '\t' | '\r'
+                )
+            })
+            .unwrap_or(tail.len());
+        if at_boundary && end > 0 && &tail[..end] != REDACTED {
+            result.push_str(REDACTED);
+        } else {
+            result.push_str(&tail[..end]);
+        }
+        rest = &tail[end..];
+    }
+    result.push_str(rest);
+    result
+}
+
+// 脱敏后的配置摘要：账号只保留UID、昵称等，推送token等密钥全部隐藏
+pub fn config_summary(config: &Config) -> Value {
+    let mut value = config.as_value().clone();
+    if let Some(accounts) = value.get("accounts").and_then(Value::as_array) {
+        let accounts: Vec<Value> = accounts
+            .iter()
+            .map(|account| {
+                json!({
+                    "uid": account["uid"],
+                    "name": account["name"],
+                    "level": account["level"],
+                    "is_login": account["is_login"],
+                    "is_active": account["is_active"],
+                    "vip_label": account["vip_label"],
+                })
+            })
+            .collect();
+        value["accounts"] = Value::Array(accounts);
+    }
+    // 抢票配置中的购票人信息只保留是否填写
+    if let Some(profiles) = value.get_mut("grab_profiles").and_then(Value::as_array_mut) {
+        for profile in profiles {
+            for field in ["no_bind_buyer", "buyer_ids"] {
+                if !profile[field].is_null() {
+                    profile[field] = json!(REDACTED);
+                }
+            }
+        }
+    }
+    // 钩子的命令和地址可能带有密钥，整体不导出
+    if let Some(map) = value.as_object_mut() {
+        map.remove("hooks");
+    }
+    redact_config(&mut value);
+    value
+}
+
+fn redact_config(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let key = key.to_lowercase();
+                let secret = SECRET_CONFIG_WORDS.iter().any(|word| key.contains(word));
+                match value {
+                    Value::String(s) if secret && !s.is_empty() => {
+                        *s = REDACTED.to_string();
+                    }
+                    _ => redact_config(value),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_config),
+        _ => {}
+    }
+}
+
+// 最近的日志文件，按文件名（含时间戳）倒序
+fn recent_log_files() -> Vec<std::path::PathBuf> {
+    let Ok(entries) = fs::read_dir(LOG_DIR) else {
+        return Vec::new();
+    };
+    let mut files: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "log"))
+        .collect();
+    files.sort();
+    files.reverse();
+    files.truncate(MAX_LOG_FILES);
+    files
+}
+
+// 生成诊断包，返回zip文件路径；output 为空时写入 Diagnostics 目录
+pub fn build_bundle(
+    app_info: Value,
+    config: &Config,
+    output: Option<&str>,
+) -> Result<String, String> {
+    let path = match output {
+        Some(path) => path.to_string(),
+        None => {
+            fs::create_dir_all(DIAGNOSTICS_DIR).map_err(|e| format!("无法创建目录: {}", e))?;
+            let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+            Path::new(DIAGNOSTICS_DIR)
+                .join(format!("btr_diagnostics_{}.zip", timestamp))
+                .to_string_lossy()
+                .to_string()
+        }
+    };
+    let file = File::create(&path).map_err(|e| format!("无法创建诊断包 {}: {}", path, e))?;
+    let mut zip = ZipWriter::new(file);
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let mut add = |name: &str, content: &[u8]| -> Result<(), String> {
+        zip.start_file(name, options)
+            .map_err(|e| format!("写入{}失败: {}", name, e))?;
+        zip.write_all(content)
+            .map_err(|e| format!("写入{}失败: {}", name, e))
+    };
+    let to_json = |value: &Value| serde_json::to_vec_pretty(value).unwrap_or_default();
+
+    let mut app_info = app_info;
+    app_info["system"] = json!({
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "generated_at": now_string(),
+    });
+    add("app_info.json", &to_json(&app_info))?;
+    add("config_summary.json", &to_json(&config_summary(config)))?;
+    add("task_history.json", &to_json(&json!(task_history())))?;
+    add("errno_stats.json", &to_json(&json!(errno_stats())))?;
+
+    for log_file in recent_log_files() {
+        let Some(name) = log_file
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+        else {
+            continue;
+        };
+        match fs::read(&log_file) {
+            Ok(content) => {
+                let content = redact_text(&String::from_utf8_lossy(&content));
+                add(&format!("logs/{}", name), content.as_bytes())?;
+            }
+            Err(e) => log::warn!("读取日志文件{}失败: {}", name, e),
+        }
+    }
+
+    zip.finish().map_err(|e| format!("生成诊断包失败: {}", e))?;
+    log::info!("诊断包已生成: {}", path);
+    Ok(path)
+}
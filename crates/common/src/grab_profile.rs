@@ -0,0 +1,148 @@
+use crate::i18n::{t, tr};
+use crate::money::Money;
+use crate::retry::RetryPolicy;
+use crate::seat::SeatSelection;
+use crate::taskmanager::GrabTicketRequest;
+use crate::ticket::NoBindBuyerInfo;
+use crate::ticket_preference::TicketPreference;
+use crate::utils::Config;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+// 命名的抢票配置，保存在 config["grab_profiles"]，切换活动时无需重新选择
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct GrabProfile {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub uid: Option<i64>, //None 时使用启用的账号
+    pub project_id: String,
+    #[serde(default)]
+    pub screen_id: String,
+    #[serde(default)]
+    pub ticket_id: String,
+    #[serde(default)]
+    pub buyer_type: Option<u8>, //0 非实名, 1 实名, 2 实名(备用模式)，None 按项目要求
+    #[serde(default)]
+    pub buyer_ids: Vec<String>, //实名购票人ID，为空时使用默认购票人
+    #[serde(default)]
+    pub no_bind_buyer: Option<NoBindBuyerInfo>,
+    #[serde(default)]
+    pub grab_mode: u8,
+    #[serde(default)]
+    pub skip_words: Vec<String>,
+    #[serde(default)]
+    pub retry_policy: Option<RetryPolicy>, //None 时使用设置页的重试策略
+    #[serde(default)]
+    pub max_total_price: Option<Money>, //预算上限（含快递费）
+    #[serde(default)]
+    pub preferences: Vec<TicketPreference>,
+    #[serde(default)]
+    pub seats: SeatSelection, //选座项目的指定座位或区域偏好
+    #[serde(default)]
+    pub address_id: Option<String>, //快递配送时的收货地址ID，None 时使用账号的默认地址
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
+impl GrabProfile {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err(t("profile.name_empty").to_string());
+        }
+        if self.project_id.trim().is_empty() {
+            return Err(t("task.no_project").to_string());
+        }
+        if self.grab_mode > 2 {
+            return Err(t("cli.invalid_mode").to_string());
+        }
+        match self.buyer_type {
+            Some(0) if self.no_bind_buyer.is_none() => {
+                return Err(t("task.no_bind_buyer_missing").to_string());
+            }
+            Some(3..) => return Err(t("task.invalid_buyer_type").to_string()),
+            _ => {}
+        }
+        if let Some(policy) = &self.retry_policy {
+            policy.validate()?;
+        }
+        Ok(())
+    }
+
+    // 在按项目/场次/票种/购票人构造的请求上套用配置中的其他设置
+    pub fn apply(&self, request: &mut GrabTicketRequest) {
+        match self.buyer_type {
+            Some(0) => {
+                request.biliticket.id_bind = 0;
+                request.biliticket.buyer_info = None;
+                request.biliticket.no_bind_buyer_info = self.no_bind_buyer.clone();
+                request.biliticket.count = Some(1);
+                request.buyer_info.clear();
+                request.count = 1;
+            }
+            Some(buyer_type) => request.biliticket.id_bind = buyer_type as usize,
+            None => {}
+        }
+        let skip_words: Vec<String> = self
+            .skip_words
+            .iter()
+            .map(|word| word.trim().to_string())
+            .filter(|word| !word.is_empty())
+            .collect();
+        request.skip_words = (!skip_words.is_empty()).then_some(skip_words);
+        request.max_total_price = self.max_total_price;
+        request.preferences = self.preferences.clone();
+        request.seat_selection = self.seats.clone();
+        if let Some(policy) = &self.retry_policy {
+            request.retry_policy = policy.clone();
+        }
+    }
+}
+
+pub fn load_profiles(config: &Config) -> Vec<GrabProfile> {
+    serde_json::from_value(config["grab_profiles"].clone()).unwrap_or_default()
+}
+
+pub fn find_profile(config: &Config, id: &str) -> Result<GrabProfile, String> {
+    load_profiles(config)
+        .into_iter()
+        .find(|profile| profile.id == id)
+        .ok_or_else(|| tr("profile.not_found", &[&id]))
+}
+
+// 新增或按ID更新配置，ID为空时生成新ID，只修改 config，由调用方保存
+pub fn upsert_profile(
+    config: &mut Config,
+    mut profile: GrabProfile,
+) -> Result<GrabProfile, String> {
+    profile.validate()?;
+    profile.name = profile.name.trim().to_string();
+    let now = chrono::Local::now();
+    profile.updated_at = now.timestamp();
+
+    let mut profiles = load_profiles(config);
+    if profile.id.is_empty() {
+        profile.id = now.timestamp_millis().to_string();
+        profiles.push(profile.clone());
+    } else {
+        match profiles.iter_mut().find(|p| p.id == profile.id) {
+            Some(existing) => *existing = profile.clone(),
+            None => return Err(tr("profile.not_found", &[&profile.id])),
+        }
+    }
+    config["grab_profiles"] = json!(profiles);
+    Ok(profile)
+}
+
+pub fn remove_profile(config: &mut Config, id: &str) -> Result<(), String> {
+    let Value::Array(profiles) = &mut config["grab_profiles"] else {
+        return Err(tr("profile.not_found", &[&id]));
+    };
+    let original_len = profiles.len();
+    profiles.retain(|profile| profile["id"].as_str() != Some(id));
+    if profiles.len() == original_len {
+        return Err(tr("profile.not_found", &[&id]));
+    }
+    Ok(())
+}
@@ -0,0 +1,185 @@
+use crate::notification::NotifyEvent;
+use crate::utils::Config;
+use serde::{Deserialize, Serialize};
+use std::process::Stdio;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+// 默认超时时间（秒）
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+// 日志中保留的输出长度
+const MAX_OUTPUT_LOG: usize = 2000;
+
+// 钩子可订阅的事件
+pub const HOOK_EVENTS: [NotifyEvent; 3] = [
+    NotifyEvent::GrabSuccess,
+    NotifyEvent::GrabFailed,
+    NotifyEvent::SessionExpired,
+];
+
+// 本地命令钩子：事件发生时运行指定程序
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CommandHook {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub command: String, //可执行文件路径，不经过shell
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub events: Vec<NotifyEvent>, //为空时订阅全部钩子事件
+    #[serde(default = "default_timeout")]
+    pub timeout_secs: u64,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_timeout() -> u64 {
+    DEFAULT_TIMEOUT_SECS
+}
+
+impl CommandHook {
+    pub fn subscribes(&self, event: NotifyEvent) -> bool {
+        self.enabled
+            && !self.command.trim().is_empty()
+            && (self.events.is_empty() || self.events.contains(&event))
+    }
+
+    fn display_name(&self) -> &str {
+        if self.name.is_empty() {
+            &self.command
+        } else {
+            &self.name
+        }
+    }
+}
+
+// 传给钩子的事件数据，同时以环境变量和stdin JSON提供
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct HookPayload {
+    pub event: Option<NotifyEvent>,
+    pub task_id: String,
+    pub uid: i64,
+    pub project_id: String,
+    pub project_name: String,
+    pub screen_id: String,
+    pub screen_name: String,
+    pub ticket_id: String,
+    pub ticket_name: String,
+    pub order_id: Option<String>,
+    pub pay_url: Option<String>,
+    pub message: String,
+    pub timestamp: i64,
+}
+
+impl HookPayload {
+    fn env_vars(&self) -> Vec<(&'static str, String)> {
+        let event = self
+            .event
+            .and_then(|e| serde_json::to_value(e).ok())
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default();
+        vec![
+            ("BTR_EVENT", event),
+            ("BTR_TASK_ID", self.task_id.clone()),
+            ("BTR_UID", self.uid.to_string()),
+            ("BTR_PROJECT_ID", self.project_id.clone()),
+            ("BTR_PROJECT", self.project_name.clone()),
+            ("BTR_SCREEN_ID", self.screen_id.clone()),
+            ("BTR_SCREEN", self.screen_name.clone()),
+            ("BTR_TICKET_ID", self.ticket_id.clone()),
+            ("BTR_TICKET", self.ticket_name.clone()),
+            ("BTR_ORDER_ID", self.order_id.clone().unwrap_or_default()),
+            ("BTR_PAY_URL", self.pay_url.clone().unwrap_or_default()),
+            ("BTR_MESSAGE", self.message.clone()),
+            ("BTR_TIMESTAMP", self.timestamp.to_string()),
+        ]
+    }
+}
+
+// 任务管理器与抢票任务共享的钩子列表，设置修改后对运行中的任务立即生效
+pub type SharedHooks = Arc<RwLock<Vec<CommandHook>>>;
+
+// 从配置的 hooks 字段读取
+pub fn load_hooks(config: &Config) -> Vec<CommandHook> {
+    serde_json::from_value(config["hooks"].clone()).unwrap_or_default()
+}
+
+// 触发事件，订阅的钩子在后台运行，不阻塞抢票流程
+pub fn fire(hooks: &SharedHooks, event: NotifyEvent, mut payload: HookPayload) {
+    let hooks: Vec<CommandHook> = match hooks.read() {
+        Ok(hooks) => hooks
+            .iter()
+            .filter(|h| h.subscribes(event))
+            .cloned()
+            .collect(),
+        Err(_) => return,
+    };
+    if hooks.is_empty() {
+        return;
+    }
+    payload.event = Some(event);
+    payload.timestamp = chrono::Local::now().timestamp();
+    for hook in hooks {
+        let payload = payload.clone();
+        tokio::spawn(async move {
+            if let Err(e) = run_hook(&hook, &payload).await {
+                log::error!("钩子{}运行失败: {}", hook.display_name(), e);
+            }
+        });
+    }
+}
+
+// 运行一个钩子，等待结束并记录退出状态和输出
+pub async fn run_hook(hook: &CommandHook, payload: &HookPayload) -> Result<(), String> {
+    let name = hook.display_name();
+    let mut child = Command::new(&hook.command)
+        .args(&hook.args)
+        .envs(payload.env_vars())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("无法启动 {}: {}", hook.command, e))?;
+
+    let input = serde_json::to_vec(payload).map_err(|e| e.to_string())?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // 钩子不读取stdin时写入会失败，忽略即可
+        let _ = stdin.write_all(&input).await;
+    }
+
+    let timeout = Duration::from_secs(hook.timeout_secs.max(1));
+    let output = match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(output) => output.map_err(|e| e.to_string())?,
+        Err(_) => return Err(format!("超过{}秒未结束，已终止", timeout.as_secs())),
+    };
+
+    let stdout = truncate(&String::from_utf8_lossy(&output.stdout));
+    let stderr = truncate(&String::from_utf8_lossy(&output.stderr));
+    if output.status.success() {
+        log::info!("钩子{}运行完成，退出状态: {}", name, output.status);
+    } else {
+        log::error!("钩子{}运行失败，退出状态: {}", name, output.status);
+    }
+    if !stdout.is_empty() {
+        log::info!("钩子{}输出: {}", name, stdout);
+    }
+    if !stderr.is_empty() {
+        log::warn!("钩子{}错误输出: {}", name, stderr);
+    }
+    Ok(())
+}
+
+fn truncate(text: &str) -> String {
+    let text = text.trim();
+    match text.char_indices().nth(MAX_OUTPUT_LOG) {
+        Some((index, _)) => format!("{}...", &text[..index]),
+        None => text.to_string(),
+    }
+}
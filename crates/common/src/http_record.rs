@@ -0,0 +1,347 @@
+use once_cell::sync::Lazy;
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+// 录制文件默认目录
+const RECORD_DIR: &str = "HttpRecord";
+const REDACTED: &str = "<redacted>";
+
+// 请求头中需要脱敏的字段（cookie 只保留名称）
+const SENSITIVE_HEADERS: &[&str] = &["authorization", "x-csrf-token", "x-bili-ticket"];
+
+// 请求体/响应体中需要脱敏的字段，不区分大小写
+const SENSITIVE_FIELDS: &[&str] = &[
+    "csrf",
+    "bili_jct",
+    "sessdata",
+    "dedeuserid__ckmd5",
+    "refresh_token",
+    "access_token",
+    "access_key",
+    "token",
+    "ptoken",
+    "personal_id",
+    "id_card",
+    "tel",
+    "phone",
+    "mobile",
+    "addr",
+    "address",
+];
+
+// 一次完整的请求与响应
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HttpExchange {
+    pub seq: u64,
+    pub timestamp: String,
+    pub method: String,
+    pub url: String,
+    pub request_headers: BTreeMap<String, String>,
+    pub request_body: Option<String>,
+    pub status: u16,
+    pub response_headers: BTreeMap<String, String>,
+    pub response_body: String,
+    pub elapsed_ms: u64,
+    pub error: Option<String>, //请求失败时的错误信息
+}
+
+enum RecordMode {
+    Off,
+    Record {
+        path: String,
+        file: File,
+        seq: u64,
+    },
+    Replay {
+        path: String,
+        exchanges: Vec<HttpExchange>,
+        used: Vec<bool>,
+    },
+}
+
+static MODE: Lazy<Mutex<RecordMode>> = Lazy::new(|| Mutex::new(RecordMode::Off));
+
+// 开始录制，返回录制文件路径；dir 为空时写入 HttpRecord 目录
+pub fn start_recording(dir: Option<&str>) -> Result<String, String> {
+    let dir = dir.unwrap_or(RECORD_DIR);
+    create_private_dir(dir).map_err(|e| format!("无法创建录制目录: {}", e))?;
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let path = Path::new(dir)
+        .join(format!("session_{}.jsonl", timestamp))
+        .to_string_lossy()
+        .to_string();
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    // 录制内容可能残留账号信息，仅允许当前用户读写
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let file = options
+        .open(&path)
+        .map_err(|e| format!("无法创建录制文件 {}: {}", path, e))?;
+
+    let mut mode = MODE.lock().map_err(|_| "录制状态锁定失败".to_string())?;
+    *mode = RecordMode::Record {
+        path: path.clone(),
+        file,
+        seq: 0,
+    };
+    log::info!("开始录制HTTP请求: {}", path);
+    Ok(path)
+}
+
+fn create_private_dir(dir: &str) -> std::io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(dir)
+}
+
+// 开始回放，返回录制文件中的请求数
+pub fn start_replay(path: &str) -> Result<usize, String> {
+    let exchanges = load_session(path)?;
+    let count = exchanges.len();
+    let mut mode = MODE.lock().map_err(|_| "录制状态锁定失败".to_string())?;
+    *mode = RecordMode::Replay {
+        path: path.to_string(),
+        used: vec![false; count],
+        exchanges,
+    };
+    log::info!("开始回放HTTP录制: {}，共{}条", path, count);
+    Ok(count)
+}
+
+pub fn stop() {
+    if let Ok(mut mode) = MODE.lock() {
+        *mode = RecordMode::Off;
+    }
+}
+
+pub fn is_recording() -> bool {
+    matches!(MODE.lock().as_deref(), Ok(RecordMode::Record { .. }))
+}
+
+pub fn is_replaying() -> bool {
+    matches!(MODE.lock().as_deref(), Ok(RecordMode::Replay { .. }))
+}
+
+// 当前录制或回放的文件
+pub fn current_session() -> Option<String> {
+    match MODE.lock().as_deref() {
+        Ok(RecordMode::Record { path, .. }) | Ok(RecordMode::Replay { path, .. }) => {
+            Some(path.clone())
+        }
+        _ => None,
+    }
+}
+
+// 环境变量 BTR_HTTP_REPLAY=<录制文件> 开启回放，BTR_HTTP_RECORD=1 或 <目录> 开启录制
+pub fn init_from_env() {
+    if let Ok(path) = std::env::var("BTR_HTTP_REPLAY") {
+        if let Err(e) = start_replay(&path) {
+            log::error!("开启HTTP回放失败: {}", e);
+        }
+    } else if let Ok(dir) = std::env::var("BTR_HTTP_RECORD") {
+        let dir = match dir.as_str() {
+            "" | "1" | "true" => None,
+            dir => Some(dir),
+        };
+        if let Err(e) = start_recording(dir) {
+            log::error!("开启HTTP录制失败: {}", e);
+        }
+    }
+}
+
+// 读取录制文件，每行一条记录
+pub fn load_session(path: &str) -> Result<Vec<HttpExchange>, String> {
+    let file = File::open(path).map_err(|e| format!("无法打开录制文件 {}: {}", path, e))?;
+    BufReader::new(file)
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.as_ref().map_or(true, |l| !l.trim().is_empty()))
+        .map(|(index, line)| {
+            let line = line.map_err(|e| e.to_string())?;
+            serde_json::from_str(&line).map_err(|e| format!("第{}行解析失败: {}", index + 1, e))
+        })
+        .collect()
+}
+
+// 追加一条录制记录，未开启录制时忽略
+pub(crate) fn record(mut exchange: HttpExchange) {
+    let Ok(mut mode) = MODE.lock() else {
+        return;
+    };
+    let RecordMode::Record { file, seq, .. } = &mut *mode else {
+        return;
+    };
+    *seq += 1;
+    exchange.seq = *seq;
+    match serde_json::to_string(&exchange) {
+        Ok(line) => {
+            if let Err(e) = writeln!(file, "{}", line) {
+                log::error!("写入HTTP录制失败: {}", e);
+            }
+        }
+        Err(e) => log::error!("序列化HTTP录制失败: {}", e),
+    }
+}
+
+// 按方法和URL取出下一条未使用的录制记录，完整URL匹配不到时忽略查询参数再匹配
+pub(crate) fn take_replay(method: &str, url: &str) -> Option<HttpExchange> {
+    let mut mode = MODE.lock().ok()?;
+    let RecordMode::Replay {
+        exchanges, used, ..
+    } = &mut *mode
+    else {
+        return None;
+    };
+    let without_query = |u: &str| u.split('?').next().unwrap_or(u).to_string();
+    let index = exchanges
+        .iter()
+        .enumerate()
+        .position(|(i, e)| !used[i] && e.method == method && e.url == url)
+        .or_else(|| {
+            exchanges.iter().enumerate().position(|(i, e)| {
+                !used[i] && e.method == method && without_query(&e.url) == without_query(url)
+            })
+        })?;
+    used[index] = true;
+    Some(exchanges[index].clone())
+}
+
+// 将录制记录还原为响应
+pub(crate) fn to_response(exchange: &HttpExchange) -> reqwest::Response {
+    let mut response = http::Response::new(exchange.response_body.clone());
+    if let Ok(status) = http::StatusCode::from_u16(exchange.status) {
+        *response.status_mut() = status;
+    }
+    for (name, values) in &exchange.response_headers {
+        // 脱敏后响应体长度会变化，不还原长度相关的头
+        if name == "content-length" || name == "transfer-encoding" {
+            continue;
+        }
+        let Ok(name) = http::header::HeaderName::from_bytes(name.as_bytes()) else {
+            continue;
+        };
+        for value in values.split('\n') {
+            if let Ok(value) = http::HeaderValue::from_str(value) {
+                response.headers_mut().append(name.clone(), value);
+            }
+        }
+    }
+    reqwest::Response::from(response)
+}
+
+// 回放时找不到对应记录返回的响应
+pub(crate) fn missing_response(method: &str, url: &str) -> reqwest::Response {
+    log::warn!("回放记录中没有匹配的请求: {} {}", method, url);
+    let body = serde_json::json!({
+        "errno": -1,
+        "code": -1,
+        "msg": "回放记录中没有匹配的请求",
+        "message": "回放记录中没有匹配的请求",
+    })
+    .to_string();
+    let response = http::Response::builder()
+        .status(404)
+        .header("content-type", "application/json")
+        .body(body.clone())
+        .unwrap_or_else(|_| http::Response::new(body));
+    reqwest::Response::from(response)
+}
+
+pub fn redact_headers(headers: &HeaderMap) -> BTreeMap<String, String> {
+    let mut redacted = BTreeMap::new();
+    for (name, value) in headers {
+        let name = name.as_str().to_lowercase();
+        let value = value.to_str().unwrap_or_default();
+        let value = match name.as_str() {
+            "cookie" => redact_cookie_header(value),
+            "set-cookie" => redact_set_cookie(value),
+            _ if SENSITIVE_HEADERS.contains(&name.as_str()) => REDACTED.to_string(),
+            _ => value.to_string(),
+        };
+        // 同名响应头（如多个 set-cookie）合并保存
+        redacted
+            .entry(name)
+            .and_modify(|existing: &mut String| {
+                existing.push('\n');
+                existing.push_str(&value);
+            })
+            .or_insert(value);
+    }
+    redacted
+}
+
+// k=v; k=v 只保留名称
+fn redact_cookie_header(value: &str) -> String {
+    value
+        .split(';')
+        .filter_map(|part| part.trim().split_once('='))
+        .map(|(name, _)| format!("{}={}", name, REDACTED))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+// 只替换cookie的值，保留 Path、Expires 等属性
+fn redact_set_cookie(value: &str) -> String {
+    match value.split_once(';') {
+        Some((pair, attributes)) => format!("{};{}", redact_cookie_header(pair), attributes),
+        None => redact_cookie_header(value),
+    }
+}
+
+// 查询参数按字段脱敏
+pub fn redact_url(url: &str) -> String {
+    match url.split_once('?') {
+        Some((base, query)) => format!("{}?{}", base, redact_body(query)),
+        None => url.to_string(),
+    }
+}
+
+// JSON 和表单格式的请求体按字段脱敏，其他格式原样保存
+pub fn redact_body(body: &str) -> String {
+    if let Ok(mut json) = serde_json::from_str::<Value>(body) {
+        if json.is_object() || json.is_array() {
+            redact_json(&mut json);
+            return json.to_string();
+        }
+    }
+    if body.contains('=') && !body.contains(char::is_whitespace) {
+        return body
+            .split('&')
+            .map(|pair| match pair.split_once('=') {
+                Some((key, _)) if is_sensitive(key) => format!("{}={}", key, REDACTED),
+                _ => pair.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("&");
+    }
+    body.to_string()
+}
+
+fn redact_json(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if is_sensitive(key) && !value.is_object() && !value.is_array() {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact_json(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+fn is_sensitive(key: &str) -> bool {
+    SENSITIVE_FIELDS.contains(&key.to_lowercase().as_str())
+}
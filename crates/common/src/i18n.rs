@@ -0,0 +1,1249 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+// 界面语言
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Lang {
+    #[default]
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+    #[serde(rename = "en")]
+    En,
+}
+
+impl Lang {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Lang::ZhCn => "zh-CN",
+            Lang::En => "en",
+        }
+    }
+
+    // 兼容 zh、zh_CN、en-US 等写法
+    pub fn parse(code: &str) -> Option<Lang> {
+        let code = code.trim().to_lowercase().replace('_', "-");
+        if code.starts_with("zh") {
+            Some(Lang::ZhCn)
+        } else if code.starts_with("en") {
+            Some(Lang::En)
+        } else {
+            None
+        }
+    }
+}
+
+static CURRENT_LANG: AtomicU8 = AtomicU8::new(0);
+
+pub fn set_lang(lang: Lang) {
+    CURRENT_LANG.store(lang as u8, Ordering::Relaxed);
+}
+
+pub fn current_lang() -> Lang {
+    match CURRENT_LANG.load(Ordering::Relaxed) {
+        1 => Lang::En,
+        _ => Lang::ZhCn,
+    }
+}
+
+// 消息目录：(消息id, 简体中文, English)，参数用 {0}、{1} 占位
+const CATALOG: &[(&str, &str, &str)] = &[
+    // 通用
+    ("common.unknown", "未知", "Unknown"),
+    ("id_bind.none", "非实名", "No real-name"),
+    ("id_bind.per_order", "一单一证", "One ID per order"),
+    ("id_bind.per_person", "一人一证", "One ID per person"),
+    // 抢票结果
+    ("grab.success", "抢票成功", "Tickets grabbed successfully"),
+    (
+        "grab.confirm_retry_exhausted",
+        "确认订单失败，已达最大重试次数",
+        "Order confirmation failed after the maximum number of retries",
+    ),
+    (
+        "grab.order_retry_exhausted",
+        "下单失败，已达最大重试次数",
+        "Order creation failed after the maximum number of retries",
+    ),
+    (
+        "grab.deadline_exceeded",
+        "已超过抢票总时限，停止抢票",
+        "Stopped grabbing after reaching the overall deadline",
+    ),
+    (
+        "grab.over_budget",
+        "订单总价{0}（含快递费{1}）超出预算上限{2}，已放弃下单",
+        "Order total {0} (shipping {1}) exceeds the budget limit {2}, order abandoned",
+    ),
+    (
+        "grab.budget_no_project",
+        "设置了预算上限但未获取到项目信息，无法计算快递费，已放弃下单",
+        "A budget limit is set but project info is missing, shipping cannot be priced, order abandoned",
+    ),
+    (
+        "grab.fatal.sold_out",
+        "当前项目/类型/场次已停售",
+        "This project, ticket type or screen is no longer on sale",
+    ),
+    (
+        "grab.fatal.single_buyer_project",
+        "仅限1人抢票的项目，或抢票格式有误",
+        "This project allows one buyer only, or the request is malformed",
+    ),
+    (
+        "grab.fatal.no_buyer",
+        "没有配置购票人信息",
+        "No buyer information configured",
+    ),
+    (
+        "grab.fatal.unpaid_order",
+        "购票人存在待付款订单",
+        "The buyer already has an unpaid order",
+    ),
+    (
+        "grab.fatal.event_ended",
+        "活动已结束",
+        "The event has ended",
+    ),
+    (
+        "grab.fatal.one_buyer_only",
+        "当前项目只能选择一个购票人",
+        "Only one buyer can be selected for this project",
+    ),
+    (
+        "grab.fatal.id_bind",
+        "实名/非实名类型错误",
+        "Real-name type mismatch",
+    ),
+    (
+        "grab.countdown_failed",
+        "获取倒计时失败: {0}",
+        "Failed to get the sale countdown: {0}",
+    ),
+    (
+        "grab.captcha_exhausted",
+        "验证码处理失败，已达最大重试次数: {0}",
+        "Captcha handling failed, retry limit reached: {0}",
+    ),
+    (
+        "grab.token_exhausted",
+        "获取token失败，错误代码: {0}，错误信息：{1}",
+        "Failed to get the ticket token, error code {0}: {1}",
+    ),
+    (
+        "grab.watch_no_bind_unsupported",
+        "暂不支持抢非实名票捡漏模式",
+        "Leftover-ticket mode does not support non-real-name tickets yet",
+    ),
+    // 抢票进度
+    ("progress.started", "开始抢票", "Grabbing started"),
+    (
+        "progress.countdown",
+        "距离抢票时间还有{0}秒",
+        "{0} seconds until sales open",
+    ),
+    (
+        "progress.token_ok",
+        "获取抢票token成功",
+        "Got the ticket token",
+    ),
+    (
+        "progress.captcha",
+        "需要验证码，开始处理验证码",
+        "Captcha required, solving it",
+    ),
+    (
+        "progress.tickets_appeared",
+        "出现可售票种：{0}",
+        "Tickets became available: {0}",
+    ),
+    (
+        "progress.ticket_available",
+        "{0} {1}可售，开始抢票",
+        "{0} {1} is available, grabbing",
+    ),
+    (
+        "progress.confirm_ok",
+        "确认订单成功，准备下单",
+        "Order confirmed, placing the order",
+    ),
+    (
+        "progress.seat_failed",
+        "选座失败：{0}",
+        "Seat selection failed: {0}",
+    ),
+    // 推送模板
+    (
+        "push.grab_success.title",
+        "恭喜{0}抢票成功！",
+        "Got tickets for {0}!",
+    ),
+    (
+        "push.grab_success.body",
+        "抢票成功！\n项目：{0}\n场次：{1}\n票类型：{2}\n支付链接：{3}\n请尽快支付{4}元，以免支付超时导致票丢失\n如果觉得本项目好用，可前往https://github.com/biliticket/bili_ticket_rush 帮我们点个小星星star收藏本项目以防走丢\n本项目完全免费开源，仅供学习使用，开发组不承担使用本软件造成的一切后果",
+        "Tickets grabbed!\nProject: {0}\nScreen: {1}\nTicket: {2}\nPayment link: {3}\nPlease pay ¥{4} soon, or the order will time out and the tickets will be released\nIf you like this project, please star https://github.com/biliticket/bili_ticket_rush so you can find it again\nThis project is free and open source for learning purposes only; the developers take no responsibility for any consequences of using it",
+    ),
+    ("push.grab_failed.title", "抢票失败", "Ticket grab failed"),
+    (
+        "push.grab_failed.body",
+        "抢票已停止：{0}",
+        "Ticket grab stopped: {0}",
+    ),
+    (
+        "push.session_expired.title",
+        "账号登录已失效",
+        "Account session expired",
+    ),
+    (
+        "push.session_expired.body",
+        "账号{0}登录已失效，抢票已停止，请重新登录",
+        "The session of account {0} has expired and grabbing has stopped, please log in again",
+    ),
+    (
+        "push.unpaid_order.title",
+        "订单待支付",
+        "Order awaiting payment",
+    ),
+    (
+        "push.unpaid_order.body",
+        "{0}的订单{1}仍未支付，请尽快支付以免超时取消",
+        "Order {1} for {0} is still unpaid, please pay before it is cancelled",
+    ),
+    (
+        "push.sale_reminder.title",
+        "{0}还有{1}开售",
+        "{0} goes on sale in {1}",
+    ),
+    (
+        "push.sale_reminder.body",
+        "项目：{0}\n票档：{1}\n开售时间：{2}\n票价：{3}\n场次：{4}\n请提前登录账号、确认购票人",
+        "Project: {0}\nTier: {1}\nSale opens: {2}\nPrice: {3}\nScreens: {4}\nLog in and check your buyers in advance",
+    ),
+    (
+        "push.watcher_change.title",
+        "检测到回流票",
+        "Returned tickets detected",
+    ),
+    (
+        "push.watcher_change.body",
+        "{0}出现可售票种：\n{1}",
+        "Tickets became available for {0}:\n{1}",
+    ),
+    (
+        "push.task_started.title",
+        "抢票任务已开始",
+        "Grab task started",
+    ),
+    (
+        "push.task_started.body",
+        "账号：{0}\n项目：{1}\n任务ID：{2}",
+        "Account: {0}\nProject: {1}\nTask ID: {2}",
+    ),
+    (
+        "push.task_stopped.title",
+        "抢票任务已停止",
+        "Grab task stopped",
+    ),
+    (
+        "push.task_stopped.body",
+        "任务{0}已手动停止",
+        "Task {0} was stopped manually",
+    ),
+    (
+        "push.task_finished.body",
+        "任务{0}已结束",
+        "Task {0} has ended",
+    ),
+    ("push.test.title", "BTR 测试推送", "BTR test notification"),
+    (
+        "push.test.body",
+        "这是一条来自{0}的测试推送",
+        "This is a test notification sent via {0}",
+    ),
+    ("hook.test.project", "测试项目", "Test project"),
+    ("hook.test.screen", "测试场次", "Test screen"),
+    ("hook.test.ticket", "测试票种", "Test ticket"),
+    (
+        "hook.test.message",
+        "这是一条测试事件",
+        "This is a test event",
+    ),
+    // 推送结果
+    ("push.result.success", "推送成功", "Sent"),
+    (
+        "push.result.not_configured",
+        "未填写Token或地址",
+        "Token or address not configured",
+    ),
+    (
+        "push.result.http_failed",
+        "推送失败，状态码: {0}",
+        "Push failed with status {0}",
+    ),
+    (
+        "push.result.body_failed",
+        "推送失败，{0}",
+        "Push failed, {0}",
+    ),
+    (
+        "push.result.body_error",
+        "错误码 {0}: {1}",
+        "error code {0}: {1}",
+    ),
+    (
+        "push.result.request_failed",
+        "推送失败: {0}",
+        "Push failed: {0}",
+    ),
+    (
+        "push.result.read_failed",
+        "读取响应失败: {0}",
+        "Failed to read response: {0}",
+    ),
+    (
+        "push.result.client_failed",
+        "创建HTTP客户端失败: {0}",
+        "Failed to create HTTP client: {0}",
+    ),
+    (
+        "push.result.invalid_token",
+        "Token格式错误: {0}",
+        "Invalid token: {0}",
+    ),
+    (
+        "push.result.invalid_channel",
+        "无效的推送渠道",
+        "Invalid push channel",
+    ),
+    (
+        "push.result.smtp_unimplemented",
+        "SMTP推送功能未实现",
+        "SMTP push is not implemented",
+    ),
+    (
+        "push.desktop.failed",
+        "显示桌面通知失败: {0}",
+        "Failed to show desktop notification: {0}",
+    ),
+    ("push.desktop.open", "打开", "Open"),
+    (
+        "push.summary.no_channel",
+        "没有已启用且已配置的推送渠道",
+        "No push channel is enabled and configured",
+    ),
+    (
+        "push.summary.all_failed",
+        "{0} 成功 / {1} 失败。失败详情: {2}",
+        "{0} succeeded / {1} failed. Details: {2}",
+    ),
+    (
+        "push.summary.ok",
+        "{0} 个渠道推送成功",
+        "Sent to {0} channel(s)",
+    ),
+    (
+        "push.summary.channel_error",
+        "{0}推送出错: {1}",
+        "{0} failed: {1}",
+    ),
+    (
+        "push.disabled",
+        "推送未开启",
+        "Push notifications are disabled",
+    ),
+    (
+        "push.unknown_channel",
+        "未知的推送渠道: {0}",
+        "Unknown push channel: {0}",
+    ),
+    // 推送渠道与事件名称
+    ("channel.all", "全部渠道", "All channels"),
+    ("channel.bark", "Bark", "Bark"),
+    ("channel.pushplus", "PushPlus", "PushPlus"),
+    ("channel.fangtang", "方糖", "ServerChan"),
+    ("channel.dingtalk", "钉钉", "DingTalk"),
+    ("channel.wechat", "企业微信", "WeCom"),
+    ("channel.smtp", "SMTP邮件", "SMTP email"),
+    ("channel.gotify", "Gotify", "Gotify"),
+    ("channel.desktop", "桌面通知", "Desktop notification"),
+    ("event.grab_success", "抢票成功", "Grab succeeded"),
+    ("event.grab_failed", "抢票失败", "Grab failed"),
+    ("event.session_expired", "登录失效", "Session expired"),
+    ("event.task_started", "任务开始", "Task started"),
+    ("event.task_stopped", "任务停止", "Task stopped"),
+    ("event.unpaid_order", "待支付提醒", "Unpaid order reminder"),
+    ("event.watcher_change", "回流变化", "Returned tickets"),
+    ("event.sale_reminder", "开售提醒", "Sale reminder"),
+    // 开售日历
+    ("sale.event.summary", "{0} {1} 开售", "{0} {1} on sale"),
+    (
+        "sale.event.description",
+        "项目ID: {0}\n票价: {1}\n场次: {2}",
+        "Project id: {0}\nPrice: {1}\nScreens: {2}",
+    ),
+    ("sale.offset.days", "{0}天", "{0} day(s)"),
+    ("sale.offset.hours", "{0}小时", "{0} hour(s)"),
+    ("sale.offset.minutes", "{0}分钟", "{0} minute(s)"),
+    ("sale.no_account", "没有可用的账号", "No account available"),
+    // 订单日历
+    (
+        "order.event.description",
+        "场次: {0}\n订单号: {1}\n账号: {2} ({3})\n数量: {4}",
+        "Screen: {0}\nOrder: {1}\nAccount: {2} ({3})\nQuantity: {4}",
+    ),
+    // 提交前检查
+    (
+        "validate.account_not_login",
+        "账号{0}未登录或cookie为空",
+        "Account {0} is not logged in or has no cookie",
+    ),
+    (
+        "validate.account_no_csrf",
+        "账号{0}缺少csrf，下单可能失败",
+        "Account {0} has no csrf token, ordering may fail",
+    ),
+    (
+        "validate.project_missing",
+        "未获取项目信息，跳过场次、票种和开售时间检查",
+        "Project info not loaded, skipping screen, ticket and sale time checks",
+    ),
+    (
+        "validate.project_mismatch",
+        "已获取的项目信息({0})与任务项目{1}不一致，请重新获取",
+        "Loaded project info ({0}) does not match task project {1}, please reload it",
+    ),
+    (
+        "validate.id_bind_mismatch",
+        "项目为{0}，但选择的是{1}购票人",
+        "The project requires \"{0}\" but the selected buyers are \"{1}\"",
+    ),
+    (
+        "validate.tel_format",
+        "手机号{0}格式可能有误",
+        "Phone number {0} looks invalid",
+    ),
+    (
+        "validate.no_bind_buyer_missing",
+        "未填写非实名购票人姓名和手机号",
+        "Name and phone of the non-real-name buyer are missing",
+    ),
+    (
+        "validate.buyer_missing",
+        "未选择实名购票人",
+        "No real-name buyer selected",
+    ),
+    (
+        "validate.buyer_unverified",
+        "购票人{0}未通过实名认证",
+        "Buyer {0} has not passed real-name verification",
+    ),
+    (
+        "validate.buyer_count_mismatch",
+        "购票数量{0}与购票人数{1}不一致",
+        "Ticket count {0} does not match the number of buyers {1}",
+    ),
+    (
+        "validate.buyer_type_unknown",
+        "未知的购票人类型：{0}",
+        "Unknown buyer type: {0}",
+    ),
+    (
+        "validate.no_candidates",
+        "当前没有符合偏好的可售票种，捡漏模式将持续等待回流",
+        "No ticket matching the preferences is on sale, leftover mode will keep waiting",
+    ),
+    (
+        "validate.sale_ended",
+        "已于{0}停止售票",
+        "Sales ended at {0}",
+    ),
+    (
+        "validate.sale_started",
+        "已过开售时间，定时模式将立即开始抢票",
+        "Sales have already started, scheduled mode will start grabbing immediately",
+    ),
+    (
+        "validate.sale_not_started",
+        "{0}才开售，直接抢票会一直失败，建议使用定时模式",
+        "Sales start at {0}, direct mode will keep failing until then, use scheduled mode instead",
+    ),
+    (
+        "validate.address_missing",
+        "该场次需快递配送，但没有选择收货地址",
+        "This screen ships paper tickets but no delivery address was chosen",
+    ),
+    (
+        "validate.address_selected",
+        "配送方式: {0}，快递费{1}，收货地址: {2}",
+        "Delivery: {0}, shipping {1}, address: {2}",
+    ),
+    (
+        "validate.seat_auto",
+        "该场次需要选座，未指定座位或区域，将自动选择任意区域的可选座位",
+        "This screen uses reserved seating and no seats or areas were chosen, free seats in any area will be picked",
+    ),
+    (
+        "validate.over_budget",
+        "订单总价{0}超出预算上限{1}",
+        "Order total {0} exceeds the budget limit {1}",
+    ),
+    (
+        "validate.budget_no_project",
+        "设置了预算上限但未获取项目信息，抢票时会重新获取，获取失败将放弃下单",
+        "A budget limit is set but project info is not loaded, it will be fetched when grabbing and the order is abandoned if that fails",
+    ),
+    (
+        "validate.screen_not_found",
+        "项目中不存在场次{0}",
+        "Screen {0} does not exist in the project",
+    ),
+    (
+        "validate.ticket_not_found",
+        "场次中不存在票种{0}",
+        "Ticket {0} does not exist in the screen",
+    ),
+    (
+        "validate.ticket_unavailable",
+        "票种{0}当前不可购买（{1}）",
+        "Ticket {0} cannot be bought right now ({1})",
+    ),
+    (
+        "validate.push_no_method",
+        "已开启推送但未选择任何推送渠道",
+        "Push is enabled but no channel is selected",
+    ),
+    (
+        "validate.push_unconfigured",
+        "已开启推送但所有渠道都未配置，抢票结果不会推送",
+        "Push is enabled but no channel is configured, results will not be pushed",
+    ),
+    (
+        "validate.rejected",
+        "抢票配置有误：{0}",
+        "Invalid grab configuration: {0}",
+    ),
+    // 试运行检查
+    ("dry_run.check.account", "账号", "Account"),
+    ("dry_run.check.project", "项目信息", "Project"),
+    ("dry_run.check.id_bind", "实名类型", "Real-name type"),
+    ("dry_run.check.screen", "场次", "Screen"),
+    ("dry_run.check.ticket", "票种", "Ticket"),
+    ("dry_run.check.buyer", "购票人", "Buyers"),
+    ("dry_run.check.count", "购票数量", "Ticket count"),
+    ("dry_run.check.seat", "选座", "Seats"),
+    ("dry_run.check.address", "收货地址", "Delivery address"),
+    ("dry_run.check.token", "获取token", "Ticket token"),
+    ("dry_run.check.confirm", "确认订单", "Order confirmation"),
+    ("dry_run.check.budget", "预算", "Budget"),
+    (
+        "dry_run.account_ok",
+        "{0}（UID {1}）已登录",
+        "{0} (UID {1}) is logged in",
+    ),
+    (
+        "dry_run.account_not_login",
+        "账号未登录或登录已过期",
+        "The account is not logged in or the session has expired",
+    ),
+    (
+        "dry_run.account_mismatch",
+        "登录的账号UID {0} 与任务UID {1} 不一致",
+        "Logged-in UID {0} does not match the task UID {1}",
+    ),
+    (
+        "dry_run.account_failed",
+        "检查登录状态失败：{0}",
+        "Failed to check the login state: {0}",
+    ),
+    ("dry_run.name_status", "{0}（{1}）", "{0} ({1})"),
+    (
+        "dry_run.id_bind",
+        "项目实名类型{0}，任务购票人类型{1}",
+        "Project real-name type {0}, task buyer type {1}",
+    ),
+    (
+        "dry_run.screen_missing",
+        "项目中不存在场次{0}",
+        "Screen {0} not found in the project",
+    ),
+    ("dry_run.ticket_ok", "{0} {1}（{2}）", "{0} {1} ({2})"),
+    (
+        "dry_run.ticket_missing",
+        "场次中不存在票种{0}",
+        "Ticket {0} not found in the screen",
+    ),
+    (
+        "dry_run.no_bind_buyer",
+        "非实名购票人：{0}",
+        "Non-real-name buyer: {0}",
+    ),
+    ("dry_run.buyers", "实名购票人：{0}", "Real-name buyers: {0}"),
+    (
+        "dry_run.buyers_missing",
+        "账号下不存在购票人：{0}",
+        "Buyers not found in the account: {0}",
+    ),
+    (
+        "dry_run.buyer_list_failed",
+        "获取购票人列表失败：{0}",
+        "Failed to fetch the buyer list: {0}",
+    ),
+    (
+        "dry_run.count",
+        "购票数量{0}，购票人{1}位",
+        "{0} tickets, {1} buyers",
+    ),
+    (
+        "dry_run.seats",
+        "{0}（剩余可选{1}个座位）",
+        "{0} ({1} seats still free)",
+    ),
+    (
+        "dry_run.seat_map_failed",
+        "获取座位图失败：{0}",
+        "Failed to fetch the seat map: {0}",
+    ),
+    ("dry_run.delivery", "{0}，快递费{1}", "{0}, shipping {1}"),
+    (
+        "dry_run.address",
+        "{0}，收货地址ID {1}",
+        "{0}, delivery address id {1}",
+    ),
+    (
+        "dry_run.address_not_chosen",
+        "{0}，未选择收货地址",
+        "{0}, no delivery address chosen",
+    ),
+    (
+        "dry_run.address_list_failed",
+        "获取收货地址失败：{0}",
+        "Failed to fetch delivery addresses: {0}",
+    ),
+    (
+        "dry_run.token_ok",
+        "获取抢票token成功",
+        "Got the ticket token",
+    ),
+    (
+        "dry_run.token_failed",
+        "错误代码{0}：{1}（未开售时失败属正常现象）",
+        "Error code {0}: {1} (expected before sales open)",
+    ),
+    (
+        "dry_run.confirm_ok",
+        "{0} {1} x{2}，总价{3}",
+        "{0} {1} x{2}, total {3}",
+    ),
+    (
+        "dry_run.budget",
+        "总价{0}，预算上限{1}",
+        "Total {0}, budget limit {1}",
+    ),
+    // 提交抢票任务
+    (
+        "task.no_project",
+        "请先选择项目",
+        "Please select a project first",
+    ),
+    (
+        "task.no_account",
+        "请先添加账号",
+        "Please add an account first",
+    ),
+    (
+        "task.no_active_account",
+        "没有可用的账号，请确保至少有一个账号是激活状态",
+        "No usable account, make sure at least one account is active",
+    ),
+    (
+        "task.account_uninitialized",
+        "账号未初始化，请重新添加账号",
+        "Account is not initialized, please add it again",
+    ),
+    (
+        "task.no_bind_buyer_missing",
+        "请先设置非实名购票人信息",
+        "Please set the non-real-name buyer first",
+    ),
+    (
+        "task.buyer_missing",
+        "请先选择实名购票人信息",
+        "Please select real-name buyers first",
+    ),
+    (
+        "task.invalid_buyer_type",
+        "无效的购票人类型",
+        "Invalid buyer type",
+    ),
+    (
+        "task.submit_failed",
+        "提交抢票任务失败: {0}",
+        "Failed to submit grab task: {0}",
+    ),
+    ("task.cancelled", "任务已取消", "Task cancelled"),
+    (
+        "task.dry_run_submit",
+        "试运行请求请使用 submit_dry_run 提交",
+        "Dry-run requests must be submitted with submit_dry_run",
+    ),
+    ("task.not_found", "任务不存在", "Task not found"),
+    (
+        "task.orders_ok",
+        "获取全部订单成功: {0}",
+        "Fetched all orders: {0}",
+    ),
+    (
+        "task.project_ok",
+        "项目{0}请求成功",
+        "Project request succeeded ({0})",
+    ),
+    ("task.buyers_ok", "购票人信息请求成功", "Fetched buyer info"),
+    (
+        "task.already_finished",
+        "任务已结束",
+        "Task has already ended",
+    ),
+    (
+        "task.result_taken",
+        "任务结果已被取走",
+        "The task result was already taken",
+    ),
+    (
+        "task.no_result",
+        "任务{0}已结束但没有返回结果",
+        "Task {0} ended without a result",
+    ),
+    // 抢票配置
+    (
+        "profile.name_empty",
+        "抢票配置名称不能为空",
+        "Grab profile name must not be empty",
+    ),
+    (
+        "profile.not_found",
+        "未找到抢票配置: {0}",
+        "Grab profile not found: {0}",
+    ),
+    (
+        "profile.save_failed",
+        "保存抢票配置失败: {0}",
+        "Failed to save grab profile: {0}",
+    ),
+    (
+        "profile.delete_save_failed",
+        "删除抢票配置后保存失败: {0}",
+        "Failed to save after deleting grab profile: {0}",
+    ),
+    // 选座
+    ("seat.name", "{0}排{1}座", "Row {0} Seat {1}"),
+    (
+        "seat.count_mismatch",
+        "指定了{0}个座位，但购票数量为{1}",
+        "{0} seats were chosen but {1} tickets are being bought",
+    ),
+    (
+        "seat.not_found",
+        "座位图中没有座位: {0}",
+        "Seat not in the seat map: {0}",
+    ),
+    (
+        "seat.unavailable",
+        "座位已售出: {0}",
+        "Seat already taken: {0}",
+    ),
+    (
+        "seat.sold_out",
+        "偏好区域内没有足够的可选座位",
+        "Not enough free seats in the preferred areas",
+    ),
+    // 收货地址
+    (
+        "address.not_found",
+        "账号中没有收货地址: {0}",
+        "Delivery address not found in the account: {0}",
+    ),
+    (
+        "address.empty",
+        "账号没有保存收货地址，请先在会员购中添加",
+        "The account has no saved delivery address, add one on the Bilibili ticket site first",
+    ),
+    ("delivery.electronic", "电子票", "E-ticket"),
+    ("delivery.express", "快递配送", "Shipped by express"),
+    ("delivery.on_site", "现场取票", "Pick up at venue"),
+    (
+        "delivery.unknown",
+        "未知配送方式({0})",
+        "Unknown delivery type ({0})",
+    ),
+    // 账号导入导出
+    ("import.empty", "导入内容为空", "Import content is empty"),
+    (
+        "import.invalid_json",
+        "JSON格式错误: {0}",
+        "Invalid JSON: {0}",
+    ),
+    (
+        "import.unknown_format",
+        "无法识别的cookie导出格式",
+        "Unrecognized cookie export format",
+    ),
+    (
+        "import.no_session",
+        "没有找到可导入的账号",
+        "No account found to import",
+    ),
+    (
+        "import.missing_sessdata",
+        "缺少B站域名下的SESSDATA，请确认已登录",
+        "No SESSDATA for bilibili.com, make sure you are logged in",
+    ),
+    (
+        "import.not_logged_in",
+        "cookie已失效或未登录",
+        "Cookie expired or not logged in",
+    ),
+    (
+        "import.validate_failed",
+        "校验失败: {0}",
+        "Validation failed: {0}",
+    ),
+    ("import.success", "导入成功", "Imported"),
+    (
+        "import.updated",
+        "账号已存在，已更新cookie",
+        "Account already exists, cookie updated",
+    ),
+    (
+        "import.nothing_to_export",
+        "没有可导出的账号",
+        "No account to export",
+    ),
+    // 命令行
+    (
+        "cli.usage",
+        "用法:\n  cli project <项目ID>    查看项目详情与票价\n  cli validate <项目ID> <场次ID> <票种ID> [--mode N] [购票人ID...]\n                          提交抢票前检查账号、购票人、开售时间等配置\n  cli login [--invert]    在终端显示二维码扫码登录（浅色背景终端加 --invert）\n  cli orders [list]       列出最近的订单\n  cli orders show <订单号>\n                          查看订单详情（购票人、座位、配送、退票）\n  cli orders ics <文件>   将所有账号的已支付订单导出为日历（.ics）\n  cli profile [list]      列出已保存的抢票配置\n  cli profile add <名称> <项目ID> <场次ID> <票种ID> [--mode N] [--uid UID] [--budget 金额] [--skip 词1,词2] [--seats 座位ID,...] [--areas 区域1,区域2] [--address 地址ID] [--buyer-type 0|1|2|auto] [--no-bind-buyer 姓名,手机号] [--prefer 场次ID:票种ID:最高价:关键词1+关键词2] [--token-attempts N] [--confirm-attempts N] [--order-attempts N] [--backoff fixed|linear|exponential] [--deadline 秒] [购票人ID...]\n                          保存抢票配置（账号、项目、场次、票种、购票人、模式、跳过词、预算、选座、收货地址、购票人类型、票种偏好、重试策略）\n  cli profile update <配置ID> [--name 名称] [--project 项目ID] [--screen 场次ID] [--ticket 票种ID] [add 的选项] [购票人ID...]\n                          修改抢票配置，只更新给出的字段\n  cli profile check <配置ID>\n                          按抢票配置检查账号、购票人、开售时间等\n  cli profile delete <配置ID>\n                          删除抢票配置\n  cli seats <项目ID> <场次ID> <票种ID> [--areas 区域1,区域2] [--count N]\n                          查看选座项目的可选座位，指定 --count 时预览自动选座结果\n  cli addresses           列出账号保存的收货地址（纸质票快递配送使用）\n  cli sales [list]        列出已保存项目中待开售的票档\n  cli sales ics <文件>    将待开售票档的开售时间导出为日历（.ics）\n  cli accounts list       列出已保存的账号\n  cli accounts import <文件>\n                          从 cookies.txt、浏览器插件导出的JSON或账号导出文件导入账号\n  cli accounts export <文件> [UID...]\n                          导出账号（不指定UID时导出全部）\n  cli diagnostics [文件]  生成脱敏后的诊断包（日志、配置摘要、任务历史、错误码统计），用于问题反馈\n\n设置环境变量 BTR_LANG=en 可切换为英文输出\n设置 BTR_HTTP_RECORD=1 录制请求与响应（已脱敏），BTR_HTTP_REPLAY=<录制文件> 离线回放",
+        "Usage:\n  cli project <project id>    show project details and prices\n  cli validate <project id> <screen id> <ticket id> [--mode N] [buyer id...]\n                              check account, buyers, sale time etc. before grabbing\n  cli login [--invert]        log in by scanning a QR code in the terminal (--invert for light backgrounds)\n  cli orders [list]           list recent orders\n  cli orders show <order id>  show order details (buyers, seats, delivery, refund)\n  cli orders ics <file>       export paid orders of all accounts as a calendar (.ics)\n  cli profile [list]          list saved grab profiles\n  cli profile add <name> <project id> <screen id> <ticket id> [--mode N] [--uid UID] [--budget amount] [--skip word1,word2] [--seats seat id,...] [--areas area1,area2] [--address address id] [--buyer-type 0|1|2|auto] [--no-bind-buyer name,phone] [--prefer screen id:ticket id:max price:keyword1+keyword2] [--token-attempts N] [--confirm-attempts N] [--order-attempts N] [--backoff fixed|linear|exponential] [--deadline seconds] [buyer id...]\n                              save a grab profile (account, project, screen, ticket, buyers, mode, skip words, budget, seats, delivery address, buyer type, ticket preferences, retry policy)\n  cli profile update <profile id> [--name name] [--project project id] [--screen screen id] [--ticket ticket id] [add options] [buyer id...]\n                              change a grab profile, only the given fields are updated\n  cli profile check <profile id>\n                              check account, buyers, sale time etc. for a grab profile\n  cli profile delete <profile id>\n                              delete a grab profile\n  cli seats <project id> <screen id> <ticket id> [--areas area1,area2] [--count N]\n                              show free seats of a reserved-seating show, --count previews automatic seat picking\n  cli addresses               list the account's saved delivery addresses (used for shipped paper tickets)\n  cli sales [list]            list upcoming ticket sales of saved projects\n  cli sales ics <file>        export upcoming sale openings as a calendar (.ics)\n  cli accounts list           list saved accounts\n  cli accounts import <file>  import accounts from cookies.txt, browser extension JSON or an account export\n  cli accounts export <file> [uid...]\n                              export accounts (all when no uid is given)\n  cli diagnostics [file]      build a redacted diagnostics bundle (logs, config summary, task history, errno stats) for bug reports\n\nSet BTR_LANG=zh-CN to switch to Chinese output\nSet BTR_HTTP_RECORD=1 to record (redacted) requests and responses, BTR_HTTP_REPLAY=<session file> to replay them offline",
+    ),
+    (
+        "daemon.usage",
+        "用法: daemon [--port 端口] [--token TOKEN]\n  只监听 127.0.0.1，默认端口 18650\n  所有接口需要请求头 Authorization: Bearer <token>\n  未指定 --token 或环境变量 BTR_DAEMON_TOKEN 时，使用 daemon.token 文件中的token（不存在则自动生成）",
+        "Usage: daemon [--port PORT] [--token TOKEN]\n  listens on 127.0.0.1 only, port 18650 by default\n  every endpoint requires the header Authorization: Bearer <token>\n  without --token or BTR_DAEMON_TOKEN, the token in daemon.token is used (generated if missing)",
+    ),
+    ("daemon.unauthorized", "token无效", "Invalid token"),
+    (
+        "daemon.account_not_found",
+        "账号不存在",
+        "Account not found",
+    ),
+    (
+        "daemon.account_not_ready",
+        "账号未初始化",
+        "Account is not initialized",
+    ),
+    (
+        "daemon.invalid_grab_mode",
+        "grab_mode 只能是 0、1、2",
+        "grab_mode must be 0, 1 or 2",
+    ),
+    (
+        "daemon.task_manager_lock_failed",
+        "任务管理器锁定失败",
+        "Failed to lock the task manager",
+    ),
+    (
+        "daemon.task_list_lock_failed",
+        "任务列表锁定失败",
+        "Failed to lock the task list",
+    ),
+    (
+        "daemon.event_list_lock_failed",
+        "事件列表锁定失败",
+        "Failed to lock the event list",
+    ),
+    (
+        "daemon.log_lock_failed",
+        "日志锁定失败",
+        "Failed to lock the logs",
+    ),
+    ("daemon.invalid_port", "无效的端口", "Invalid port"),
+    ("daemon.missing_token", "缺少token", "Missing token"),
+    (
+        "daemon.unknown_arg",
+        "未知参数: {0}",
+        "Unknown argument: {0}",
+    ),
+    (
+        "daemon.token_save_failed",
+        "保存token失败: {0}",
+        "Failed to save token: {0}",
+    ),
+    (
+        "daemon.listen_failed",
+        "无法监听 {0}: {1}",
+        "Cannot listen on {0}: {1}",
+    ),
+    ("cli.error", "错误: {0}", "Error: {0}"),
+    ("cli.missing_file", "缺少文件路径", "Missing file path"),
+    ("cli.invalid_uid", "无效的UID: {0}", "Invalid uid: {0}"),
+    (
+        "cli.unknown_subcommand",
+        "未知的子命令: {0}",
+        "Unknown subcommand: {0}",
+    ),
+    (
+        "cli.read_file_failed",
+        "读取文件{0}失败: {1}",
+        "Failed to read {0}: {1}",
+    ),
+    (
+        "cli.write_file_failed",
+        "写入文件{0}失败: {1}",
+        "Failed to write {0}: {1}",
+    ),
+    ("cli.account.active", "已启用", "active"),
+    ("cli.account.inactive", "未启用", "inactive"),
+    ("cli.import.ok", "成功", "OK"),
+    ("cli.import.failed", "失败", "FAILED"),
+    (
+        "cli.import.summary",
+        "导入完成：成功 {0} 个，失败 {1} 个",
+        "Import finished: {0} succeeded, {1} failed",
+    ),
+    (
+        "cli.export.done",
+        "账号已导出到 {0}",
+        "Accounts exported to {0}",
+    ),
+    (
+        "cli.diagnostics.done",
+        "诊断包已生成: {0}",
+        "Diagnostics bundle written to {0}",
+    ),
+    ("cli.missing_order_id", "缺少订单号", "Missing order id"),
+    (
+        "cli.calendar.done",
+        "已导出{0}个订单到 {1}",
+        "Exported {0} orders to {1}",
+    ),
+    (
+        "cli.sales.done",
+        "已导出{0}个开售时间到 {1}",
+        "Exported {0} sale openings to {1}",
+    ),
+    (
+        "cli.sales.empty",
+        "已保存的项目中没有待开售的票档",
+        "No upcoming sales in saved projects",
+    ),
+    (
+        "cli.sales.no_project",
+        "项目列表为空，请先在界面中添加项目",
+        "No saved projects, add some in the GUI first",
+    ),
+    ("cli.order.title", "订单 {0}  {1}", "Order {0}  {1}"),
+    (
+        "cli.order.ticket",
+        "票档: {0}  单价: {1}  数量: {2}",
+        "Ticket: {0}  Price: {1}  Qty: {2}",
+    ),
+    (
+        "cli.order.amount",
+        "订单金额: {0}  实付: {1}",
+        "Total: {0}  Paid: {1}",
+    ),
+    ("cli.order.ctime", "下单时间: {0}", "Created: {0}"),
+    ("cli.order.pay_deadline", "支付截止: {0}", "Pay before: {0}"),
+    ("cli.order.buyers", "购票人:", "Buyers:"),
+    ("cli.order.seats", "座位:", "Seats:"),
+    ("cli.order.delivery", "配送信息:", "Delivery:"),
+    ("cli.order.refundable", "可退票", "Refundable"),
+    ("cli.order.not_refundable", "不可退票", "Not refundable"),
+    (
+        "cli.login.scan_hint",
+        "请使用哔哩哔哩手机客户端扫描二维码，或在手机上打开以下链接:",
+        "Scan the QR code with the bilibili mobile app, or open this link on your phone:",
+    ),
+    (
+        "cli.login.pending",
+        "二维码已生成，等待扫描...",
+        "Waiting for scan...",
+    ),
+    (
+        "cli.login.scanning",
+        "二维码已扫描，请在手机上确认登录",
+        "Scanned, please confirm the login on your phone",
+    ),
+    (
+        "cli.login.confirming",
+        "已确认，正在登录...",
+        "Confirmed, logging in...",
+    ),
+    (
+        "cli.login.expired",
+        "二维码已过期，正在重新生成",
+        "QR code expired, generating a new one",
+    ),
+    ("cli.login.failed", "登录失败: {0}", "Login failed: {0}"),
+    (
+        "cli.login.too_many_refresh",
+        "二维码多次过期，已停止登录",
+        "QR code expired too many times, giving up",
+    ),
+    (
+        "cli.login.success",
+        "登录成功: {0} (UID {1})，账号已保存",
+        "Logged in as {0} (uid {1}), account saved",
+    ),
+    (
+        "cli.no_account",
+        "没有可用账号，请先登录",
+        "No account available, please log in first",
+    ),
+    (
+        "cli.account_uninitialized",
+        "账号未初始化请求客户端",
+        "Account HTTP client is not initialized",
+    ),
+    ("cli.missing_project_id", "缺少项目ID", "Missing project id"),
+    (
+        "cli.missing_profile_id",
+        "缺少抢票配置ID",
+        "Missing profile id",
+    ),
+    (
+        "cli.missing_profile_args",
+        "用法: cli profile add <名称> <项目ID> <场次ID> <票种ID> [--mode N] [--uid UID] [--budget 金额] [--skip 词1,词2] [--seats 座位ID,...] [--areas 区域1,区域2] [--address 地址ID] [--buyer-type 0|1|2|auto] [--no-bind-buyer 姓名,手机号] [--prefer 场次ID:票种ID:最高价:关键词1+关键词2] [--token-attempts N] [--confirm-attempts N] [--order-attempts N] [--backoff fixed|linear|exponential] [--deadline 秒] [购票人ID...]",
+        "Usage: cli profile add <name> <project id> <screen id> <ticket id> [--mode N] [--uid UID] [--budget amount] [--skip word1,word2] [--seats seat id,...] [--areas area1,area2] [--address address id] [--buyer-type 0|1|2|auto] [--no-bind-buyer name,phone] [--prefer screen id:ticket id:max price:keyword1+keyword2] [--token-attempts N] [--confirm-attempts N] [--order-attempts N] [--backoff fixed|linear|exponential] [--deadline seconds] [buyer id...]",
+    ),
+    ("cli.profile.empty", "暂无抢票配置", "No grab profiles"),
+    (
+        "cli.invalid_buyer_type",
+        "--buyer-type 需要 0、1、2 或 auto",
+        "--buyer-type expects 0, 1, 2 or auto",
+    ),
+    (
+        "cli.invalid_no_bind_buyer",
+        "--no-bind-buyer 需要 姓名,手机号",
+        "--no-bind-buyer expects name,phone",
+    ),
+    (
+        "cli.invalid_preference",
+        "无效的票种偏好: {0}，格式为 场次ID:票种ID:最高价:关键词1+关键词2",
+        "Invalid ticket preference: {0}, expected screen id:ticket id:max price:keyword1+keyword2",
+    ),
+    (
+        "cli.invalid_number",
+        "{0} 需要一个数字",
+        "{0} expects a number",
+    ),
+    (
+        "cli.invalid_backoff",
+        "--backoff 需要 fixed、linear 或 exponential",
+        "--backoff expects fixed, linear or exponential",
+    ),
+    (
+        "cli.profile.saved",
+        "已保存抢票配置 {0}（ID: {1}）",
+        "Saved grab profile {0} (id: {1})",
+    ),
+    (
+        "cli.profile.deleted",
+        "已删除抢票配置 {0}",
+        "Deleted grab profile {0}",
+    ),
+    ("cli.profile.any_account", "启用的账号", "active account"),
+    ("cli.profile.mode", "模式{0}", "mode {0}"),
+    (
+        "cli.missing_seats_args",
+        "用法: cli seats <项目ID> <场次ID> <票种ID> [--areas 区域1,区域2] [--count N]",
+        "Usage: cli seats <project id> <screen id> <ticket id> [--areas area1,area2] [--count N]",
+    ),
+    ("cli.seats.empty", "座位图为空", "The seat map is empty"),
+    (
+        "cli.seats.area",
+        "{0}（区域ID: {1}）可选 {2} 个座位",
+        "{0} (area id: {1}), {2} seats free",
+    ),
+    (
+        "cli.seats.picked",
+        "自动选座结果: {0}",
+        "Seats that would be picked: {0}",
+    ),
+    (
+        "cli.seats.invalid_count",
+        "无效的座位数量: {0}",
+        "Invalid seat count: {0}",
+    ),
+    (
+        "cli.addresses.empty",
+        "账号没有保存收货地址",
+        "The account has no saved delivery addresses",
+    ),
+    ("cli.addresses.default", "默认", "default"),
+    (
+        "cli.missing_validate_args",
+        "缺少参数：需要项目ID、场次ID和票种ID",
+        "Missing arguments: project id, screen id and ticket id are required",
+    ),
+    (
+        "cli.invalid_mode",
+        "--mode 需要 0、1 或 2",
+        "--mode expects 0, 1 or 2",
+    ),
+    ("cli.show_time", "演出时间: {0}", "Show time: {0}"),
+    (
+        "cli.venue_with_address",
+        "场馆: {0} ({1})",
+        "Venue: {0} ({1})",
+    ),
+    ("cli.venue", "场馆: {0}", "Venue: {0}"),
+    ("cli.sale_time", "售票时间: {0}", "Sale time: {0}"),
+    ("cli.id_bind", "实名制: {0}", "Real-name: {0}"),
+    ("cli.express_fee", "快递费: {0}", "Shipping fee: {0}"),
+    ("cli.section.base_info", "基础信息", "Basic info"),
+    ("cli.section.purchase_notes", "购票须知", "Purchase notes"),
+    ("cli.section.real_name_rules", "实名规则", "Real-name rules"),
+    ("cli.section.refund_policy", "退票政策", "Refund policy"),
+    (
+        "cli.screen",
+        "[场次] {0} (ID: {1}) {2} {3} {4}",
+        "[Screen] {0} (ID: {1}) {2} {3} {4}",
+    ),
+    ("cli.column.ticket_id", "票种ID", "Ticket ID"),
+    ("cli.column.price", "价格", "Price"),
+    ("cli.column.status", "状态", "Status"),
+    ("cli.column.ticket", "票种", "Ticket"),
+    (
+        "cli.validate.passed",
+        "检查通过，未发现问题",
+        "All checks passed",
+    ),
+    (
+        "cli.validate.failed",
+        "抢票配置检查未通过",
+        "Grab configuration check failed",
+    ),
+    ("cli.validate.error", "错误", "ERROR"),
+    ("cli.validate.warning", "警告", "WARN"),
+    (
+        "cli.validate.summary",
+        "共 {0} 个错误，{1} 个警告",
+        "{0} error(s), {1} warning(s)",
+    ),
+    // 图形界面
+    ("gui.status.idle", "空闲", "Idle"),
+    ("gui.status.running", "运行", "Running"),
+    ("gui.status.grabbing", "抢票", "Grabbing"),
+    ("gui.login_method.qrcode", "扫码登录", "QR code login"),
+    (
+        "gui.save_config_failed",
+        "保存配置失败: {0}",
+        "Failed to save config: {0}",
+    ),
+    (
+        "gui.qrcode.generate_failed",
+        "生成二维码失败: {0}",
+        "Failed to generate QR code: {0}",
+    ),
+    (
+        "gui.qrcode.image_failed",
+        "转换图片失败: {0}",
+        "Failed to convert image: {0}",
+    ),
+    (
+        "gui.qrcode.submit_failed",
+        "提交二维码登录任务失败: {0}",
+        "Failed to submit QR code login task: {0}",
+    ),
+    (
+        "gui.qrcode.generated",
+        "二维码生成成功，请使用B站APP扫描",
+        "QR code generated, scan it with the Bilibili app",
+    ),
+    (
+        "gui.qrcode.pending",
+        "二维码已生成，等待扫描",
+        "QR code generated, waiting for scan",
+    ),
+    (
+        "gui.qrcode.scanning",
+        "二维码已扫描，等待确认",
+        "QR code scanned, waiting for confirmation",
+    ),
+    (
+        "gui.qrcode.confirming",
+        "二维码已确认，正在登录",
+        "QR code confirmed, logging in",
+    ),
+    ("gui.qrcode.success", "登录成功", "Logged in"),
+    ("gui.qrcode.failed", "登录失败: {0}", "Login failed: {0}"),
+    ("gui.qrcode.expired", "二维码已过期", "QR code expired"),
+    (
+        "gui.project.exists",
+        "项目ID已存在",
+        "Project id already exists",
+    ),
+    (
+        "gui.project.serialize_failed",
+        "序列化项目失败: {0}",
+        "Failed to serialize project: {0}",
+    ),
+    (
+        "gui.project.save_failed",
+        "保存项目失败: {0}",
+        "Failed to save project: {0}",
+    ),
+    (
+        "gui.project.parse_failed",
+        "解析项目列表失败: {0}",
+        "Failed to parse project list: {0}",
+    ),
+    (
+        "gui.project.list_missing",
+        "项目列表不存在",
+        "Project list does not exist",
+    ),
+    (
+        "gui.project.not_found",
+        "未找到指定ID的项目",
+        "No project with this id",
+    ),
+    (
+        "gui.project.delete_save_failed",
+        "删除项目后保存失败: {0}",
+        "Failed to save after deleting project: {0}",
+    ),
+    (
+        "gui.project.list_invalid",
+        "项目列表格式错误",
+        "Invalid project list format",
+    ),
+    // HTTP录制
+    (
+        "http_record.replaying",
+        "正在回放HTTP录制，无法切换录制",
+        "Replaying an HTTP recording, recording cannot be toggled",
+    ),
+];
+
+// 当前语言下的消息，目录中没有的id原样返回
+pub fn t(id: &'static str) -> &'static str {
+    let lang = current_lang();
+    CATALOG
+        .iter()
+        .find(|(key, _, _)| *key == id)
+        .map(|(_, zh, en)| match lang {
+            Lang::ZhCn => *zh,
+            Lang::En => *en,
+        })
+        .unwrap_or(id)
+}
+
+// 带参数的消息，依次替换 {0}、{1}...
+pub fn tr(id: &'static str, args: &[&(dyn Display + Sync)]) -> String {
+    let mut text = t(id).to_string();
+    for (index, arg) in args.iter().enumerate() {
+        text = text.replace(&format!("{{{}}}", index), &arg.to_string());
+    }
+    text
+}
@@ -0,0 +1,137 @@
+use crate::i18n::tr;
+use crate::project_detail::VenueDesc;
+use crate::show_orderlist::Order;
+use chrono::{Duration, NaiveDateTime, TimeZone, Utc};
+
+// 接口返回的时间均为北京时间
+const CHINA_OFFSET_HOURS: i64 = 8;
+// 场次没有结束时间时按此时长计算
+const DEFAULT_EVENT_HOURS: i64 = 3;
+// 每行最多75字节，超出后折行
+const MAX_LINE_OCTETS: usize = 75;
+
+// 日历中的一个事件
+#[derive(Clone, Debug)]
+pub struct CalendarEvent {
+    pub uid: String,
+    pub start: NaiveDateTime, //UTC
+    pub end: NaiveDateTime,   //UTC
+    pub summary: String,
+    pub location: String,
+    pub description: String,
+}
+
+// 场次时间，支持 "2024-07-06 18:00:00"、"2024-07-06 18:00" 和秒级时间戳，返回UTC时间
+pub fn parse_screen_time(text: &str) -> Option<NaiveDateTime> {
+    let text = text.trim();
+    if let Ok(timestamp) = text.parse::<i64>() {
+        return Utc
+            .timestamp_opt(timestamp, 0)
+            .single()
+            .map(|t| t.naive_utc());
+    }
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y/%m/%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .map(|local| local - Duration::hours(CHINA_OFFSET_HOURS))
+}
+
+// 订单对应的场次事件，场次时间无法解析时返回 None
+pub fn order_event(
+    order: &Order,
+    account_name: &str,
+    uid: i64,
+    venue: Option<&VenueDesc>,
+) -> Option<CalendarEvent> {
+    let item = &order.item_info;
+    let start = parse_screen_time(&item.screen_start_time)?;
+    let end = parse_screen_time(&item.screen_end_time)
+        .filter(|end| *end > start)
+        .unwrap_or(start + Duration::hours(DEFAULT_EVENT_HOURS));
+    let location = match venue {
+        Some(venue) if !venue.address.is_empty() => format!("{} {}", venue.name, venue.address),
+        Some(venue) => venue.name.clone(),
+        None => String::new(),
+    };
+    Some(CalendarEvent {
+        uid: format!("{}@btr", order.order_id),
+        start,
+        end,
+        summary: format!("{} - {}", item.name, account_name),
+        location,
+        description: tr(
+            "order.event.description",
+            &[
+                &item.screen_name,
+                &order.order_id,
+                &account_name,
+                &uid,
+                &order.count,
+            ],
+        ),
+    })
+}
+
+// 生成 iCalendar 文本，按 uid 去重
+pub fn build_calendar(events: &[CalendarEvent]) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//biliticket//BTR//CN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:BTR".to_string(),
+    ];
+    let stamp = format_time(&Utc::now().naive_utc());
+    let mut seen = std::collections::HashSet::new();
+    for event in events {
+        if !seen.insert(event.uid.as_str()) {
+            continue;
+        }
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", escape(&event.uid)));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("DTSTART:{}", format_time(&event.start)));
+        lines.push(format!("DTEND:{}", format_time(&event.end)));
+        lines.push(format!("SUMMARY:{}", escape(&event.summary)));
+        if !event.location.is_empty() {
+            lines.push(format!("LOCATION:{}", escape(&event.location)));
+        }
+        lines.push(format!("DESCRIPTION:{}", escape(&event.description)));
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut calendar = String::new();
+    for line in lines {
+        calendar.push_str(&fold(&line));
+        calendar.push_str("\r\n");
+    }
+    calendar
+}
+
+fn format_time(time: &NaiveDateTime) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+// 超长行按字节折行，不拆分多字节字符，续行以空格开头
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded
+}
@@ -1,14 +1,33 @@
 pub mod account;
+pub mod address;
 pub mod captcha;
+pub mod cookie_import;
+pub mod desktop_notify;
+pub mod diagnostics;
+pub mod grab_profile;
+pub mod hooks;
+pub mod http_record;
 pub mod http_utils;
+pub mod i18n;
+pub mod ics;
 pub mod login;
+pub mod money;
+pub mod notification;
+pub mod pacer;
+pub mod project_detail;
 pub mod push;
 pub mod record_log;
+pub mod retry;
+pub mod sale_calendar;
+pub mod seat;
 pub mod show_orderlist;
+pub mod task_handle;
 pub mod taskmanager;
 pub mod ticket;
+pub mod ticket_preference;
 pub mod utility;
 pub mod utils;
+pub mod validate;
 
 pub mod cookie_manager;
 pub mod gen_cp;
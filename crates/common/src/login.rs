@@ -2,7 +2,7 @@ use crate::account::Account;
 use crate::account::add_account;
 use crate::captcha::LocalCaptcha;
 use crate::captcha::captcha;
-use crate::http_utils::{request_get, request_get_sync, request_post};
+use crate::http_utils::{request_get, request_post};
 use crate::utility::CustomConfig;
 use reqwest::Client;
 use serde_json::json;
@@ -39,29 +39,25 @@ pub enum SendLoginSmsStatus {
     Failed(String),
 }
 
-pub fn qrcode_login(client: &Client) -> Result<String, String> {
-    // 创建一个临时的运行时来执行异步代码
-    let rt = tokio::runtime::Runtime::new().unwrap();
-    rt.block_on(async {
-        let response = request_get(
-            client,
-            "https://passport.bilibili.com/x/passport-login/web/qrcode/generate",
-            None,
-        )
+pub async fn qrcode_login(client: &Client) -> Result<String, String> {
+    let response = request_get(
+        client,
+        "https://passport.bilibili.com/x/passport-login/web/qrcode/generate",
+        None,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let json = response
+        .json::<serde_json::Value>()
         .await
         .map_err(|e| e.to_string())?;
 
-        let json = response
-            .json::<serde_json::Value>()
-            .await
-            .map_err(|e| e.to_string())?;
-
-        if let Some(qrcode_key) = json["data"]["qrcode_key"].as_str() {
-            Ok(qrcode_key.to_string())
-        } else {
-            Err("无法获取二维码URL".to_string())
-        }
-    })
+    if let Some(qrcode_key) = json["data"]["qrcode_key"].as_str() {
+        Ok(qrcode_key.to_string())
+    } else {
+        Err("无法获取二维码URL".to_string())
+    }
 }
 pub fn password_login(username: &str, password: &str) -> Result<String, String> {
     Err("暂不支持账号密码登录".to_string())
@@ -206,8 +202,8 @@ pub async fn sms_login(
     Err("短信登录失败".to_string())
 }
 
-pub fn cookie_login(cookie: &str, client: &Client, ua: &str) -> Result<Account, String> {
-    match add_account(cookie, client, ua) {
+pub async fn cookie_login(cookie: &str, client: &Client, ua: &str) -> Result<Account, String> {
+    match add_account(cookie, client, ua).await {
         Ok(account) => {
             log::info!("ck登录成功");
             Ok(account)
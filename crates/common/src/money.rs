@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::{Add, Mul};
+
+// 金额，内部以分为单位存储，避免浮点和整数除法丢失精度
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct Money(i64);
+
+impl Money {
+    pub const ZERO: Money = Money(0);
+
+    pub fn from_fen(fen: i64) -> Self {
+        Money(fen)
+    }
+
+    pub fn fen(&self) -> i64 {
+        self.0
+    }
+
+    // 解析以元为单位的字符串，最多两位小数，如 "680"、"680.5"、"¥680.50"
+    pub fn parse_yuan(input: &str) -> Result<Self, String> {
+        let text = input
+            .trim()
+            .trim_start_matches('¥')
+            .trim_end_matches('元')
+            .trim();
+        if text.is_empty() {
+            return Err("金额不能为空".to_string());
+        }
+        let (negative, text) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text),
+        };
+        let (yuan_part, fen_part) = match text.split_once('.') {
+            Some((yuan, fen)) => (yuan, fen),
+            None => (text, ""),
+        };
+        if fen_part.len() > 2
+            || !yuan_part.chars().all(|c| c.is_ascii_digit())
+            || !fen_part.chars().all(|c| c.is_ascii_digit())
+            || (yuan_part.is_empty() && fen_part.is_empty())
+        {
+            return Err(format!("无效的金额: {}", input));
+        }
+        let yuan: i64 = if yuan_part.is_empty() {
+            0
+        } else {
+            yuan_part
+                .parse()
+                .map_err(|_| format!("无效的金额: {}", input))?
+        };
+        let fen: i64 = format!("{:0<2}", fen_part)
+            .parse()
+            .map_err(|_| format!("无效的金额: {}", input))?;
+        let total = yuan
+            .checked_mul(100)
+            .and_then(|v| v.checked_add(fen))
+            .ok_or_else(|| format!("金额过大: {}", input))?;
+        Ok(Money(if negative { -total } else { total }))
+    }
+
+    // 以元为单位的字符串，固定两位小数，如 "680.50"
+    pub fn yuan_string(&self) -> String {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.unsigned_abs();
+        format!("{}{}.{:02}", sign, abs / 100, abs % 100)
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "¥{}", self.yuan_string())
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+    fn add(self, rhs: Money) -> Money {
+        Money(self.0 + rhs.0)
+    }
+}
+
+impl Mul<i64> for Money {
+    type Output = Money;
+    fn mul(self, rhs: i64) -> Money {
+        Money(self.0 * rhs)
+    }
+}
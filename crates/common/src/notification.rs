@@ -0,0 +1,152 @@
+use crate::i18n::t;
+use crate::taskmanager::PushType;
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+
+// 通知事件类型
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyEvent {
+    GrabSuccess,    //抢票成功
+    GrabFailed,     //抢票彻底失败（致命错误或达到重试上限）
+    SessionExpired, //账号登录失效
+    TaskStarted,    //抢票任务开始
+    TaskStopped,    //抢票任务停止
+    UnpaidOrder,    //订单未支付提醒
+    WatcherChange,  //捡漏监控到可售票种变化
+    SaleReminder,   //已保存项目即将开售
+}
+
+impl NotifyEvent {
+    pub const ALL: [NotifyEvent; 8] = [
+        NotifyEvent::GrabSuccess,
+        NotifyEvent::GrabFailed,
+        NotifyEvent::SessionExpired,
+        NotifyEvent::TaskStarted,
+        NotifyEvent::TaskStopped,
+        NotifyEvent::UnpaidOrder,
+        NotifyEvent::WatcherChange,
+        NotifyEvent::SaleReminder,
+    ];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            NotifyEvent::GrabSuccess => t("event.grab_success"),
+            NotifyEvent::GrabFailed => t("event.grab_failed"),
+            NotifyEvent::SessionExpired => t("event.session_expired"),
+            NotifyEvent::TaskStarted => t("event.task_started"),
+            NotifyEvent::TaskStopped => t("event.task_stopped"),
+            NotifyEvent::UnpaidOrder => t("event.unpaid_order"),
+            NotifyEvent::WatcherChange => t("event.watcher_change"),
+            NotifyEvent::SaleReminder => t("event.sale_reminder"),
+        }
+    }
+
+    // 未配置路由规则时，只推送重要事件，避免任务开始/停止等消息刷屏
+    pub fn enabled_by_default(&self) -> bool {
+        matches!(
+            self,
+            NotifyEvent::GrabSuccess
+                | NotifyEvent::GrabFailed
+                | NotifyEvent::SessionExpired
+                | NotifyEvent::UnpaidOrder
+                | NotifyEvent::SaleReminder
+        )
+    }
+
+    pub fn default_priority(&self) -> NotifyPriority {
+        match self {
+            NotifyEvent::GrabSuccess | NotifyEvent::SessionExpired | NotifyEvent::UnpaidOrder => {
+                NotifyPriority::TimeSensitive
+            }
+            NotifyEvent::GrabFailed | NotifyEvent::WatcherChange | NotifyEvent::SaleReminder => {
+                NotifyPriority::Active
+            }
+            NotifyEvent::TaskStarted | NotifyEvent::TaskStopped => NotifyPriority::Passive,
+        }
+    }
+}
+
+// 推送优先级，对应 Bark 的 level 和 Gotify 的 priority
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyPriority {
+    Passive, //仅加入通知列表，不亮屏
+    #[default]
+    Active, //立即显示
+    TimeSensitive, //时效性通知，专注模式下也会显示
+}
+
+impl NotifyPriority {
+    pub fn bark_level(&self) -> &'static str {
+        match self {
+            NotifyPriority::Passive => "passive",
+            NotifyPriority::Active => "active",
+            NotifyPriority::TimeSensitive => "timeSensitive",
+        }
+    }
+
+    pub fn gotify_priority(&self) -> u8 {
+        match self {
+            NotifyPriority::Passive => 2,
+            NotifyPriority::Active => 5,
+            NotifyPriority::TimeSensitive => 9,
+        }
+    }
+}
+
+// 路由规则：某个渠道接收哪些事件、以什么优先级推送
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RoutingRule {
+    pub channel: PushType,
+    pub events: Vec<NotifyEvent>,
+    #[serde(default)]
+    pub priority: NotifyPriority,
+}
+
+// 免打扰时段，时段内只推送 bypass_events 中的事件
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub enabled: bool,
+    pub start: String, //开始时间 HH:MM
+    pub end: String,   //结束时间 HH:MM，早于开始时间表示跨天
+    #[serde(default)]
+    pub bypass_events: Vec<NotifyEvent>,
+}
+
+impl Default for QuietHours {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start: "23:00".to_string(),
+            end: "08:00".to_string(),
+            bypass_events: vec![NotifyEvent::GrabSuccess],
+        }
+    }
+}
+
+impl QuietHours {
+    pub fn parse_time(text: &str) -> Result<NaiveTime, String> {
+        NaiveTime::parse_from_str(text.trim(), "%H:%M")
+            .map_err(|_| format!("无效的时间: {}，格式应为 HH:MM", text))
+    }
+
+    pub fn is_quiet(&self, now: NaiveTime) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let (Ok(start), Ok(end)) = (Self::parse_time(&self.start), Self::parse_time(&self.end))
+        else {
+            return false;
+        };
+        if start <= end {
+            now >= start && now < end
+        } else {
+            now >= start || now < end
+        }
+    }
+
+    pub fn blocks(&self, event: NotifyEvent, now: NaiveTime) -> bool {
+        self.is_quiet(now) && !self.bypass_events.contains(&event)
+    }
+}
@@ -0,0 +1,128 @@
+use crate::utils::Config;
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+// 请求节流设置，所有账号共用，每个账号单独计时
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PacerConfig {
+    pub enabled: bool,
+    pub min_interval_ms: u64, //同一账号两次请求之间的最小间隔
+    pub backoff_base_ms: u64, //遇到限流或服务器错误后的首次等待
+    pub backoff_max_ms: u64,  //连续出错时等待时间的上限
+}
+
+// 默认关闭，抢票循环自身的等待时间保持不变
+impl Default for PacerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_interval_ms: 200,
+            backoff_base_ms: 1000,
+            backoff_max_ms: 30_000,
+        }
+    }
+}
+
+impl PacerConfig {
+    pub fn load(config: &Config) -> Self {
+        serde_json::from_value(config["request_pacing"].clone()).unwrap_or_default()
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref PACER_CONFIG: RwLock<PacerConfig> = RwLock::new(PacerConfig::default());
+}
+
+pub fn set_config(config: PacerConfig) {
+    if let Ok(mut current) = PACER_CONFIG.write() {
+        *current = config;
+    }
+}
+
+pub fn config() -> PacerConfig {
+    PACER_CONFIG
+        .read()
+        .map(|config| config.clone())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Default)]
+struct PacerState {
+    next_allowed: Option<Instant>,
+    failures: u32, //连续限流/服务器错误次数
+}
+
+// 单个账号的请求节奏控制，由 CookieManager 在发送请求前后调用
+#[derive(Debug, Default)]
+pub struct RequestPacer {
+    state: Mutex<PacerState>,
+}
+
+impl RequestPacer {
+    // 等到允许发送下一个请求，并发请求按先后顺序排队
+    pub async fn acquire(&self) {
+        let config = config();
+        if !config.enabled {
+            return;
+        }
+        let wait = {
+            let Ok(mut state) = self.state.lock() else {
+                return;
+            };
+            let now = Instant::now();
+            let slot = state.next_allowed.map_or(now, |t| t.max(now));
+            state.next_allowed = Some(slot + Duration::from_millis(config.min_interval_ms));
+            slot - now
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    // 根据响应状态码调整节奏，限流和服务器错误时指数退避
+    pub fn on_status(&self, status: u16, url: &str) {
+        let config = config();
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        let Some(reason) = throttle_reason(status) else {
+            state.failures = 0;
+            return;
+        };
+        if !config.enabled {
+            log::warn!("{}: {}", reason, api_path(url));
+            return;
+        }
+        state.failures += 1;
+        let backoff = config
+            .backoff_base_ms
+            .saturating_mul(1 << (state.failures - 1).min(16))
+            .min(config.backoff_max_ms.max(config.backoff_base_ms));
+        let resume = Instant::now() + Duration::from_millis(backoff);
+        state.next_allowed = Some(state.next_allowed.map_or(resume, |t| t.max(resume)));
+        log::warn!(
+            "{}: {}，第{}次，{}毫秒后再发送请求",
+            reason,
+            api_path(url),
+            state.failures,
+            backoff
+        );
+    }
+}
+
+// 需要退避的状态码
+fn throttle_reason(status: u16) -> Option<String> {
+    match status {
+        412 => Some("请求被拦截(412)，可能触发了风控".to_string()),
+        429 => Some("请求过于频繁(429)".to_string()),
+        500..=599 => Some(format!("服务器错误({})", status)),
+        _ => None,
+    }
+}
+
+// 日志只保留接口路径，不输出查询参数
+fn api_path(url: &str) -> &str {
+    url.split('?').next().unwrap_or(url)
+}
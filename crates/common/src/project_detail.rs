@@ -0,0 +1,232 @@
+use crate::address::delivery_type_name;
+use crate::money::Money;
+use crate::ticket::{BaseInfoItem, DescribeList, ModuleItem, TicketInfo};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// performance_desc 中已知模块的类型化结果，未识别的模块保留原始数据
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "data", rename_all = "snake_case")]
+pub enum DescModule {
+    BaseInfo(Vec<BaseInfoItem>),      //基础信息
+    Venue(VenueDesc),                 //场馆/地址
+    PurchaseNotes(Vec<BaseInfoItem>), //购票须知
+    RealNameRules(Vec<BaseInfoItem>), //实名规则
+    RefundPolicy(Vec<BaseInfoItem>),  //退票政策
+    Unknown(ModuleItem),              //未知模块
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct VenueDesc {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default, alias = "address_detail")]
+    pub address: String,
+}
+
+// 场次票价表
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScreenPriceTable {
+    pub screen_id: usize,
+    pub name: String,
+    pub start_time: String,
+    pub sale_flag: String,
+    pub clickable: bool,
+    pub delivery: String, //配送方式
+    pub tickets: Vec<TicketPriceRow>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TicketPriceRow {
+    pub ticket_id: usize,
+    pub desc: String,
+    pub price: usize,       //票价(分)
+    pub price_text: String, //票价(元)
+    pub sale_flag: String,
+    pub clickable: bool,
+}
+
+// 项目详情页所需的全部信息
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProjectDetail {
+    pub id: i32,
+    pub name: String,
+    pub show_time: String,
+    pub sale_time: String,
+    pub venue: Option<VenueDesc>,
+    pub id_bind: usize,
+    pub express_fee: usize,
+    pub is_hot: bool,
+    pub base_info: Vec<BaseInfoItem>,
+    pub purchase_notes: Vec<BaseInfoItem>,
+    pub real_name_rules: Vec<BaseInfoItem>,
+    pub refund_policy: Vec<BaseInfoItem>,
+    pub unknown_modules: Vec<ModuleItem>,
+    pub screens: Vec<ScreenPriceTable>,
+}
+
+impl ModuleItem {
+    // 按模块名解析为类型化模块，解析失败时保留原始数据
+    pub fn parse(&self) -> DescModule {
+        let items = || parse_items(&self.details, self.module_name.as_deref());
+        let parsed = match self.module.as_str() {
+            "base_info" => items().map(DescModule::BaseInfo),
+            "venue" | "venue_info" | "address" => parse_venue(&self.details).map(DescModule::Venue),
+            "notice" | "buy_notice" | "purchase_notice" | "ticket_notice" => {
+                items().map(DescModule::PurchaseNotes)
+            }
+            "real_name" | "realname" | "real_name_notice" => items().map(DescModule::RealNameRules),
+            "refund" | "refund_policy" | "refund_rule" | "refund_notice" => {
+                items().map(DescModule::RefundPolicy)
+            }
+            _ => None,
+        };
+        parsed.unwrap_or_else(|| DescModule::Unknown(self.clone()))
+    }
+}
+
+impl DescribeList {
+    pub fn modules(&self) -> Vec<DescModule> {
+        self.list.iter().map(ModuleItem::parse).collect()
+    }
+}
+
+impl TicketInfo {
+    // 汇总项目详情，base_info 中的实名/退票/场馆条目会被归入对应分类
+    pub fn project_detail(&self) -> ProjectDetail {
+        let mut detail = ProjectDetail {
+            id: self.id,
+            name: self.name.clone(),
+            show_time: format!(
+                "{} ~ {}",
+                format_timestamp(self.start_time),
+                format_timestamp(self.end_time)
+            ),
+            sale_time: format!(
+                "{} ~ {}",
+                format_timestamp(self.sale_begin),
+                format_timestamp(self.sale_end)
+            ),
+            venue: self.venue_info.clone(),
+            id_bind: self.id_bind,
+            express_fee: self.express_fee,
+            is_hot: self.hot_project,
+            base_info: Vec::new(),
+            purchase_notes: Vec::new(),
+            real_name_rules: Vec::new(),
+            refund_policy: Vec::new(),
+            unknown_modules: Vec::new(),
+            screens: self.screen_list.iter().map(screen_price_table).collect(),
+        };
+
+        let modules = self
+            .performance_desc
+            .as_ref()
+            .map(|desc| desc.modules())
+            .unwrap_or_default();
+        for module in modules {
+            match module {
+                DescModule::BaseInfo(items) => {
+                    for item in items {
+                        if item.title.contains("实名") {
+                            detail.real_name_rules.push(item);
+                        } else if item.title.contains("退") {
+                            detail.refund_policy.push(item);
+                        } else if detail.venue.is_none()
+                            && (item.title.contains("场馆") || item.title.contains("地址"))
+                        {
+                            detail.venue = Some(VenueDesc {
+                                name: item.content.clone(),
+                                address: String::new(),
+                            });
+                            detail.base_info.push(item);
+                        } else {
+                            detail.base_info.push(item);
+                        }
+                    }
+                }
+                DescModule::Venue(venue) => detail.venue = Some(venue),
+                DescModule::PurchaseNotes(items) => detail.purchase_notes.extend(items),
+                DescModule::RealNameRules(items) => detail.real_name_rules.extend(items),
+                DescModule::RefundPolicy(items) => detail.refund_policy.extend(items),
+                DescModule::Unknown(item) => detail.unknown_modules.push(item),
+            }
+        }
+        detail
+    }
+}
+
+// details 可能是 [{title, content}] 数组，也可能是单个字符串
+fn parse_items(details: &Value, module_name: Option<&str>) -> Option<Vec<BaseInfoItem>> {
+    match details {
+        Value::Array(list) => {
+            let items: Vec<BaseInfoItem> = list
+                .iter()
+                .filter_map(|v| match v {
+                    Value::String(s) => Some(BaseInfoItem {
+                        title: module_name.unwrap_or_default().to_string(),
+                        content: s.clone(),
+                    }),
+                    _ => serde_json::from_value::<BaseInfoItem>(v.clone()).ok(),
+                })
+                .collect();
+            if items.is_empty() && !list.is_empty() {
+                None
+            } else {
+                Some(items)
+            }
+        }
+        Value::String(s) => Some(vec![BaseInfoItem {
+            title: module_name.unwrap_or_default().to_string(),
+            content: s.clone(),
+        }]),
+        _ => None,
+    }
+}
+
+fn parse_venue(details: &Value) -> Option<VenueDesc> {
+    match details {
+        Value::Object(_) => serde_json::from_value::<VenueDesc>(details.clone()).ok(),
+        Value::String(s) => Some(VenueDesc {
+            name: s.clone(),
+            address: String::new(),
+        }),
+        _ => None,
+    }
+}
+
+fn screen_price_table(screen: &crate::ticket::ScreenInfo) -> ScreenPriceTable {
+    ScreenPriceTable {
+        screen_id: screen.id,
+        name: screen.name.clone(),
+        start_time: format_timestamp(screen.start_time as i64),
+        sale_flag: screen.sale_flag.display_name.clone(),
+        clickable: screen.clickable,
+        delivery: delivery_type_name(screen.delivery_type),
+        tickets: screen
+            .ticket_list
+            .iter()
+            .map(|ticket| TicketPriceRow {
+                ticket_id: ticket.id,
+                desc: ticket.desc.clone(),
+                price: ticket.price,
+                price_text: Money::from_fen(ticket.price as i64).yuan_string(),
+                sale_flag: ticket.sale_flag.display_name.clone(),
+                clickable: ticket.clickable,
+            })
+            .collect(),
+    }
+}
+
+pub fn format_timestamp(ts: i64) -> String {
+    if ts <= 0 {
+        return "未知".to_string();
+    }
+    match chrono::DateTime::from_timestamp(ts, 0) {
+        Some(t) => t
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string(),
+        None => "未知".to_string(),
+    }
+}
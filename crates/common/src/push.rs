@@ -1,7 +1,19 @@
-use crate::taskmanager::{PushRequest, PushType, TaskManager, TaskRequest};
-use reqwest::Client;
+use crate::desktop_notify;
+use crate::i18n::{t, tr};
+use crate::notification::{NotifyEvent, NotifyPriority, QuietHours, RoutingRule};
+use crate::taskmanager::{PushRequest, PushType, TaskManager};
+use reqwest::{Client, RequestBuilder};
 use serde::{Deserialize, Serialize};
 
+// 单个渠道的推送结果
+#[derive(Clone, Debug, Serialize)]
+pub struct ChannelPushResult {
+    pub channel: PushType,
+    pub success: bool,
+    pub status_code: Option<u16>, //HTTP状态码，请求未发出时为None
+    pub message: String,
+}
+
 //推送token
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PushConfig {
@@ -14,6 +26,10 @@ pub struct PushConfig {
     pub wechat_token: String,
     pub gotify_config: GotifyConfig,
     pub smtp_config: SmtpConfig,
+    #[serde(default)]
+    pub routing_rules: Vec<RoutingRule>, //按事件类型路由到渠道，为空时重要事件推送到全部已启用渠道
+    #[serde(default)]
+    pub quiet_hours: QuietHours, //免打扰时段
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -61,6 +77,8 @@ impl PushConfig {
             wechat_token: String::new(),
             gotify_config: GotifyConfig::new(),
             smtp_config: SmtpConfig::new(),
+            routing_rules: Vec::new(),
+            quiet_hours: QuietHours::default(),
         }
     }
 
@@ -74,16 +92,17 @@ impl PushConfig {
         if !self.enabled {
             return;
         }
-        let push_request = TaskRequest::PushRequest(PushRequest {
+        let push_request = PushRequest {
             title: title.to_string(),
             message: message.to_string(),
             jump_url: jump_url.clone(),
             push_config: self.clone(),
             push_type: PushType::All,
-        });
-        match task_manager.submit_task(push_request) {
-            Ok(task_id) => {
-                log::debug!("提交全渠道推送任务成功，任务ID: {}", task_id);
+            event: None,
+        };
+        match task_manager.submit_push(push_request) {
+            Ok(handle) => {
+                log::debug!("提交全渠道推送任务成功，任务ID: {}", handle.id());
             }
             Err(e) => {
                 log::error!("提交推送任务失败: {}", e);
@@ -91,120 +110,197 @@ impl PushConfig {
         }
     }
 
-    pub async fn push_all_async(
+    // 按路由规则提交事件通知，由任务管理器异步推送
+    pub fn notify(
         &self,
+        event: NotifyEvent,
         title: &str,
         message: &str,
         jump_url: &Option<String>,
-    ) -> (bool, String) {
-        let mut success_count = 0;
-        let mut failure_count = 0;
-        let mut failures = Vec::new();
-
-        // 检查是否启用了Bark推送
-        if self.enabled_methods.contains(&"bark".to_string()) && !self.bark_token.is_empty() {
-            let (success, msg) = self.push_bark(title, message).await;
-            if success {
-                success_count += 1;
-            } else {
-                failure_count += 1;
-                failures.push(format!("Bark推送出错: {}", msg));
-            }
+        task_manager: &mut dyn TaskManager,
+    ) {
+        if !self.enabled {
+            return;
         }
-
-        // 检查是否启用了PushPlus推送
-        if self.enabled_methods.contains(&"pushplus".to_string()) && !self.pushplus_token.is_empty()
-        {
-            let (success, msg) = self.push_pushplus(title, message).await;
-            if success {
-                success_count += 1;
-            } else {
-                failure_count += 1;
-                failures.push(format!("PushPlus推送出错: {}", msg));
-            }
+        let push_request = PushRequest {
+            title: title.to_string(),
+            message: message.to_string(),
+            jump_url: jump_url.clone(),
+            push_config: self.clone(),
+            push_type: PushType::All,
+            event: Some(event),
+        };
+        if let Err(e) = task_manager.submit_push(push_request) {
+            log::error!("提交{}通知失败: {}", event.display_name(), e);
         }
+    }
 
-        // 检查是否启用了方糖推送
-        if self.enabled_methods.contains(&"fangtang".to_string()) && !self.fangtang_token.is_empty()
-        {
-            let (success, msg) = self.push_fangtang(title, message).await;
-            if success {
-                success_count += 1;
-            } else {
-                failure_count += 1;
-                failures.push(format!("Fangtang推送出错: {}", msg));
-            }
+    // 按路由规则直接推送事件通知
+    pub async fn notify_async(
+        &self,
+        event: NotifyEvent,
+        title: &str,
+        message: &str,
+        jump_url: &Option<String>,
+    ) -> Vec<ChannelPushResult> {
+        if !self.enabled {
+            return Vec::new();
+        }
+        let now = chrono::Local::now().time();
+        if self.quiet_hours.blocks(event, now) {
+            log::info!("免打扰时段，跳过{}通知", event.display_name());
+            return Vec::new();
         }
+        let routes = self.routes(event);
+        self.push_routes(routes, title, message, jump_url).await
+    }
 
-        // 检查是否启用了钉钉推送
-        if self.enabled_methods.contains(&"dingtalk".to_string()) && !self.dingtalk_token.is_empty()
-        {
-            let (success, msg) = self.push_dingtalk(title, message).await;
-            if success {
-                success_count += 1;
-            } else {
-                failure_count += 1;
-                failures.push(format!("Dingtalk推送出错: {}", msg));
+    // 事件对应的渠道和优先级，只包含已启用且已配置的渠道
+    pub fn routes(&self, event: NotifyEvent) -> Vec<(PushType, NotifyPriority)> {
+        let available = |channel: &PushType| {
+            self.enabled_methods.iter().any(|m| m == channel.method())
+                && self.is_channel_configured(channel)
+        };
+        if self.routing_rules.is_empty() {
+            if !event.enabled_by_default() {
+                return Vec::new();
             }
+            return PushType::CHANNELS
+                .iter()
+                .filter(|c| available(c))
+                .map(|c| (c.clone(), event.default_priority()))
+                .collect();
         }
-
-        // 检查是否启用了微信推送
-        if self.enabled_methods.contains(&"wechat".to_string()) && !self.wechat_token.is_empty() {
-            let (success, msg) = self.push_wechat(title, message).await;
-            if success {
-                success_count += 1;
-            } else {
-                failure_count += 1;
-                failures.push(format!("WeChat推送出错: {}", msg));
+        let mut routes: Vec<(PushType, NotifyPriority)> = Vec::new();
+        for rule in &self.routing_rules {
+            if !rule.events.contains(&event) || !available(&rule.channel) {
+                continue;
+            }
+            // 同一渠道命中多条规则时只推送一次，取第一条规则的优先级
+            if routes.iter().any(|(c, _)| *c == rule.channel) {
+                continue;
             }
+            routes.push((rule.channel.clone(), rule.priority));
         }
+        routes
+    }
 
-        // 检查是否启用了SMTP推送
-        if self.enabled_methods.contains(&"smtp".to_string())
-            && !self.smtp_config.smtp_server.is_empty()
-        {
-            let (success, msg) = self.push_smtp(title, message).await;
-            if success {
-                success_count += 1;
-            } else {
-                failure_count += 1;
-                failures.push(format!("SMTP推送出错: {}", msg));
+    // 全部渠道推送并汇总结果
+    pub async fn push_all_async(
+        &self,
+        title: &str,
+        message: &str,
+        jump_url: &Option<String>,
+    ) -> (bool, String) {
+        let results = self
+            .push_channels(&PushType::All, title, message, jump_url)
+            .await;
+        summarize_results(&results)
+    }
+
+    // 渠道是否已填写必要的token/地址
+    pub fn is_channel_configured(&self, channel: &PushType) -> bool {
+        match channel {
+            PushType::All => false,
+            PushType::Bark => !self.bark_token.is_empty(),
+            PushType::PushPlus => !self.pushplus_token.is_empty(),
+            PushType::Fangtang => !self.fangtang_token.is_empty(),
+            PushType::Dingtalk => !self.dingtalk_token.is_empty(),
+            PushType::WeChat => !self.wechat_token.is_empty(),
+            PushType::Smtp => {
+                !self.smtp_config.smtp_server.is_empty() && !self.smtp_config.smtp_to.is_empty()
+            }
+            PushType::Gotify => {
+                !self.gotify_config.gotify_url.is_empty()
+                    && !self.gotify_config.gotify_token.is_empty()
             }
+            PushType::Desktop => true, //本机通知无需配置
         }
+    }
+
+    // All 推送到所有已启用且已配置的渠道，指定渠道时只推送该渠道（用于测试，不要求已启用）
+    pub async fn push_channels(
+        &self,
+        push_type: &PushType,
+        title: &str,
+        message: &str,
+        jump_url: &Option<String>,
+    ) -> Vec<ChannelPushResult> {
+        let channels: Vec<PushType> = match push_type {
+            PushType::All => PushType::CHANNELS
+                .iter()
+                .filter(|c| self.enabled_methods.iter().any(|m| m == c.method()))
+                .filter(|c| self.is_channel_configured(c))
+                .cloned()
+                .collect(),
+            channel => vec![channel.clone()],
+        };
+        let routes = channels
+            .into_iter()
+            .map(|c| (c, NotifyPriority::TimeSensitive))
+            .collect();
+        self.push_routes(routes, title, message, jump_url).await
+    }
 
-        // 检查是否启用了Gotify推送
-        if self.enabled_methods.contains(&"gotify".to_string())
-            && !self.gotify_config.gotify_token.is_empty()
-        {
-            let (success, msg) = self.push_gotify(title, message, jump_url).await;
-            if success {
-                success_count += 1;
+    async fn push_routes(
+        &self,
+        routes: Vec<(PushType, NotifyPriority)>,
+        title: &str,
+        message: &str,
+        jump_url: &Option<String>,
+    ) -> Vec<ChannelPushResult> {
+        let mut results = Vec::new();
+        for (channel, priority) in routes {
+            let result = if self.is_channel_configured(&channel) {
+                self.push_channel(&channel, priority, title, message, jump_url)
+                    .await
             } else {
-                failure_count += 1;
-                failures.push(format!("Gotify推送出错: {}", msg));
+                ChannelPushResult::failed(
+                    channel.clone(),
+                    t("push.result.not_configured").to_string(),
+                )
+            };
+            if result.success {
+                log::info!("{}推送成功", channel.display_name());
+            } else {
+                log::error!("{}推送出错: {}", channel.display_name(), result.message);
             }
+            results.push(result);
         }
+        results
+    }
 
-        if success_count == 0 {
-            return (
-                false,
-                format!(
-                    "{} 成功 / {} 失败。失败详情: {}",
-                    success_count,
-                    failure_count,
-                    failures.join("; ")
-                ),
-            );
-        } else {
-            return (true, format!("{} 个渠道推送成功", success_count));
+    async fn push_channel(
+        &self,
+        channel: &PushType,
+        priority: NotifyPriority,
+        title: &str,
+        message: &str,
+        jump_url: &Option<String>,
+    ) -> ChannelPushResult {
+        match channel {
+            PushType::Bark => self.push_bark(title, message, priority).await,
+            PushType::PushPlus => self.push_pushplus(title, message).await,
+            PushType::Fangtang => self.push_fangtang(title, message).await,
+            PushType::Dingtalk => self.push_dingtalk(title, message).await,
+            PushType::WeChat => self.push_wechat(title, message).await,
+            PushType::Smtp => self.push_smtp(title, message).await,
+            PushType::Gotify => self.push_gotify(title, message, jump_url, priority).await,
+            PushType::Desktop => self.push_desktop(title, message, jump_url, priority).await,
+            PushType::All => ChannelPushResult::failed(
+                PushType::All,
+                t("push.result.invalid_channel").to_string(),
+            ),
         }
     }
+
     pub async fn push_gotify(
         &self,
         title: &str,
         message: &str,
         jump_url: &Option<String>,
-    ) -> (bool, String) {
+        priority: NotifyPriority,
+    ) -> ChannelPushResult {
         let mut default_headers = reqwest::header::HeaderMap::new();
         let jump_url_real = match jump_url {
             Some(url) => url,
@@ -219,21 +315,27 @@ impl PushConfig {
             "Content-Type",
             reqwest::header::HeaderValue::from_static("application/json"),
         );
-        default_headers.insert(
-            "Authorization",
-            reqwest::header::HeaderValue::from_str(&format!(
-                "Bearer {}",
-                self.gotify_config.gotify_token
-            ))
-            .unwrap(),
-        );
+        match reqwest::header::HeaderValue::from_str(&format!(
+            "Bearer {}",
+            self.gotify_config.gotify_token
+        )) {
+            Ok(value) => {
+                default_headers.insert("Authorization", value);
+            }
+            Err(e) => {
+                return ChannelPushResult::failed(
+                    PushType::Gotify,
+                    tr("push.result.invalid_token", &[&e]),
+                );
+            }
+        }
         let client_builder = Client::builder()
             .default_headers(default_headers)
             .timeout(std::time::Duration::from_secs(20));
         let data = serde_json::json!({
             "message": message,
             "title": title,
-            "priority": 9,
+            "priority": priority.gotify_priority(),
             "extras": {
             "client::notification": {
                 "click": {"url": jump_url_real},
@@ -245,34 +347,29 @@ impl PushConfig {
         });
         let client = match client_builder.build() {
             Ok(client) => client,
-            Err(e) => return (false, format!("创建HTTP客户端失败: {}", e)),
+            Err(e) => {
+                return ChannelPushResult::failed(
+                    PushType::Gotify,
+                    tr("push.result.client_failed", &[&e]),
+                );
+            }
         };
         let url = format!("{}/message", push_target_url);
 
-        match client.post(&url).json(&data).send().await {
-            Ok(resp) => {
-                let status = resp.status();
-                match resp.text().await {
-                    Ok(text) => {
-                        log::debug!("Gotify 推送响应: 状态码 {}, 内容: {}", status, text);
-                        if status.is_success() {
-                            (true, "推送成功".to_string())
-                        } else {
-                            (false, format!("推送失败，状态码: {}", status))
-                        }
-                    }
-                    Err(e) => (false, format!("读取响应失败: {}", e)),
-                }
-            }
-            Err(e) => (false, format!("推送失败: {}", e)),
-        }
+        send_push(PushType::Gotify, client.post(&url).json(&data)).await
     }
-    pub async fn push_bark(&self, title: &str, message: &str) -> (bool, String) {
+
+    pub async fn push_bark(
+        &self,
+        title: &str,
+        message: &str,
+        priority: NotifyPriority,
+    ) -> ChannelPushResult {
         let client = Client::new();
         let data = serde_json::json!({
             "title":title,
             "body":message,
-            "level":"timeSensitive",
+            "level":priority.bark_level(),
            /*   #推送中断级别。
                 #active：默认值，系统会立即亮屏显示通知
                 #timeSensitive：时效性通知，可在专注状态下显示通知。
@@ -284,26 +381,10 @@ impl PushConfig {
 
         });
         let url = format!("https://api.day.app/{}/", self.bark_token);
-        match client.post(&url).json(&data).send().await {
-            Ok(resp) => {
-                let status = resp.status();
-                match resp.text().await {
-                    Ok(text) => {
-                        log::debug!("Bark 推送响应: 状态码 {}, 内容: {}", status, text);
-                        if status.is_success() {
-                            (true, "推送成功".to_string())
-                        } else {
-                            (false, format!("推送失败，状态码: {}", status))
-                        }
-                    }
-                    Err(e) => (false, format!("读取响应失败: {}", e)),
-                }
-            }
-            Err(e) => (false, format!("推送失败: {}", e)),
-        }
+        send_push(PushType::Bark, client.post(&url).json(&data)).await
     }
 
-    pub async fn push_pushplus(&self, title: &str, message: &str) -> (bool, String) {
+    pub async fn push_pushplus(&self, title: &str, message: &str) -> ChannelPushResult {
         let client = Client::new();
         let url = "http://www.pushplus.plus/send";
         let data = serde_json::json!({
@@ -311,26 +392,10 @@ impl PushConfig {
             "title":title,
             "content":message,
         });
-        match client.post(url).json(&data).send().await {
-            Ok(resp) => {
-                let status = resp.status();
-                match resp.text().await {
-                    Ok(text) => {
-                        log::debug!("PushPlus 推送响应: 状态码 {}, 内容: {}", status, text);
-                        if status.is_success() {
-                            (true, "推送成功".to_string())
-                        } else {
-                            (false, format!("推送失败，状态码: {}", status))
-                        }
-                    }
-                    Err(e) => (false, format!("读取响应失败: {}", e)),
-                }
-            }
-            Err(e) => (false, format!("推送失败: {}", e)),
-        }
+        send_push(PushType::PushPlus, client.post(url).json(&data)).await
     }
 
-    pub async fn push_fangtang(&self, title: &str, message: &str) -> (bool, String) {
+    pub async fn push_fangtang(&self, title: &str, message: &str) -> ChannelPushResult {
         let client = Client::new();
         let url = format!("https://sctapi.ftqq.com/{}.send", self.fangtang_token);
         let data = serde_json::json!({
@@ -338,26 +403,10 @@ impl PushConfig {
             "desp":message,
             "noip":1
         });
-        match client.post(url).json(&data).send().await {
-            Ok(resp) => {
-                let status = resp.status();
-                match resp.text().await {
-                    Ok(text) => {
-                        log::debug!("Fangtang 推送响应: 状态码 {}, 内容: {}", status, text);
-                        if status.is_success() {
-                            (true, "推送成功".to_string())
-                        } else {
-                            (false, format!("推送失败，状态码: {}", status))
-                        }
-                    }
-                    Err(e) => (false, format!("读取响应失败: {}", e)),
-                }
-            }
-            Err(e) => (false, format!("推送失败: {}", e)),
-        }
+        send_push(PushType::Fangtang, client.post(url).json(&data)).await
     }
 
-    pub async fn push_dingtalk(&self, title: &str, message: &str) -> (bool, String) {
+    pub async fn push_dingtalk(&self, title: &str, message: &str) -> ChannelPushResult {
         let client = Client::new();
         let url = format!(
             "https://oapi.dingtalk.com/robot/send?access_token={}",
@@ -369,33 +418,15 @@ impl PushConfig {
                 "content":format!("{} \n {}", title, message)
             }
         });
-        match client
+        let request = client
             .post(url)
             .json(&data)
             .header("Content-Type", "application/json")
-            .header("Charset", "UTF-8")
-            .send()
-            .await
-        {
-            Ok(resp) => {
-                let status = resp.status();
-                match resp.text().await {
-                    Ok(text) => {
-                        log::debug!("钉钉推送响应: 状态码 {}, 内容: {}", status, text);
-                        if status.is_success() {
-                            (true, "推送成功".to_string())
-                        } else {
-                            (false, format!("推送失败，状态码: {}", status))
-                        }
-                    }
-                    Err(e) => (false, format!("读取响应失败: {}", e)),
-                }
-            }
-            Err(e) => (false, format!("推送失败: {}", e)),
-        }
+            .header("Charset", "UTF-8");
+        send_push(PushType::Dingtalk, request).await
     }
 
-    pub async fn push_wechat(&self, title: &str, message: &str) -> (bool, String) {
+    pub async fn push_wechat(&self, title: &str, message: &str) -> ChannelPushResult {
         let client = Client::new();
         let url = format!(
             "https://qyapi.weixin.qq.com/cgi-bin/webhook/send?key={}",
@@ -407,34 +438,144 @@ impl PushConfig {
                 "content":format!("{} \n {}", title, message)
             }
         });
-        match client
+        let request = client
             .post(url)
             .json(&data)
             .header("Content-Type", "application/json")
-            .header("Charset", "UTF-8")
-            .send()
-            .await
-        {
-            Ok(resp) => {
-                let status = resp.status();
-                match resp.text().await {
-                    Ok(text) => {
-                        log::debug!("微信推送响应: 状态码 {}, 内容: {}", status, text);
-                        if status.is_success() {
-                            (true, "推送成功".to_string())
-                        } else {
-                            (false, format!("推送失败，状态码: {}", status))
-                        }
-                    }
-                    Err(e) => (false, format!("读取响应失败: {}", e)),
-                }
-            }
-            Err(e) => (false, format!("推送失败: {}", e)),
+            .header("Charset", "UTF-8");
+        send_push(PushType::WeChat, request).await
+    }
+
+    pub async fn push_desktop(
+        &self,
+        title: &str,
+        message: &str,
+        jump_url: &Option<String>,
+        priority: NotifyPriority,
+    ) -> ChannelPushResult {
+        let jump_url = jump_url
+            .as_deref()
+            .map(|url| desktop_notify::web_url(url).to_string());
+        let (title, message) = (title.to_string(), message.to_string());
+        let shown = tokio::task::spawn_blocking(move || {
+            desktop_notify::show(&title, &message, priority, jump_url.as_deref())
+        })
+        .await
+        .unwrap_or_else(|e| Err(e.to_string()));
+        match shown {
+            Ok(()) => ChannelPushResult {
+                channel: PushType::Desktop,
+                success: true,
+                status_code: None,
+                message: t("push.result.success").to_string(),
+            },
+            Err(e) => ChannelPushResult::failed(PushType::Desktop, e),
         }
     }
 
-    pub async fn push_smtp(&self, title: &str, message: &str) -> (bool, String) {
-        return (false, "SMTP推送功能未实现".to_string());
+    pub async fn push_smtp(&self, title: &str, message: &str) -> ChannelPushResult {
+        return ChannelPushResult::failed(
+            PushType::Smtp,
+            t("push.result.smtp_unimplemented").to_string(),
+        );
+    }
+}
+
+impl ChannelPushResult {
+    fn failed(channel: PushType, message: String) -> Self {
+        Self {
+            channel,
+            success: false,
+            status_code: None,
+            message,
+        }
+    }
+}
+
+// 发送推送请求，HTTP状态码和返回体中的错误码都会检查
+async fn send_push(channel: PushType, request: RequestBuilder) -> ChannelPushResult {
+    let resp = match request.send().await {
+        Ok(resp) => resp,
+        Err(e) => {
+            return ChannelPushResult::failed(channel, tr("push.result.request_failed", &[&e]));
+        }
+    };
+    let status = resp.status();
+    let text = match resp.text().await {
+        Ok(text) => text,
+        Err(e) => {
+            return ChannelPushResult {
+                channel,
+                success: false,
+                status_code: Some(status.as_u16()),
+                message: tr("push.result.read_failed", &[&e]),
+            };
+        }
+    };
+    log::debug!(
+        "{} 推送响应: 状态码 {}, 内容: {}",
+        channel.display_name(),
+        status,
+        text
+    );
+    let (success, message) = if !status.is_success() {
+        (false, tr("push.result.http_failed", &[&status]))
+    } else if let Some(err) = body_error(&text) {
+        (false, tr("push.result.body_failed", &[&err]))
+    } else {
+        (true, t("push.result.success").to_string())
+    };
+    ChannelPushResult {
+        channel,
+        success,
+        status_code: Some(status.as_u16()),
+        message,
+    }
+}
+
+// 钉钉、企业微信、PushPlus等在HTTP 200时仍会在返回体中给出错误码
+fn body_error(text: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(text).ok()?;
+    let msg = ["errmsg", "msg", "message"]
+        .iter()
+        .find_map(|key| value.get(*key).and_then(|m| m.as_str()))
+        .unwrap_or_default();
+    let code = value
+        .get("errcode")
+        .or_else(|| value.get("code"))
+        .and_then(|c| c.as_i64())?;
+    if code == 0 || code == 200 {
+        None
+    } else {
+        Some(tr("push.result.body_error", &[&code, &msg]))
+    }
+}
+
+// 汇总各渠道结果，至少一个渠道成功即视为成功
+pub fn summarize_results(results: &[ChannelPushResult]) -> (bool, String) {
+    let success_count = results.iter().filter(|r| r.success).count();
+    let failures: Vec<String> = results
+        .iter()
+        .filter(|r| !r.success)
+        .map(|r| {
+            tr(
+                "push.summary.channel_error",
+                &[&r.channel.display_name(), &r.message],
+            )
+        })
+        .collect();
+    if results.is_empty() {
+        (false, t("push.summary.no_channel").to_string())
+    } else if success_count == 0 {
+        (
+            false,
+            tr(
+                "push.summary.all_failed",
+                &[&success_count, &failures.len(), &failures.join("; ")],
+            ),
+        )
+    } else {
+        (true, tr("push.summary.ok", &[&success_count]))
     }
 }
 
@@ -0,0 +1,198 @@
+use crate::utils::Config;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+// 退避曲线，按失败次数增加等待时间
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Backoff {
+    #[default]
+    Fixed, //每次等待相同时间
+    Linear,      //等待时间 = 基础延迟 × 失败次数
+    Exponential, //等待时间 = 基础延迟 × 2^(失败次数-1)
+}
+
+// 捡漏模式每个票种默认的下单尝试次数
+const WATCH_ORDER_ATTEMPTS: u32 = 30;
+
+// 单个阶段的重试设置
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhaseRetry {
+    pub max_attempts: u32, //最多尝试次数
+    pub delay_ms: u64,     //失败后的基础等待时间
+}
+
+// 抢票各阶段的重试策略，随抢票请求下发
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    pub token: PhaseRetry,     //获取token
+    pub confirm: PhaseRetry,   //确认订单
+    pub order: PhaseRetry,     //创建订单，最多尝试次数为 0 时按抢票模式决定
+    pub poll_interval_ms: u64, //捡漏模式每轮检查的间隔
+    pub project_retry_ms: u64, //捡漏模式获取项目信息失败后的等待时间
+    pub backoff: Backoff,
+    pub max_delay_ms: u64,  //退避后单次等待的上限
+    pub jitter: f64,        //随机抖动比例，0.2 表示在 ±20% 范围内浮动
+    pub deadline_secs: u64, //开始抢票后的总时限，0 为不限制
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::for_mode(0)
+    }
+}
+
+impl RetryPolicy {
+    // 各抢票模式原有的重试次数和等待时间：
+    // 定时/直接模式确认订单失败立即重试，直接模式获取token最多10次；捡漏模式每个票种下单30次，每轮间隔2秒
+    pub fn for_mode(grab_mode: u8) -> Self {
+        let watch = grab_mode == 2;
+        Self {
+            token: PhaseRetry {
+                max_attempts: if grab_mode == 1 { 10 } else { 5 },
+                delay_ms: 1000,
+            },
+            confirm: PhaseRetry {
+                max_attempts: 4,
+                delay_ms: if watch { 300 } else { 0 },
+            },
+            order: PhaseRetry {
+                max_attempts: if watch { WATCH_ORDER_ATTEMPTS } else { 0 },
+                delay_ms: 400,
+            },
+            poll_interval_ms: 2000,
+            project_retry_ms: 1000,
+            backoff: Backoff::Fixed,
+            max_delay_ms: 5000,
+            jitter: 0.0,
+            deadline_secs: 0,
+        }
+    }
+
+    // 设置页保存过的重试策略
+    pub fn configured(config: &Config) -> Option<Self> {
+        serde_json::from_value::<RetryPolicy>(config["retry_policy"].clone()).ok()
+    }
+
+    // 读取 config["retry_policy"]，未保存时使用该模式原有的设置，旧配置只有设置页的延迟时间
+    // 旧配置的最大尝试次数从未用于下单，不作为下单次数上限
+    pub fn load(config: &Config, grab_mode: u8) -> Self {
+        if let Some(policy) = Self::configured(config) {
+            return policy;
+        }
+        let mut policy = RetryPolicy::for_mode(grab_mode);
+        if let Some(delay) = config["status_delay"].as_u64().filter(|n| *n > 0) {
+            policy.poll_interval_ms = delay * 1000;
+        }
+        policy
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, phase) in [("token", self.token), ("confirm", self.confirm)] {
+            if phase.max_attempts == 0 {
+                return Err(format!("{}最多尝试次数必须大于0", name));
+            }
+        }
+        if !(0.0..=1.0).contains(&self.jitter) {
+            return Err("随机抖动比例必须在0-1之间".to_string());
+        }
+        Ok(())
+    }
+
+    // 下单次数上限：未设置时定时和直接模式不限制，捡漏模式每个票种30次
+    pub fn order_limit(&self, grab_mode: u8) -> Option<u32> {
+        match self.order.max_attempts {
+            0 if grab_mode == 2 => Some(WATCH_ORDER_ATTEMPTS),
+            0 => None,
+            limit => Some(limit),
+        }
+    }
+
+    // 某阶段第 failures 次失败后的等待时间
+    pub fn delay(&self, phase: PhaseRetry, failures: u32) -> Duration {
+        let failures = failures.max(1);
+        let base = phase.delay_ms as f64;
+        let delay = match self.backoff {
+            Backoff::Fixed => base,
+            Backoff::Linear => base * failures as f64,
+            Backoff::Exponential => base * 2f64.powi(failures.min(31) as i32 - 1),
+        };
+        // 上限不低于基础延迟，避免把固定间隔也截短
+        let delay = delay.min(self.max_delay_ms.max(phase.delay_ms) as f64);
+        Duration::from_millis(self.with_jitter(delay) as u64)
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.with_jitter(self.poll_interval_ms as f64) as u64)
+    }
+
+    pub fn project_retry_interval(&self) -> Duration {
+        Duration::from_millis(self.with_jitter(self.project_retry_ms as f64) as u64)
+    }
+
+    // 从 start 开始计算的截止时间，不限制时为 None
+    pub fn deadline_from(&self, start: Instant) -> Option<Instant> {
+        (self.deadline_secs > 0).then(|| start + Duration::from_secs(self.deadline_secs))
+    }
+
+    fn with_jitter(&self, millis: f64) -> f64 {
+        if self.jitter <= 0.0 {
+            return millis;
+        }
+        let jitter = self.jitter.min(1.0);
+        millis * rand::thread_rng().gen_range(1.0 - jitter..=1.0 + jitter)
+    }
+}
+
+// 各阶段实际尝试的次数，随抢票结果返回
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetryAttempts {
+    pub token: u32,
+    pub confirm: u32,
+    pub order: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phase(max_attempts: u32, delay_ms: u64) -> PhaseRetry {
+        PhaseRetry {
+            max_attempts,
+            delay_ms,
+        }
+    }
+
+    #[test]
+    fn scheduled_mode_matches_baseline() {
+        let policy = RetryPolicy::for_mode(0);
+        assert_eq!(policy.token, phase(5, 1000));
+        assert_eq!(policy.confirm, phase(4, 0));
+        assert_eq!(policy.order.delay_ms, 400);
+        assert_eq!(policy.order_limit(0), None);
+        assert_eq!(policy.delay(policy.confirm, 3), Duration::ZERO);
+        assert_eq!(RetryPolicy::default(), policy);
+    }
+
+    #[test]
+    fn direct_mode_matches_baseline() {
+        let policy = RetryPolicy::for_mode(1);
+        assert_eq!(policy.token, phase(10, 1000));
+        assert_eq!(policy.confirm, phase(4, 0));
+        assert_eq!(policy.order.delay_ms, 400);
+        assert_eq!(policy.order_limit(1), None);
+    }
+
+    #[test]
+    fn watch_mode_matches_baseline() {
+        let policy = RetryPolicy::for_mode(2);
+        assert_eq!(policy.token, phase(5, 1000));
+        assert_eq!(policy.confirm, phase(4, 300));
+        assert_eq!(policy.order, phase(30, 400));
+        assert_eq!(policy.order_limit(2), Some(30));
+        assert_eq!(policy.poll_interval(), Duration::from_secs(2));
+        assert_eq!(policy.project_retry_interval(), Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,204 @@
+use crate::i18n::{t, tr};
+use crate::ics::CalendarEvent;
+use crate::money::Money;
+use crate::ticket::TicketInfo;
+use crate::utils::Config;
+use chrono::{Duration, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+// 日历中开售事件的时长
+const SALE_EVENT_MINUTES: i64 = 30;
+
+// 一个票档的一次开售，同一票档同一时间开售的场次合并
+#[derive(Clone, Debug, Serialize)]
+pub struct SaleOpening {
+    pub project_id: String,
+    pub project_name: String,
+    pub tier: String, //票档名称
+    pub sale_start: i64,
+    pub min_price: Money,
+    pub max_price: Money,
+    pub screens: Vec<String>,
+}
+
+impl SaleOpening {
+    // 用于提醒去重的标识
+    pub fn key(&self) -> String {
+        format!("{}|{}|{}", self.project_id, self.tier, self.sale_start)
+    }
+
+    pub fn price_range(&self) -> String {
+        if self.min_price == self.max_price {
+            self.min_price.to_string()
+        } else {
+            format!("{}-{}", self.min_price, self.max_price)
+        }
+    }
+}
+
+// 开售提醒设置
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SaleReminderConfig {
+    pub enabled: bool,
+    pub offsets_minutes: Vec<i64>, //开售前多少分钟提醒
+}
+
+impl Default for SaleReminderConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            offsets_minutes: vec![24 * 60, 60, 10],
+        }
+    }
+}
+
+impl SaleReminderConfig {
+    pub fn load(config: &Config) -> Self {
+        serde_json::from_value(config["sale_reminders"].clone()).unwrap_or_default()
+    }
+}
+
+// 项目列表中保存的项目ID
+pub fn saved_project_ids(config: &Config) -> Vec<String> {
+    config["projects"]
+        .as_array()
+        .map(|projects| {
+            projects
+                .iter()
+                .filter_map(|p| p["id"].as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// 项目中尚未开售的票档，票种没有单独的开售时间时使用项目开售时间
+pub fn upcoming_sales(info: &TicketInfo, now: i64) -> Vec<SaleOpening> {
+    let mut groups: BTreeMap<(i64, String), SaleOpening> = BTreeMap::new();
+    for screen in &info.screen_list {
+        for ticket in &screen.ticket_list {
+            let sale_start = match ticket.saleStart as i64 {
+                0 => info.sale_begin,
+                start => start,
+            };
+            if sale_start <= now {
+                continue;
+            }
+            let price = Money::from_fen(ticket.price as i64);
+            let opening = groups
+                .entry((sale_start, ticket.desc.clone()))
+                .or_insert_with(|| SaleOpening {
+                    project_id: info.id.to_string(),
+                    project_name: info.name.clone(),
+                    tier: ticket.desc.clone(),
+                    sale_start,
+                    min_price: price,
+                    max_price: price,
+                    screens: Vec::new(),
+                });
+            opening.min_price = opening.min_price.min(price);
+            opening.max_price = opening.max_price.max(price);
+            if !opening.screens.contains(&screen.name) {
+                opening.screens.push(screen.name.clone());
+            }
+        }
+    }
+    groups.into_values().collect()
+}
+
+pub fn sale_events(openings: &[SaleOpening]) -> Vec<CalendarEvent> {
+    openings
+        .iter()
+        .filter_map(|opening| {
+            let start = Utc
+                .timestamp_opt(opening.sale_start, 0)
+                .single()?
+                .naive_utc();
+            Some(CalendarEvent {
+                uid: format!("sale-{}@btr", opening.key()),
+                start,
+                end: start + Duration::minutes(SALE_EVENT_MINUTES),
+                summary: tr(
+                    "sale.event.summary",
+                    &[&opening.project_name, &opening.tier],
+                ),
+                location: String::new(),
+                description: tr(
+                    "sale.event.description",
+                    &[
+                        &opening.project_id,
+                        &opening.price_range(),
+                        &opening.screens.join("、"),
+                    ],
+                ),
+            })
+        })
+        .collect()
+}
+
+// 到期的提醒，同一次开售同时到期多个提醒时只发离开售最近的一个
+pub fn due_reminders(
+    openings: &[SaleOpening],
+    config: &SaleReminderConfig,
+    now: i64,
+    sent: &mut HashSet<String>,
+) -> Vec<(SaleOpening, i64)> {
+    if !config.enabled {
+        return Vec::new();
+    }
+    let mut due = Vec::new();
+    for opening in openings.iter().filter(|o| o.sale_start > now) {
+        let mut offsets: Vec<i64> = config
+            .offsets_minutes
+            .iter()
+            .copied()
+            .filter(|offset| *offset > 0 && now >= opening.sale_start - offset * 60)
+            .filter(|offset| !sent.contains(&format!("{}|{}", opening.key(), offset)))
+            .collect();
+        offsets.sort();
+        let Some(&closest) = offsets.first() else {
+            continue;
+        };
+        for offset in &offsets {
+            sent.insert(format!("{}|{}", opening.key(), offset));
+        }
+        due.push((opening.clone(), closest));
+    }
+    due
+}
+
+// 提醒提前量，如 1天、1小时、10分钟
+pub fn format_offset(minutes: i64) -> String {
+    if minutes % (24 * 60) == 0 {
+        tr("sale.offset.days", &[&(minutes / (24 * 60))])
+    } else if minutes % 60 == 0 {
+        tr("sale.offset.hours", &[&(minutes / 60)])
+    } else {
+        tr("sale.offset.minutes", &[&minutes])
+    }
+}
+
+// 推送标题和内容
+pub fn reminder_message(opening: &SaleOpening, offset: i64) -> (String, String) {
+    let start = chrono::Local
+        .timestamp_opt(opening.sale_start, 0)
+        .single()
+        .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| t("common.unknown").to_string());
+    (
+        tr(
+            "push.sale_reminder.title",
+            &[&opening.project_name, &format_offset(offset)],
+        ),
+        tr(
+            "push.sale_reminder.body",
+            &[
+                &opening.project_name,
+                &opening.tier,
+                &start,
+                &opening.price_range(),
+                &opening.screens.join("、"),
+            ],
+        ),
+    )
+}
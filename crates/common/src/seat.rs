@@ -0,0 +1,323 @@
+use crate::i18n::{t, tr};
+use crate::show_orderlist::{array_field, bool_field, int_field, str_field};
+use crate::ticket::TicketInfo;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Seat {
+    pub id: String,
+    pub area_id: String,
+    pub row: String,
+    pub col: String,
+    pub name: String, //展示名，如 "3排12座"
+    pub available: bool,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SeatRow {
+    pub name: String,
+    pub seats: Vec<Seat>, //按座位号排列
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SeatArea {
+    pub id: String,
+    pub name: String,
+    pub rows: Vec<SeatRow>,
+}
+
+impl SeatArea {
+    pub fn seats(&self) -> impl Iterator<Item = &Seat> {
+        self.rows.iter().flat_map(|row| row.seats.iter())
+    }
+
+    pub fn available_count(&self) -> usize {
+        self.seats().filter(|seat| seat.available).count()
+    }
+
+    // 区域偏好可以填区域ID或区域名称中的关键词
+    fn matches(&self, preference: &str) -> bool {
+        let preference = preference.trim();
+        !preference.is_empty() && (self.id == preference || self.name.contains(preference))
+    }
+}
+
+// 选座项目某个场次的座位图
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SeatMap {
+    pub screen_id: String,
+    pub areas: Vec<SeatArea>,
+}
+
+impl SeatMap {
+    // 接口按区域返回座位列表，部分场次直接返回不分区域的座位列表，字段名不固定
+    pub fn from_value(data: &Value, screen_id: &str) -> Self {
+        let mut areas: Vec<SeatArea> = array_field(data, &["area_list", "areas", "area_info"])
+            .iter()
+            .map(|area| {
+                let id = str_field(area, &["area_id", "id"]);
+                let seats = array_field(area, &["seat_list", "seats"])
+                    .iter()
+                    .map(|seat| parse_seat(seat, &id))
+                    .collect();
+                SeatArea {
+                    name: str_field(area, &["area_name", "name"]),
+                    rows: group_rows(seats),
+                    id,
+                }
+            })
+            .collect();
+
+        if areas.is_empty() {
+            let mut grouped: Vec<(String, String, Vec<Seat>)> = Vec::new();
+            for value in array_field(data, &["seat_list", "seats"]) {
+                let area_id = str_field(value, &["area_id"]);
+                let seat = parse_seat(value, &area_id);
+                match grouped.iter_mut().find(|(id, _, _)| *id == area_id) {
+                    Some((_, _, seats)) => seats.push(seat),
+                    None => grouped.push((
+                        area_id,
+                        str_field(value, &["area_name", "area"]),
+                        vec![seat],
+                    )),
+                }
+            }
+            areas = grouped
+                .into_iter()
+                .map(|(id, name, seats)| SeatArea {
+                    id,
+                    name,
+                    rows: group_rows(seats),
+                })
+                .collect();
+        }
+
+        SeatMap {
+            screen_id: screen_id.to_string(),
+            areas,
+        }
+    }
+
+    pub fn seats(&self) -> impl Iterator<Item = &Seat> {
+        self.areas.iter().flat_map(|area| area.seats())
+    }
+
+    pub fn available_count(&self) -> usize {
+        self.seats().filter(|seat| seat.available).count()
+    }
+}
+
+fn parse_seat(value: &Value, area_id: &str) -> Seat {
+    let row = str_field(value, &["row", "row_name", "row_no"]);
+    let col = str_field(value, &["col", "column", "seat_no"]);
+    let mut name = str_field(value, &["seat_name", "name"]);
+    if name.is_empty() {
+        name = tr("seat.name", &[&row, &col]);
+    }
+    Seat {
+        id: str_field(value, &["seat_id", "id"]),
+        area_id: area_id.to_string(),
+        row,
+        col,
+        name,
+        // 没有可售标记时按 status 判断，1 为可售
+        available: bool_field(value, &["can_sale", "available", "is_available"])
+            || int_field(value, &["status", "seat_status"]) == 1,
+    }
+}
+
+// 按排分组，保留接口中排的顺序，排内按座位号排序
+fn group_rows(seats: Vec<Seat>) -> Vec<SeatRow> {
+    let mut rows: Vec<SeatRow> = Vec::new();
+    for seat in seats {
+        match rows.iter_mut().find(|row| row.name == seat.row) {
+            Some(row) => row.seats.push(seat),
+            None => rows.push(SeatRow {
+                name: seat.row.clone(),
+                seats: vec![seat],
+            }),
+        }
+    }
+    for row in &mut rows {
+        row.seats
+            .sort_by_key(|seat| seat.col.parse::<i64>().unwrap_or(i64::MAX));
+    }
+    rows
+}
+
+// 选座设置：指定座位，或按区域偏好自动选座
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SeatSelection {
+    #[serde(default)]
+    pub seat_ids: Vec<String>, //指定的座位ID，非空时只使用这些座位
+    #[serde(default)]
+    pub areas: Vec<String>, //区域偏好（区域ID或名称关键词），按顺序尝试，为空时不限区域
+}
+
+impl SeatSelection {
+    pub fn is_empty(&self) -> bool {
+        self.seat_ids.is_empty() && self.areas.iter().all(|area| area.trim().is_empty())
+    }
+}
+
+// 项目或场次需要选座
+pub fn needs_seat_selection(info: &TicketInfo, screen_id: &str) -> bool {
+    info.pick_seat == 1
+        || info
+            .screen_list
+            .iter()
+            .any(|screen| screen.id.to_string() == screen_id && screen.pick_seat == 1)
+}
+
+// 按选座设置选出 count 个座位
+// 指定座位时必须全部可售；按区域选座时优先同一排相邻的座位，没有连座再选同区域的散座
+pub fn pick_seats(
+    map: &SeatMap,
+    selection: &SeatSelection,
+    count: usize,
+) -> Result<Vec<Seat>, String> {
+    if !selection.seat_ids.is_empty() {
+        if selection.seat_ids.len() != count {
+            return Err(tr(
+                "seat.count_mismatch",
+                &[&selection.seat_ids.len(), &count],
+            ));
+        }
+        return selection
+            .seat_ids
+            .iter()
+            .map(|id| {
+                let seat = map
+                    .seats()
+                    .find(|seat| &seat.id == id)
+                    .ok_or_else(|| tr("seat.not_found", &[&id]))?;
+                if !seat.available {
+                    return Err(tr("seat.unavailable", &[&seat.name]));
+                }
+                Ok(seat.clone())
+            })
+            .collect();
+    }
+
+    let preferences: Vec<&String> = selection
+        .areas
+        .iter()
+        .filter(|area| !area.trim().is_empty())
+        .collect();
+    let areas: Vec<&SeatArea> = if preferences.is_empty() {
+        map.areas.iter().collect()
+    } else {
+        preferences
+            .iter()
+            .flat_map(|preference| map.areas.iter().filter(|area| area.matches(preference)))
+            .collect()
+    };
+
+    for area in &areas {
+        for row in &area.rows {
+            let adjacent = row
+                .seats
+                .windows(count.max(1))
+                .find(|window| window.iter().all(|seat| seat.available) && consecutive(window));
+            if let Some(seats) = adjacent {
+                return Ok(seats.to_vec());
+            }
+        }
+    }
+    for area in &areas {
+        let seats: Vec<Seat> = area
+            .seats()
+            .filter(|seat| seat.available)
+            .take(count)
+            .cloned()
+            .collect();
+        if seats.len() == count {
+            log::warn!(
+                "区域{}没有{}个相邻的可售座位，改选散座：{}",
+                area.name,
+                count,
+                seat_names(&seats)
+            );
+            return Ok(seats);
+        }
+    }
+    Err(t("seat.sold_out").to_string())
+}
+
+// 同一排中座位号依次加一才算相邻，座位号缺失或不是数字时不算
+fn consecutive(seats: &[Seat]) -> bool {
+    let cols: Option<Vec<i64>> = seats.iter().map(|seat| seat.col.parse().ok()).collect();
+    cols.is_some_and(|cols| cols.windows(2).all(|pair| pair[1] == pair[0] + 1))
+}
+
+// 确认订单和下单接口中的座位参数，与 buyer_info 一样以 JSON 字符串提交
+pub fn seat_payload(seats: &[Seat]) -> String {
+    let list: Vec<Value> = seats
+        .iter()
+        .map(|seat| {
+            json!({
+                "seat_id": seat.id,
+                "area_id": seat.area_id,
+                "row": seat.row,
+                "col": seat.col,
+            })
+        })
+        .collect();
+    Value::Array(list).to_string()
+}
+
+pub fn seat_names(seats: &[Seat]) -> String {
+    seats
+        .iter()
+        .map(|seat| seat.name.as_str())
+        .collect::<Vec<_>>()
+        .join("、")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(cols: &[(&str, bool)]) -> SeatMap {
+        let seats = cols
+            .iter()
+            .map(|(col, available)| Seat {
+                id: col.to_string(),
+                area_id: "1".to_string(),
+                row: "1".to_string(),
+                col: col.to_string(),
+                name: col.to_string(),
+                available: *available,
+            })
+            .collect();
+        SeatMap {
+            screen_id: "1".to_string(),
+            areas: vec![SeatArea {
+                id: "1".to_string(),
+                name: "A".to_string(),
+                rows: group_rows(seats),
+            }],
+        }
+    }
+
+    fn ids(seats: &[Seat]) -> Vec<&str> {
+        seats.iter().map(|seat| seat.id.as_str()).collect()
+    }
+
+    #[test]
+    fn picks_consecutive_cols() {
+        let map = row(&[("1", true), ("3", true), ("4", true)]);
+        let seats = pick_seats(&map, &SeatSelection::default(), 2).unwrap();
+        assert_eq!(ids(&seats), ["3", "4"]);
+    }
+
+    #[test]
+    fn gap_in_cols_is_not_adjacent() {
+        let map = row(&[("1", true), ("2", false), ("3", true), ("5", true)]);
+        assert!(!consecutive(&map.areas[0].rows[0].seats[2..]));
+        // 没有连座时退回散座
+        let seats = pick_seats(&map, &SeatSelection::default(), 2).unwrap();
+        assert_eq!(ids(&seats), ["1", "3"]);
+    }
+}
@@ -1,4 +1,6 @@
+use crate::money::Money;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct OrderResponse {
     pub errno: i32,
@@ -30,6 +32,22 @@ pub struct Order {
     pub sub_status_name: String,
 }
 
+// 订单状态 status
+pub const ORDER_STATUS_UNPAID: i32 = 1; //待支付
+pub const ORDER_STATUS_PAID: i32 = 2; //已支付
+pub const ORDER_STATUS_COMPLETED: i32 = 3; //已完成
+
+impl Order {
+    pub fn is_unpaid(&self) -> bool {
+        self.status == ORDER_STATUS_UNPAID
+    }
+
+    // 已支付的订单，取消、退款等其他状态都不算
+    pub fn is_paid(&self) -> bool {
+        matches!(self.status, ORDER_STATUS_PAID | ORDER_STATUS_COMPLETED)
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ItemInfo {
     pub name: String,
@@ -44,3 +62,186 @@ pub struct ItemInfo {
 pub struct ImageInfo {
     pub url: String,
 }
+
+// 订单详情，由 order/info 接口返回的原始数据整理而来
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct OrderDetail {
+    pub order_id: String,
+    pub status: i32,
+    pub sub_status: i32,
+    pub status_name: String,
+    pub project_id: i64,
+    pub project_name: String,
+    pub screen_name: String,
+    pub screen_start_time: String,
+    pub ticket_name: String, //票档
+    pub unit_price: Money,
+    pub count: i32,
+    pub total_money: Money,
+    pub pay_money: Money,
+    pub express_fee: Money,
+    pub ctime: String,
+    pub pay_deadline: Option<i64>, //支付截止时间戳(秒)，仅待支付订单有值
+    pub buyers: Vec<OrderBuyer>,
+    pub seats: Vec<OrderSeat>,
+    pub delivery: Option<OrderDelivery>, //纸质票配送信息
+    pub refund: RefundEligibility,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct OrderBuyer {
+    pub name: String,
+    pub personal_id: String, //接口返回的已脱敏证件号
+    pub tel: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct OrderSeat {
+    pub area: String,
+    pub seat: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct OrderDelivery {
+    pub name: String,
+    pub tel: String,
+    pub address: String,
+    pub express_company: String,
+    pub tracking_no: String,
+    pub express_status: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct RefundEligibility {
+    pub refundable: bool,
+    pub reason: String, //不可退原因或退票说明
+}
+
+impl OrderDetail {
+    // data 结构：订单字段与订单列表一致，另有 item_info、buyer_list、seat_list、
+    // deliver_info、express_info 和退票相关字段，缺失的字段保留默认值
+    pub fn from_value(data: &Value) -> Self {
+        let item = &data["item_info"];
+        let count = int_field(data, &["count"]) as i32;
+        let total_money = Money::from_fen(int_field(data, &["total_money"]));
+        let mut unit_price = Money::from_fen(int_field(item, &["price"]));
+        if unit_price.fen() == 0 && count > 0 {
+            unit_price = Money::from_fen(total_money.fen() / count as i64);
+        }
+
+        let status = int_field(data, &["status"]) as i32;
+        // 仅待支付订单返回剩余支付秒数
+        let pay_remain = int_field(data, &["pay_remain_time"]);
+        let pay_deadline = (status == ORDER_STATUS_UNPAID && pay_remain > 0)
+            .then(|| chrono::Local::now().timestamp() + pay_remain);
+
+        OrderDetail {
+            order_id: str_field(data, &["order_id"]),
+            status,
+            sub_status: int_field(data, &["sub_status"]) as i32,
+            status_name: str_field(data, &["sub_status_name"]),
+            project_id: int_field(item, &["project_id"]),
+            project_name: str_field(item, &["name"]),
+            screen_name: str_field(item, &["screen_name"]),
+            screen_start_time: str_field(item, &["screen_start_time"]),
+            ticket_name: str_field(item, &["sku_name"]),
+            unit_price,
+            count,
+            total_money,
+            pay_money: Money::from_fen(int_field(data, &["pay_money"])),
+            express_fee: Money::from_fen(int_field(data, &["express_fee"])),
+            ctime: str_field(data, &["ctime"]),
+            pay_deadline,
+            buyers: array_field(data, &["buyer_list"])
+                .iter()
+                .map(|buyer| OrderBuyer {
+                    name: str_field(buyer, &["name"]),
+                    personal_id: str_field(buyer, &["personal_id"]),
+                    tel: str_field(buyer, &["tel"]),
+                })
+                .collect(),
+            seats: array_field(data, &["seat_list"])
+                .iter()
+                .filter_map(|seat| {
+                    let seat = OrderSeat {
+                        area: str_field(seat, &["area_name"]),
+                        seat: str_field(seat, &["seat_name"]),
+                    };
+                    (!seat.area.is_empty() || !seat.seat.is_empty()).then_some(seat)
+                })
+                .collect(),
+            delivery: parse_delivery(data),
+            refund: RefundEligibility {
+                refundable: bool_field(data, &["refundable"]),
+                reason: str_field(data, &["refund_desc"]),
+            },
+        }
+    }
+
+    // 待支付订单
+    pub fn is_unpaid(&self) -> bool {
+        self.status == ORDER_STATUS_UNPAID
+    }
+}
+
+fn parse_delivery(data: &Value) -> Option<OrderDelivery> {
+    let addr = &data["deliver_info"];
+    if !addr.is_object() {
+        return None;
+    }
+    let express = &data["express_info"];
+    let full_address = format!(
+        "{}{}{}{}",
+        str_field(addr, &["prov"]),
+        str_field(addr, &["city"]),
+        str_field(addr, &["area"]),
+        str_field(addr, &["addr"])
+    );
+    Some(OrderDelivery {
+        name: str_field(addr, &["name"]),
+        tel: str_field(addr, &["tel"]),
+        address: full_address,
+        express_company: str_field(express, &["express_company"]),
+        tracking_no: str_field(express, &["express_no"]),
+        express_status: str_field(express, &["status_desc"]),
+    })
+}
+
+// 依次取第一个存在的字段，数字字段也转为字符串
+pub(crate) fn str_field(value: &Value, keys: &[&str]) -> String {
+    keys.iter()
+        .find_map(|key| match &value[*key] {
+            Value::String(s) if !s.is_empty() => Some(s.clone()),
+            Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+// 数字字段可能以字符串形式返回
+pub(crate) fn int_field(value: &Value, keys: &[&str]) -> i64 {
+    keys.iter()
+        .find_map(|key| match &value[*key] {
+            Value::Number(n) => n.as_i64(),
+            Value::String(s) => s.parse().ok(),
+            _ => None,
+        })
+        .unwrap_or(0)
+}
+
+pub(crate) fn bool_field(value: &Value, keys: &[&str]) -> bool {
+    keys.iter()
+        .find_map(|key| match &value[*key] {
+            Value::Bool(b) => Some(*b),
+            Value::Number(n) => n.as_i64().map(|n| n != 0),
+            _ => None,
+        })
+        .unwrap_or(false)
+}
+
+pub(crate) fn array_field<'a>(value: &'a Value, keys: &[&str]) -> &'a [Value] {
+    keys.iter()
+        .find_map(|key| value[*key].as_array())
+        .map(Vec::as_slice)
+        .unwrap_or(&[])
+}
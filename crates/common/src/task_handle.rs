@@ -0,0 +1,389 @@
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::{broadcast, oneshot, watch};
+
+use crate::diagnostics::{self, TaskRecord};
+use crate::i18n::{t, tr};
+use crate::taskmanager::{
+    DryRunResult, GetAllorderRequestResult, GetBuyerInfoResult, GetTicketInfoResult,
+    GrabTicketResult, LoginSmsRequestResult, PushRequestResult, SubmitSmsLoginResult,
+    TaskQrCodeLoginResult, TaskResult, TaskStatus,
+};
+
+// 进度消息缓冲条数，订阅方跟不上时丢弃最旧的消息
+const PROGRESS_CAPACITY: usize = 64;
+
+// 任务进度消息
+#[derive(Clone, Debug)]
+pub struct TaskProgress {
+    pub task_id: String,
+    pub message: String,
+}
+
+// 任务结果类型，每种任务对应一个
+pub trait TaskOutput: Send + 'static {
+    fn is_success(&self) -> bool;
+
+    // 转换为轮询接口使用的结果枚举
+    fn into_task_result(self) -> TaskResult;
+}
+
+// 创建一对任务句柄和任务上下文，句柄交给调用方，上下文交给执行任务的一方
+pub fn task_channel<T: TaskOutput>(task_id: &str) -> (TaskHandle<T>, TaskContext<T>) {
+    let (status_tx, status_rx) = watch::channel(TaskStatus::Pending);
+    let (result_tx, result_rx) = oneshot::channel();
+    let (progress_tx, progress_rx) = broadcast::channel(PROGRESS_CAPACITY);
+    let (cancel_tx, cancel_rx) = watch::channel(false);
+    let control = TaskControl {
+        task_id: task_id.to_string(),
+        status: status_rx,
+        cancel: Arc::new(cancel_tx),
+    };
+
+    let handle = TaskHandle {
+        control,
+        result: Some(result_rx),
+        progress: progress_rx,
+    };
+    let context = TaskContext {
+        task_id: task_id.to_string(),
+        status: status_tx,
+        result: Mutex::new(Some(result_tx)),
+        progress: progress_tx,
+        cancel: cancel_rx,
+        started_at: diagnostics::now_string(),
+        started: std::time::Instant::now(),
+    };
+    (handle, context)
+}
+
+// 任务状态查询与取消，可以脱离句柄单独保存（任务管理器按ID取消任务时使用）
+#[derive(Clone)]
+pub struct TaskControl {
+    task_id: String,
+    status: watch::Receiver<TaskStatus>,
+    cancel: Arc<watch::Sender<bool>>,
+}
+
+impl TaskControl {
+    pub fn id(&self) -> &str {
+        &self.task_id
+    }
+
+    pub fn status(&self) -> TaskStatus {
+        self.status.borrow().clone()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        !matches!(self.status(), TaskStatus::Pending | TaskStatus::Running)
+    }
+
+    pub fn cancel(&self) {
+        let _ = self.cancel.send(true);
+    }
+}
+
+// 调用方持有的任务句柄：查询状态、等待结果、取消任务、订阅进度
+// 丢弃句柄不会取消任务
+pub struct TaskHandle<T> {
+    control: TaskControl,
+    result: Option<oneshot::Receiver<T>>,
+    progress: broadcast::Receiver<TaskProgress>,
+}
+
+impl<T: TaskOutput> TaskHandle<T> {
+    pub fn id(&self) -> &str {
+        self.control.id()
+    }
+
+    pub fn status(&self) -> TaskStatus {
+        self.control.status()
+    }
+
+    pub fn cancel(&self) {
+        self.control.cancel();
+    }
+
+    pub fn control(&self) -> TaskControl {
+        self.control.clone()
+    }
+
+    // 等待任务结果，结果只能取一次
+    pub async fn result(&mut self) -> Result<T, String> {
+        let receiver = self
+            .result
+            .take()
+            .ok_or_else(|| t("task.result_taken").to_string())?;
+        receiver
+            .await
+            .map_err(|_| tr("task.no_result", &[&self.control.task_id]))
+    }
+
+    // 非阻塞获取结果：None 表示任务仍在运行
+    pub fn try_result(&mut self) -> Option<Result<T, String>> {
+        let receiver = self.result.as_mut()?;
+        match receiver.try_recv() {
+            Ok(result) => {
+                self.result = None;
+                Some(Ok(result))
+            }
+            Err(oneshot::error::TryRecvError::Empty) => None,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                self.result = None;
+                Some(Err(tr("task.no_result", &[&self.control.task_id])))
+            }
+        }
+    }
+
+    // 等待下一条进度消息，任务结束后返回 None
+    pub async fn next_progress(&mut self) -> Option<TaskProgress> {
+        loop {
+            match self.progress.recv().await {
+                Ok(progress) => return Some(progress),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::debug!("任务{}跳过了{}条进度消息", self.control.task_id, skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    // 非阻塞获取已产生的进度消息
+    pub fn try_progress(&mut self) -> Vec<TaskProgress> {
+        let mut messages = Vec::new();
+        loop {
+            match self.progress.try_recv() {
+                Ok(progress) => messages.push(progress),
+                Err(broadcast::error::TryRecvError::Lagged(_)) => continue,
+                Err(_) => return messages,
+            }
+        }
+    }
+}
+
+// 执行任务的一方持有的上下文：上报状态、进度和结果，检查是否被取消
+pub struct TaskContext<T> {
+    task_id: String,
+    status: watch::Sender<TaskStatus>,
+    result: Mutex<Option<oneshot::Sender<T>>>,
+    progress: broadcast::Sender<TaskProgress>,
+    cancel: watch::Receiver<bool>,
+    started_at: String,
+    started: std::time::Instant,
+}
+
+impl<T: TaskOutput> TaskContext<T> {
+    pub fn id(&self) -> &str {
+        &self.task_id
+    }
+
+    pub fn start(&self) {
+        let _ = self.status.send(TaskStatus::Running);
+    }
+
+    pub fn progress(&self, message: impl Into<String>) {
+        let _ = self.progress.send(TaskProgress {
+            task_id: self.task_id.clone(),
+            message: message.into(),
+        });
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.cancel.borrow()
+    }
+
+    // 等待取消信号，可与 tokio::select! 配合打断等待
+    pub async fn cancelled(&self) {
+        let mut cancel = self.cancel.clone();
+        while !*cancel.borrow_and_update() {
+            if cancel.changed().await.is_err() {
+                // 所有句柄都已丢弃，不会再被取消
+                std::future::pending::<()>().await;
+            }
+        }
+    }
+
+    // 上报最终结果，只有第一次调用生效，返回是否生效
+    pub fn finish(&self, result: T) -> bool {
+        let Some(sender) = self.result.lock().ok().and_then(|mut r| r.take()) else {
+            return false;
+        };
+        let status = if self.is_cancelled() && !result.is_success() {
+            TaskStatus::Cancelled
+        } else {
+            TaskStatus::Completed(result.is_success())
+        };
+        self.record_history(&status);
+        let _ = self.status.send(status);
+        let _ = sender.send(result);
+        true
+    }
+}
+
+impl<T> TaskContext<T> {
+    // 写入任务历史，供诊断包使用
+    fn record_history(&self, status: &TaskStatus) {
+        let kind = std::any::type_name::<T>();
+        diagnostics::record_task(TaskRecord {
+            task_id: self.task_id.clone(),
+            kind: kind.rsplit("::").next().unwrap_or(kind).to_string(),
+            status: format!("{:?}", status),
+            started_at: self.started_at.clone(),
+            finished_at: diagnostics::now_string(),
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+        });
+    }
+}
+
+impl<T> Drop for TaskContext<T> {
+    // 任务结束但没有上报结果时，标记为失败或已取消
+    fn drop(&mut self) {
+        let unfinished = self.result.lock().map(|r| r.is_some()).unwrap_or(false);
+        if unfinished {
+            let status = if *self.cancel.borrow() {
+                TaskStatus::Cancelled
+            } else {
+                TaskStatus::Failed(tr("task.no_result", &[&self.task_id]))
+            };
+            self.record_history(&status);
+            let _ = self.status.send(status);
+        }
+    }
+}
+
+// 按轮询方式收集多个任务的结果，供界面定时拉取
+#[derive(Default)]
+pub struct TaskResultQueue {
+    pending: Vec<Box<dyn PendingTask>>,
+}
+
+impl TaskResultQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 跟踪任务句柄，返回任务ID
+    pub fn track<T: TaskOutput>(&mut self, handle: TaskHandle<T>) -> String {
+        let task_id = handle.id().to_string();
+        self.pending.push(Box::new(handle));
+        task_id
+    }
+
+    // 取出所有已完成任务的结果
+    pub fn drain(&mut self) -> Vec<TaskResult> {
+        let mut results = Vec::new();
+        self.pending.retain_mut(|task| match task.poll() {
+            None => true,
+            Some(Ok(result)) => {
+                results.push(result);
+                false
+            }
+            Some(Err(e)) => {
+                log::debug!("{}", e);
+                false
+            }
+        });
+        results
+    }
+}
+
+trait PendingTask: Send {
+    fn poll(&mut self) -> Option<Result<TaskResult, String>>;
+}
+
+impl<T: TaskOutput> PendingTask for TaskHandle<T> {
+    fn poll(&mut self) -> Option<Result<TaskResult, String>> {
+        self.try_result()
+            .map(|result| result.map(TaskOutput::into_task_result))
+    }
+}
+
+impl TaskOutput for TaskQrCodeLoginResult {
+    fn is_success(&self) -> bool {
+        matches!(self.status, crate::login::QrCodeLoginStatus::Success(_))
+    }
+
+    fn into_task_result(self) -> TaskResult {
+        TaskResult::QrCodeLoginResult(self)
+    }
+}
+
+impl TaskOutput for LoginSmsRequestResult {
+    fn is_success(&self) -> bool {
+        self.success
+    }
+
+    fn into_task_result(self) -> TaskResult {
+        TaskResult::LoginSmsResult(self)
+    }
+}
+
+impl TaskOutput for SubmitSmsLoginResult {
+    fn is_success(&self) -> bool {
+        self.success
+    }
+
+    fn into_task_result(self) -> TaskResult {
+        TaskResult::SubmitSmsLoginResult(self)
+    }
+}
+
+impl TaskOutput for PushRequestResult {
+    fn is_success(&self) -> bool {
+        self.success
+    }
+
+    fn into_task_result(self) -> TaskResult {
+        TaskResult::PushResult(self)
+    }
+}
+
+impl TaskOutput for GetAllorderRequestResult {
+    fn is_success(&self) -> bool {
+        self.success
+    }
+
+    fn into_task_result(self) -> TaskResult {
+        TaskResult::GetAllorderRequestResult(self)
+    }
+}
+
+impl TaskOutput for GetTicketInfoResult {
+    fn is_success(&self) -> bool {
+        self.success
+    }
+
+    fn into_task_result(self) -> TaskResult {
+        TaskResult::GetTicketInfoResult(self)
+    }
+}
+
+impl TaskOutput for GetBuyerInfoResult {
+    fn is_success(&self) -> bool {
+        self.success
+    }
+
+    fn into_task_result(self) -> TaskResult {
+        TaskResult::GetBuyerInfoResult(self)
+    }
+}
+
+impl TaskOutput for GrabTicketResult {
+    fn is_success(&self) -> bool {
+        self.success
+    }
+
+    fn into_task_result(self) -> TaskResult {
+        TaskResult::GrabTicketResult(self)
+    }
+}
+
+impl TaskOutput for DryRunResult {
+    fn is_success(&self) -> bool {
+        self.success
+    }
+
+    fn into_task_result(self) -> TaskResult {
+        TaskResult::DryRunResult(self)
+    }
+}
@@ -1,8 +1,16 @@
 use crate::captcha::LocalCaptcha;
 use crate::cookie_manager::CookieManager;
-use crate::push::PushConfig;
+use crate::hooks::{CommandHook, SharedHooks};
+use crate::i18n::t;
+use crate::money::Money;
+use crate::notification::NotifyEvent;
+use crate::push::{ChannelPushResult, PushConfig};
+use crate::retry::{RetryAttempts, RetryPolicy};
+use crate::seat::SeatSelection;
 use crate::show_orderlist::OrderResponse;
+use crate::task_handle::TaskHandle;
 use crate::ticket::*;
+use crate::ticket_preference::TicketPreference;
 use crate::utility::CustomConfig;
 use reqwest::Client;
 use std::sync::Arc;
@@ -15,6 +23,7 @@ pub enum TaskStatus {
     Running,
     Completed(bool),
     Failed(String),
+    Cancelled,
 }
 
 // 票务结果
@@ -27,30 +36,6 @@ pub struct TicketResult {
     pub timestamp: Instant,
 }
 
-// 任务信息
-pub enum Task {
-    QrCodeLoginTask(QrCodeLoginTask),
-    LoginSmsRequestTask(LoginSmsRequestTask),
-    PushTask(PushTask),
-    SubmitLoginSmsRequestTask(SubmitLoginSmsRequestTask),
-    GetAllorderRequestTask(GetAllorderRequest),
-    GetTicketInfoTask(GetTicketInfoTask),
-    GetBuyerInfoTask(GetBuyerInfoTask),
-    GrabTicketTask(GrabTicketTask),
-}
-
-// 任务请求枚举
-pub enum TaskRequest {
-    QrCodeLoginRequest(QrCodeLoginRequest),
-    LoginSmsRequest(LoginSmsRequest),
-    PushRequest(PushRequest),
-    SubmitLoginSmsRequest(SubmitLoginSmsRequest),
-    GetAllorderRequest(GetAllorderRequest),
-    GetTicketInfoRequest(GetTicketInfoRequest),
-    GetBuyerInfoRequest(GetBuyerInfoRequest),
-    GrabTicketRequest(GrabTicketRequest),
-}
-
 // 任务结果枚举
 #[derive(Clone)]
 pub enum TaskResult {
@@ -62,6 +47,7 @@ pub enum TaskResult {
     GetTicketInfoResult(GetTicketInfoResult),
     GetBuyerInfoResult(GetBuyerInfoResult),
     GrabTicketResult(GrabTicketResult),
+    DryRunResult(DryRunResult),
 }
 //抢票请求
 #[derive(Clone, Debug)]
@@ -81,15 +67,40 @@ pub struct GrabTicketRequest {
     pub is_hot: bool,
     pub local_captcha: LocalCaptcha,
     pub skip_words: Option<Vec<String>>,
+    pub max_total_price: Option<Money>, //预算上限（含快递费），None 为不限制
+    pub preferences: Vec<TicketPreference>, //捡漏模式场次/票种偏好，按优先级排列
+    pub dry_run: bool,                  //试运行：只检查配置，不会下单
+    pub hooks: SharedHooks,             //本地命令钩子，提交时由任务管理器注入
+    pub retry_policy: RetryPolicy,      //各阶段重试次数、退避和总时限
+    pub seat_selection: SeatSelection,  //选座项目的指定座位或区域偏好
+}
+
+impl GrabTicketRequest {
+    // 项目快递费，未获取到项目信息时为 None，预算检查需按超出处理
+    pub fn express_fee(&self) -> Option<Money> {
+        self.biliticket
+            .project_info
+            .as_ref()
+            .map(|info| Money::from_fen(info.express_fee as i64))
+    }
+
+    // 实际下单使用的实名购票人，请求中未指定时使用抢票对象中选择的购票人
+    pub fn resolved_buyers(&self) -> Vec<BuyerInfo> {
+        if !self.buyer_info.is_empty() {
+            return self.buyer_info.clone();
+        }
+        self.biliticket.buyer_info.clone().unwrap_or_default()
+    }
+
+    // 选座数量：按购票人数，非实名项目没有购票人列表时按请求的数量
+    pub fn seat_count(&self) -> usize {
+        match self.resolved_buyers().len() {
+            0 => self.count.max(1) as usize,
+            count => count,
+        }
+    }
 }
-#[derive(Clone, Debug)]
-pub struct GrabTicketTask {
-    pub task_id: String,
-    pub biliticket: BilibiliTicket,
-    pub status: TaskStatus,
-    pub client: Arc<Client>,
-    pub start_time: Option<Instant>,
-}
+
 #[derive(Clone, Debug)]
 pub struct GrabTicketResult {
     pub task_id: String,
@@ -100,6 +111,37 @@ pub struct GrabTicketResult {
     pub pay_token: Option<String>,
     pub confirm_result: Option<ConfirmTicketResult>,
     pub pay_result: Option<CheckFakeResultData>,
+    pub attempts: RetryAttempts, //各阶段实际尝试次数
+}
+
+impl GrabTicketResult {
+    pub fn failed(task_id: &str, uid: i64, message: String) -> Self {
+        Self {
+            task_id: task_id.to_string(),
+            uid,
+            success: false,
+            message,
+            order_id: None,
+            pay_token: None,
+            confirm_result: None,
+            pay_result: None,
+            attempts: RetryAttempts::default(),
+        }
+    }
+}
+//试运行检查项
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct DryRunCheck {
+    pub name: String,
+    pub passed: bool,
+    pub message: String,
+}
+#[derive(Clone, Debug)]
+pub struct DryRunResult {
+    pub task_id: String,
+    pub uid: i64,
+    pub success: bool,
+    pub checks: Vec<DryRunCheck>,
 }
 //获取购票人信息
 #[derive(Clone, Debug)]
@@ -117,14 +159,6 @@ pub struct GetBuyerInfoResult {
     pub success: bool,
     pub message: String,
 }
-#[derive(Clone, Debug)]
-pub struct GetBuyerInfoTask {
-    pub uid: i64,
-    pub task_id: String,
-    pub status: TaskStatus,
-    pub start_time: Option<Instant>,
-    pub cookie_manager: Arc<CookieManager>,
-}
 //请求project_id票详情
 #[derive(Clone, Debug)]
 pub struct GetTicketInfoRequest {
@@ -143,15 +177,6 @@ pub struct GetTicketInfoResult {
     pub message: String,
 }
 
-#[derive(Clone, Debug)]
-pub struct GetTicketInfoTask {
-    pub task_id: String,
-    pub project_id: String,
-    pub status: TaskStatus,
-    pub start_time: Option<Instant>,
-    pub cookie_manager: Arc<CookieManager>,
-}
-
 #[derive(Clone)]
 pub struct PushRequest {
     pub title: String,
@@ -159,10 +184,12 @@ pub struct PushRequest {
     pub jump_url: Option<String>,
     pub push_config: PushConfig,
     pub push_type: PushType,
+    pub event: Option<NotifyEvent>, //事件通知按路由规则推送，None 时按 push_type 推送
 }
 
 //推送类型
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum PushType {
     All,
     Bark,
@@ -171,6 +198,61 @@ pub enum PushType {
     Dingtalk,
     WeChat,
     Smtp,
+    Gotify,
+    Desktop,
+}
+
+impl PushType {
+    // 全部单独渠道，顺序即全渠道推送时的顺序
+    pub const CHANNELS: [PushType; 8] = [
+        PushType::Bark,
+        PushType::PushPlus,
+        PushType::Fangtang,
+        PushType::Dingtalk,
+        PushType::WeChat,
+        PushType::Smtp,
+        PushType::Gotify,
+        PushType::Desktop,
+    ];
+
+    // 对应 PushConfig.enabled_methods 中的渠道名
+    pub fn method(&self) -> &'static str {
+        match self {
+            PushType::All => "all",
+            PushType::Bark => "bark",
+            PushType::PushPlus => "pushplus",
+            PushType::Fangtang => "fangtang",
+            PushType::Dingtalk => "dingtalk",
+            PushType::WeChat => "wechat",
+            PushType::Smtp => "smtp",
+            PushType::Gotify => "gotify",
+            PushType::Desktop => "desktop",
+        }
+    }
+
+    pub fn from_method(method: &str) -> Option<PushType> {
+        if method == "all" {
+            return Some(PushType::All);
+        }
+        PushType::CHANNELS
+            .iter()
+            .find(|c| c.method() == method)
+            .cloned()
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            PushType::All => t("channel.all"),
+            PushType::Bark => t("channel.bark"),
+            PushType::PushPlus => t("channel.pushplus"),
+            PushType::Fangtang => t("channel.fangtang"),
+            PushType::Dingtalk => t("channel.dingtalk"),
+            PushType::WeChat => t("channel.wechat"),
+            PushType::Smtp => t("channel.smtp"),
+            PushType::Gotify => t("channel.gotify"),
+            PushType::Desktop => t("channel.desktop"),
+        }
+    }
 }
 
 // 推送结果结构体
@@ -180,16 +262,7 @@ pub struct PushRequestResult {
     pub success: bool,
     pub message: String,
     pub push_type: PushType,
-}
-
-#[derive(Clone)]
-pub struct PushTask {
-    pub task_id: String,
-    pub title: String,
-    pub message: String,
-    pub push_type: PushType,
-    pub status: TaskStatus,
-    pub start_time: Option<Instant>,
+    pub channels: Vec<ChannelPushResult>, //各渠道推送结果
 }
 
 pub struct TicketTask {
@@ -201,30 +274,6 @@ pub struct TicketTask {
     pub result: Option<TicketResult>,
 }
 
-pub struct QrCodeLoginTask {
-    pub task_id: String,
-    pub qrcode_key: String,
-    pub qrcode_url: String,
-    pub status: TaskStatus,
-    pub start_time: Option<Instant>,
-}
-
-pub struct LoginSmsRequestTask {
-    pub task_id: String,
-    pub phone: String,
-    pub status: TaskStatus,
-    pub start_time: Option<Instant>,
-}
-
-pub struct SubmitLoginSmsRequestTask {
-    pub task_id: String,
-    pub phone: String,
-    pub code: String,
-    pub captcha_key: String,
-    pub status: TaskStatus,
-    pub start_time: Option<Instant>,
-}
-
 //获取全部订单信息
 pub struct GetAllorderRequest {
     pub task_id: String,
@@ -245,13 +294,6 @@ pub struct GetAllorderRequestResult {
     pub timestamp: Instant,
 }
 
-pub struct GetAllorderTask {
-    pub task_id: String,
-    pub account_id: String,
-    pub status: TaskStatus,
-    pub start_time: Option<Instant>,
-}
-
 pub struct TicketRequest {
     pub ticket_id: String,
     pub account_id: String,
@@ -309,26 +351,72 @@ pub struct SubmitSmsLoginResult {
     pub message: String,
     pub cookie: Option<String>,
 }
-// 更新 TaskManager trait
+// 任务管理器：每种任务有独立的提交方法，返回带类型的任务句柄
 pub trait TaskManager: Send + 'static {
     // 创建新的任务管理器
     fn new() -> Self
     where
         Self: Sized;
 
-    // 提交任务
-    fn submit_task(&mut self, request: TaskRequest) -> Result<String, String>;
-
-    // 获取可用结果，返回 TaskResult 枚举
-    fn get_results(&mut self) -> Vec<TaskResult>;
-
-    // 取消任务
+    fn submit_qrcode_login(
+        &mut self,
+        request: QrCodeLoginRequest,
+    ) -> Result<TaskHandle<TaskQrCodeLoginResult>, String>;
+
+    fn submit_login_sms(
+        &mut self,
+        request: LoginSmsRequest,
+    ) -> Result<TaskHandle<LoginSmsRequestResult>, String>;
+
+    fn submit_sms_login(
+        &mut self,
+        request: SubmitLoginSmsRequest,
+    ) -> Result<TaskHandle<SubmitSmsLoginResult>, String>;
+
+    fn submit_push(
+        &mut self,
+        request: PushRequest,
+    ) -> Result<TaskHandle<PushRequestResult>, String>;
+
+    fn submit_get_orders(
+        &mut self,
+        request: GetAllorderRequest,
+    ) -> Result<TaskHandle<GetAllorderRequestResult>, String>;
+
+    fn submit_get_ticket_info(
+        &mut self,
+        request: GetTicketInfoRequest,
+    ) -> Result<TaskHandle<GetTicketInfoResult>, String>;
+
+    fn submit_get_buyer_info(
+        &mut self,
+        request: GetBuyerInfoRequest,
+    ) -> Result<TaskHandle<GetBuyerInfoResult>, String>;
+
+    fn submit_grab_ticket(
+        &mut self,
+        request: GrabTicketRequest,
+    ) -> Result<TaskHandle<GrabTicketResult>, String>;
+
+    // 试运行：只检查配置，不会下单
+    fn submit_dry_run(
+        &mut self,
+        request: GrabTicketRequest,
+    ) -> Result<TaskHandle<DryRunResult>, String>;
+
+    // 设置本地命令钩子，对已提交的抢票任务同样生效
+    fn set_hooks(&mut self, hooks: Vec<CommandHook>);
+
+    // 按任务ID取消任务
     fn cancel_task(&mut self, task_id: &str) -> Result<(), String>;
 
     // 获取任务状态
     fn get_task_status(&self, task_id: &str) -> Option<TaskStatus>;
 
-    // 关闭任务管理器
+    // 获取任务管理器运行时句柄，供同步调用方（如界面命令）执行异步请求
+    fn runtime_handle(&self) -> tokio::runtime::Handle;
+
+    // 关闭任务管理器，取消所有未完成的任务
     fn shutdown(&mut self);
 }
 
@@ -338,3 +426,178 @@ pub fn TaskManager_debug() -> String {
     let bytes = base64::decode(DISCLAIMER_TEXT_ENCODED).unwrap_or_default();
     String::from_utf8(bytes).unwrap_or_else(|_| "本项目免费开源".to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::login::QrCodeLoginStatus;
+    use crate::task_handle::{TaskControl, task_channel};
+    use std::collections::HashMap;
+
+    // 测试替身：二维码登录直接返回预设状态，没有预设时任务一直运行到被取消
+    struct MockTaskManager {
+        qrcode_status: Option<QrCodeLoginStatus>,
+        tasks: HashMap<String, TaskControl>,
+    }
+
+    impl TaskManager for MockTaskManager {
+        fn new() -> Self {
+            Self {
+                qrcode_status: None,
+                tasks: HashMap::new(),
+            }
+        }
+
+        fn submit_qrcode_login(
+            &mut self,
+            request: QrCodeLoginRequest,
+        ) -> Result<TaskHandle<TaskQrCodeLoginResult>, String> {
+            let (handle, ctx) = task_channel(&request.qrcode_key);
+            self.tasks.insert(request.qrcode_key, handle.control());
+            let status = self.qrcode_status.clone();
+            tokio::spawn(async move {
+                ctx.start();
+                let Some(status) = status else {
+                    ctx.cancelled().await;
+                    return;
+                };
+                let cookie = match &status {
+                    QrCodeLoginStatus::Success(cookie) => Some(cookie.clone()),
+                    _ => None,
+                };
+                ctx.finish(TaskQrCodeLoginResult {
+                    task_id: ctx.id().to_string(),
+                    status,
+                    cookie,
+                    error: None,
+                });
+            });
+            Ok(handle)
+        }
+
+        fn submit_login_sms(
+            &mut self,
+            _request: LoginSmsRequest,
+        ) -> Result<TaskHandle<LoginSmsRequestResult>, String> {
+            Err("unsupported".to_string())
+        }
+
+        fn submit_sms_login(
+            &mut self,
+            _request: SubmitLoginSmsRequest,
+        ) -> Result<TaskHandle<SubmitSmsLoginResult>, String> {
+            Err("unsupported".to_string())
+        }
+
+        fn submit_push(
+            &mut self,
+            _request: PushRequest,
+        ) -> Result<TaskHandle<PushRequestResult>, String> {
+            Err("unsupported".to_string())
+        }
+
+        fn submit_get_orders(
+            &mut self,
+            _request: GetAllorderRequest,
+        ) -> Result<TaskHandle<GetAllorderRequestResult>, String> {
+            Err("unsupported".to_string())
+        }
+
+        fn submit_get_ticket_info(
+            &mut self,
+            _request: GetTicketInfoRequest,
+        ) -> Result<TaskHandle<GetTicketInfoResult>, String> {
+            Err("unsupported".to_string())
+        }
+
+        fn submit_get_buyer_info(
+            &mut self,
+            _request: GetBuyerInfoRequest,
+        ) -> Result<TaskHandle<GetBuyerInfoResult>, String> {
+            Err("unsupported".to_string())
+        }
+
+        fn submit_grab_ticket(
+            &mut self,
+            _request: GrabTicketRequest,
+        ) -> Result<TaskHandle<GrabTicketResult>, String> {
+            Err("unsupported".to_string())
+        }
+
+        fn submit_dry_run(
+            &mut self,
+            _request: GrabTicketRequest,
+        ) -> Result<TaskHandle<DryRunResult>, String> {
+            Err("unsupported".to_string())
+        }
+
+        fn set_hooks(&mut self, _hooks: Vec<CommandHook>) {}
+
+        fn cancel_task(&mut self, task_id: &str) -> Result<(), String> {
+            let control = self
+                .tasks
+                .get(task_id)
+                .ok_or_else(|| "task not found".to_string())?;
+            control.cancel();
+            Ok(())
+        }
+
+        fn get_task_status(&self, task_id: &str) -> Option<TaskStatus> {
+            self.tasks.get(task_id).map(|control| control.status())
+        }
+
+        fn runtime_handle(&self) -> tokio::runtime::Handle {
+            tokio::runtime::Handle::current()
+        }
+
+        fn shutdown(&mut self) {
+            for control in self.tasks.values() {
+                control.cancel();
+            }
+            self.tasks.clear();
+        }
+    }
+
+    // 调用方只依赖 TaskManager 接口：提交扫码登录并等待结果
+    async fn wait_for_login(manager: &mut impl TaskManager, key: &str) -> Result<String, String> {
+        let mut handle = manager.submit_qrcode_login(QrCodeLoginRequest {
+            qrcode_key: key.to_string(),
+            qrcode_url: String::new(),
+            user_agent: None,
+        })?;
+        let result = handle.result().await?;
+        result.cookie.ok_or_else(|| format!("{:?}", result.status))
+    }
+
+    #[tokio::test]
+    async fn handle_returns_preset_result() {
+        let mut manager = MockTaskManager::new();
+        manager.qrcode_status = Some(QrCodeLoginStatus::Success("SESSDATA=1".to_string()));
+
+        let cookie = wait_for_login(&mut manager, "key").await;
+
+        assert_eq!(cookie, Ok("SESSDATA=1".to_string()));
+        assert!(matches!(
+            manager.get_task_status("key"),
+            Some(TaskStatus::Completed(true))
+        ));
+    }
+
+    #[tokio::test]
+    async fn cancel_task_stops_running_task() {
+        let mut manager = MockTaskManager::new();
+        let mut handle = manager
+            .submit_qrcode_login(QrCodeLoginRequest {
+                qrcode_key: "key".to_string(),
+                qrcode_url: String::new(),
+                user_agent: None,
+            })
+            .unwrap();
+
+        manager.cancel_task("key").unwrap();
+
+        assert!(handle.result().await.is_err());
+        assert!(matches!(handle.status(), TaskStatus::Cancelled));
+        assert!(manager.cancel_task("missing").is_err());
+    }
+}
@@ -6,7 +6,10 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::account::Account;
+use crate::address::DeliveryAddress;
 use crate::cookie_manager::CookieManager;
+use crate::money::Money;
+use crate::project_detail::VenueDesc;
 use crate::push::PushConfig;
 use crate::utility::CustomConfig;
 
@@ -63,6 +66,22 @@ pub struct ConfirmTicketResult {
     pub ticket_info: ConfirmTicketInfo,
 }
 
+impl ConfirmTicketResult {
+    // 票面总价，pay_money 缺失时按单价*数量计算
+    pub fn ticket_total(&self) -> Money {
+        if self.pay_money > 0 {
+            Money::from_fen(self.pay_money)
+        } else {
+            Money::from_fen(self.ticket_info.price) * self.count as i64
+        }
+    }
+
+    // 订单总价（含快递费）
+    pub fn total_with_express(&self, express_fee: Money) -> Money {
+        self.ticket_total() + express_fee
+    }
+}
+
 //获取token响应结构体
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -105,6 +124,7 @@ pub struct BilibiliTicket {
     pub buyer_info: Option<Vec<BuyerInfo>>,    //购买人信息（实名票）
 
     pub no_bind_buyer_info: Option<NoBindBuyerInfo>, //不实名制购票人信息
+    pub deliver_info: Option<DeliveryAddress>,       //收货地址（纸质票快递配送）
 
     pub select_ticket_id: Option<String>,
 
@@ -179,6 +199,7 @@ impl BilibiliTicket {
             buyer_info: None,
             all_buyer_info: None,
             no_bind_buyer_info: None,
+            deliver_info: None,
             select_ticket_id: None,
             pay_money: None,
             count: None,
@@ -209,6 +230,8 @@ pub struct TicketInfo {
     pub sale_flag: String, //售票状态
     pub is_free: bool,
     pub performance_desc: Option<DescribeList>, //基础信息
+    #[serde(default)]
+    pub venue_info: Option<VenueDesc>, //场馆信息
     pub id_bind: usize,                         //是否绑定
     #[serde(rename = "hotProject")]
     pub hot_project: bool, //是否热门项目
@@ -292,7 +315,9 @@ pub struct ModuleItem {
 // 为 base_info 模块中的详情项创建结构体
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct BaseInfoItem {
+    #[serde(default)]
     pub title: String,
+    #[serde(default)]
     pub content: String,
 }
 
@@ -0,0 +1,79 @@
+use crate::money::Money;
+use crate::ticket::{ScreenInfo, ScreenTicketInfo};
+use serde::{Deserialize, Serialize};
+
+// 捡漏模式的场次/票种偏好，列表顺序即优先级
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TicketPreference {
+    #[serde(default)]
+    pub screen_id: Option<usize>, //场次id，None 表示任意场次
+    #[serde(default)]
+    pub ticket_id: Option<usize>, //票种id，None 表示该场次任意票种
+    #[serde(default)]
+    pub max_price: Option<Money>, //单张票价上限
+    #[serde(default)]
+    pub keywords: Vec<String>, //场次名或票种描述需包含全部关键词
+}
+
+impl TicketPreference {
+    pub fn matches(&self, screen: &ScreenInfo, ticket: &ScreenTicketInfo) -> bool {
+        if self.screen_id.is_some_and(|id| id != screen.id) {
+            return false;
+        }
+        if self.ticket_id.is_some_and(|id| id != ticket.id) {
+            return false;
+        }
+        if self
+            .max_price
+            .is_some_and(|max| Money::from_fen(ticket.price as i64) > max)
+        {
+            return false;
+        }
+        let title =
+            format!("{} {} {}", screen.name, ticket.screen_name, ticket.desc).to_lowercase();
+        self.keywords
+            .iter()
+            .filter(|word| !word.trim().is_empty())
+            .all(|word| title.contains(&word.trim().to_lowercase()))
+    }
+}
+
+// 按偏好顺序列出候选票种，偏好为空时按接口顺序返回全部可点票种
+pub fn rank_candidates(
+    preferences: &[TicketPreference],
+    screens: &[ScreenInfo],
+) -> Vec<(ScreenInfo, ScreenTicketInfo)> {
+    let clickable = screens
+        .iter()
+        .filter(|screen| screen.clickable)
+        .flat_map(|screen| {
+            screen
+                .ticket_list
+                .iter()
+                .filter(|ticket| ticket.clickable)
+                .map(move |ticket| (screen, ticket))
+        });
+
+    if preferences.is_empty() {
+        return clickable
+            .map(|(screen, ticket)| (screen.clone(), ticket.clone()))
+            .collect();
+    }
+
+    let clickable: Vec<_> = clickable.collect();
+    let mut ranked: Vec<(ScreenInfo, ScreenTicketInfo)> = Vec::new();
+    for preference in preferences {
+        for (screen, ticket) in &clickable {
+            if ranked
+                .iter()
+                .any(|(s, t)| s.id == screen.id && t.id == ticket.id)
+            {
+                continue;
+            }
+            if preference.matches(screen, ticket) {
+                ranked.push(((*screen).clone(), (*ticket).clone()));
+            }
+        }
+    }
+    ranked
+}
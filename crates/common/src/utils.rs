@@ -55,6 +55,11 @@ impl Config {
         Self { data }
     }
 
+    // 完整配置数据（只读）
+    pub fn as_value(&self) -> &Value {
+        &self.data
+    }
+
     pub fn save_config(&self) -> io::Result<()> {
         //后续上加密
         let json_str = serde_json::to_string_pretty(&self.data)?;
@@ -0,0 +1,302 @@
+use crate::address::{delivery_type_name, needs_delivery, screen_delivery_type};
+use crate::i18n::{Lang, current_lang, t, tr};
+use crate::money::Money;
+use crate::project_detail::format_timestamp;
+use crate::seat::needs_seat_selection;
+use crate::taskmanager::{GrabTicketRequest, PushType};
+use crate::ticket::{ScreenInfo, ScreenTicketInfo, TicketInfo};
+use crate::ticket_preference::rank_candidates;
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationLevel {
+    Error,   //无法抢票，提交前必须修正
+    Warning, //可以提交，但可能失败或不符合预期
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ValidationIssue {
+    pub level: ValidationLevel,
+    pub field: String, //检查项，如 account、buyer、sale_window
+    pub message: String,
+}
+
+// 提交抢票任务前的本地检查结果
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ValidationReport {
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    fn error(&mut self, field: &str, message: String) {
+        self.issues.push(ValidationIssue {
+            level: ValidationLevel::Error,
+            field: field.to_string(),
+            message,
+        });
+    }
+
+    fn warning(&mut self, field: &str, message: String) {
+        self.issues.push(ValidationIssue {
+            level: ValidationLevel::Warning,
+            field: field.to_string(),
+            message,
+        });
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|i| i.level == ValidationLevel::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|i| i.level == ValidationLevel::Warning)
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    // 汇总错误信息，用于拒绝提交时的提示
+    pub fn error_summary(&self) -> String {
+        self.errors()
+            .map(|i| i.message.clone())
+            .collect::<Vec<_>>()
+            .join(match current_lang() {
+                Lang::ZhCn => "；",
+                Lang::En => "; ",
+            })
+    }
+}
+
+pub fn id_bind_name(id_bind: usize) -> &'static str {
+    match id_bind {
+        0 => t("id_bind.none"),
+        1 => t("id_bind.per_order"),
+        2 => t("id_bind.per_person"),
+        _ => t("common.unknown"),
+    }
+}
+
+// 只使用任务中已缓存的项目/购票人信息做检查，不发起网络请求
+pub fn validate_task(req: &GrabTicketRequest) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    let ticket = &req.biliticket;
+
+    // 账号
+    let account = &ticket.account;
+    if !account.is_login || account.cookie.is_empty() {
+        report.error(
+            "account",
+            tr("validate.account_not_login", &[&account.name]),
+        );
+    } else if account.csrf.is_empty() {
+        report.warning("account", tr("validate.account_no_csrf", &[&account.name]));
+    }
+
+    // 项目
+    let info = match &ticket.project_info {
+        Some(info) => Some(info),
+        None => {
+            report.warning("project", t("validate.project_missing").to_string());
+            None
+        }
+    };
+    if let Some(info) = info {
+        if info.id.to_string() != req.project_id {
+            report.error(
+                "project",
+                tr("validate.project_mismatch", &[&info.id, &req.project_id]),
+            );
+        }
+    }
+
+    // 实名类型
+    let id_bind = ticket.id_bind;
+    if let Some(info) = info {
+        if info.id_bind != id_bind {
+            report.error(
+                "id_bind",
+                tr(
+                    "validate.id_bind_mismatch",
+                    &[&id_bind_name(info.id_bind), &id_bind_name(id_bind)],
+                ),
+            );
+        }
+    }
+
+    // 购票人
+    match id_bind {
+        0 => match &ticket.no_bind_buyer_info {
+            Some(buyer) if !buyer.name.is_empty() && !buyer.tel.is_empty() => {
+                if buyer.tel.len() != 11 || !buyer.tel.chars().all(|c| c.is_ascii_digit()) {
+                    report.warning("buyer", tr("validate.tel_format", &[&buyer.tel]));
+                }
+            }
+            _ => report.error("buyer", t("validate.no_bind_buyer_missing").to_string()),
+        },
+        1 | 2 => {
+            let buyers = req.resolved_buyers();
+            if buyers.is_empty() {
+                report.error("buyer", t("validate.buyer_missing").to_string());
+            }
+            for buyer in buyers.iter().filter(|b| !b.isBuyerInfoVerified) {
+                report.warning("buyer", tr("validate.buyer_unverified", &[&buyer.name]));
+            }
+            if !buyers.is_empty() && buyers.len() != req.count as usize {
+                report.warning(
+                    "buyer",
+                    tr(
+                        "validate.buyer_count_mismatch",
+                        &[&req.count, &buyers.len()],
+                    ),
+                );
+            }
+        }
+        _ => report.error("buyer", tr("validate.buyer_type_unknown", &[&id_bind])),
+    }
+
+    let Some(info) = info else {
+        // 抢票时会先获取项目信息，仍获取失败则放弃下单
+        if req.max_total_price.is_some() {
+            report.warning("budget", t("validate.budget_no_project").to_string());
+        }
+        validate_push(req, &mut report);
+        return report;
+    };
+
+    // 场次与票种
+    let selected = if req.grab_mode == 2 {
+        if rank_candidates(&req.preferences, &info.screen_list).is_empty() {
+            report.warning("ticket", t("validate.no_candidates").to_string());
+        }
+        None
+    } else {
+        find_selected(req, info, &mut report)
+    };
+
+    // 开售时间
+    let now = chrono::Local::now().timestamp();
+    let sale_end = selected
+        .map(|(screen, _)| screen.sale_end as i64)
+        .filter(|end| *end > 0)
+        .unwrap_or(info.sale_end);
+    if sale_end > 0 && now > sale_end {
+        report.error(
+            "sale_window",
+            tr("validate.sale_ended", &[&format_timestamp(sale_end)]),
+        );
+    } else if info.sale_begin > 0 {
+        match req.grab_mode {
+            0 if now >= info.sale_begin => {
+                report.warning("sale_window", t("validate.sale_started").to_string())
+            }
+            1 if now < info.sale_begin => report.warning(
+                "sale_window",
+                tr(
+                    "validate.sale_not_started",
+                    &[&format_timestamp(info.sale_begin)],
+                ),
+            ),
+            _ => {}
+        }
+    }
+
+    // 选座
+    if needs_seat_selection(info, &req.screen_id) {
+        let seat_ids = &req.seat_selection.seat_ids;
+        if !seat_ids.is_empty() && seat_ids.len() != req.count as usize {
+            report.error(
+                "seat",
+                tr("seat.count_mismatch", &[&seat_ids.len(), &req.count]),
+            );
+        } else if req.seat_selection.is_empty() {
+            report.warning("seat", t("validate.seat_auto").to_string());
+        }
+    }
+
+    // 配送与预算
+    let express_fee = req.express_fee().unwrap_or(Money::ZERO);
+    if needs_delivery(info, &req.screen_id) {
+        match &req.biliticket.deliver_info {
+            Some(address) => report.warning(
+                "delivery",
+                tr(
+                    "validate.address_selected",
+                    &[
+                        &delivery_type_name(screen_delivery_type(info, &req.screen_id)),
+                        &express_fee,
+                        &address.full_address(),
+                    ],
+                ),
+            ),
+            None => report.error("delivery", t("validate.address_missing").to_string()),
+        }
+    }
+    if let (Some(max), Some((_, ticket_data))) = (req.max_total_price, selected) {
+        let total = Money::from_fen(ticket_data.price as i64) * req.count as i64 + express_fee;
+        if total > max {
+            report.error("budget", tr("validate.over_budget", &[&total, &max]));
+        }
+    }
+
+    validate_push(req, &mut report);
+    report
+}
+
+fn find_selected<'a>(
+    req: &GrabTicketRequest,
+    info: &'a TicketInfo,
+    report: &mut ValidationReport,
+) -> Option<(&'a ScreenInfo, &'a ScreenTicketInfo)> {
+    let Some(screen) = info
+        .screen_list
+        .iter()
+        .find(|s| s.id.to_string() == req.screen_id)
+    else {
+        report.error("screen", tr("validate.screen_not_found", &[&req.screen_id]));
+        return None;
+    };
+    let Some(ticket) = screen
+        .ticket_list
+        .iter()
+        .find(|t| t.id.to_string() == req.ticket_id)
+    else {
+        report.error("ticket", tr("validate.ticket_not_found", &[&req.ticket_id]));
+        return None;
+    };
+    if !ticket.clickable && req.grab_mode != 0 {
+        report.warning(
+            "ticket",
+            tr(
+                "validate.ticket_unavailable",
+                &[&ticket.desc, &ticket.sale_flag.display_name],
+            ),
+        );
+    }
+    Some((screen, ticket))
+}
+
+fn validate_push(req: &GrabTicketRequest, report: &mut ValidationReport) {
+    let push = &req.biliticket.push_self;
+    if !push.enabled {
+        return;
+    }
+    if push.enabled_methods.is_empty() {
+        report.warning("push", t("validate.push_no_method").to_string());
+        return;
+    }
+    // 未填写token的渠道在推送时会被跳过，只有全部未配置时才提示
+    let configured = push
+        .enabled_methods
+        .iter()
+        .filter_map(|method| PushType::from_method(method))
+        .any(|channel| push.is_channel_configured(&channel));
+    if !configured {
+        report.warning("push", t("validate.push_unconfigured").to_string());
+    }
+}
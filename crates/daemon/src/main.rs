@@ -0,0 +1,175 @@
+mod routes;
+mod state;
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use backend::taskmanager::TaskManagerImpl;
+use common::hooks;
+use common::i18n::{Lang, set_lang, t, tr};
+use common::pacer::{self, PacerConfig};
+use common::taskmanager::TaskManager;
+use common::utils::Config;
+use rand::{Rng, distributions::Alphanumeric, thread_rng};
+
+use crate::state::DaemonState;
+
+// 默认监听端口，只监听 127.0.0.1
+const DEFAULT_PORT: u16 = 18650;
+// 未指定token时生成并保存到此文件
+const TOKEN_FILE: &str = "daemon.token";
+// 收集任务事件和日志的间隔
+const COLLECT_INTERVAL: Duration = Duration::from_millis(500);
+
+struct Options {
+    port: u16,
+    token: Option<String>,
+}
+
+// daemon [--port N] [--token TOKEN]
+fn parse_options() -> Result<Option<Options>, String> {
+    let mut options = Options {
+        port: DEFAULT_PORT,
+        token: std::env::var("BTR_DAEMON_TOKEN")
+            .ok()
+            .filter(|t| !t.is_empty()),
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--port" => {
+                options.port = args
+                    .next()
+                    .and_then(|p| p.parse().ok())
+                    .ok_or_else(|| t("daemon.invalid_port").to_string())?;
+            }
+            "--token" => {
+                options.token = Some(
+                    args.next()
+                        .ok_or_else(|| t("daemon.missing_token").to_string())?,
+                );
+            }
+            "-h" | "--help" => return Ok(None),
+            other => return Err(tr("daemon.unknown_arg", &[&other])),
+        }
+    }
+    Ok(Some(options))
+}
+
+// 读取已保存的token，没有则生成新的
+fn load_or_create_token() -> Result<String, String> {
+    if let Ok(token) = std::fs::read_to_string(TOKEN_FILE) {
+        let token = token.trim().to_string();
+        if !token.is_empty() {
+            return Ok(token);
+        }
+    }
+    let token: String = thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect();
+    write_token(&token).map_err(|e| tr("daemon.token_save_failed", &[&e]))?;
+    Ok(token)
+}
+
+// token文件仅允许当前用户读写，创建时即设置权限
+fn write_token(token: &str) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // 已存在的空token文件不受 mode 影响，需要单独收紧权限
+        if std::path::Path::new(TOKEN_FILE).exists() {
+            std::fs::set_permissions(TOKEN_FILE, std::fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    options.open(TOKEN_FILE)?.write_all(token.as_bytes())
+}
+
+fn main() {
+    if let Err(e) = common::init_logger() {
+        eprintln!("初始化日志失败: {}", e);
+    }
+    common::http_record::init_from_env();
+
+    let options = match parse_options() {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", t("daemon.usage"));
+            return;
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            println!("{}", t("daemon.usage"));
+            std::process::exit(2);
+        }
+    };
+    let token = match options.token {
+        Some(token) => token,
+        None => match load_or_create_token() {
+            Ok(token) => {
+                log::info!("token已保存在 {}", TOKEN_FILE);
+                token
+            }
+            Err(e) => {
+                log::error!("{}", e);
+                std::process::exit(1);
+            }
+        },
+    };
+
+    let config = Config::load_config().unwrap_or_else(|_| Config::new());
+    if let Some(lang) = config["language"].as_str().and_then(Lang::parse) {
+        set_lang(lang);
+    }
+    pacer::set_config(PacerConfig::load(&config));
+    let mut task_manager = TaskManagerImpl::new();
+    task_manager.set_hooks(hooks::load_hooks(&config));
+    let runtime = task_manager.runtime_handle();
+    let state = Arc::new(DaemonState::new(task_manager, token));
+
+    if let Err(e) = runtime.block_on(serve(state, options.port)) {
+        log::error!("守护进程异常退出: {}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn serve(state: Arc<DaemonState>, port: u16) -> Result<(), String> {
+    state.reload_accounts().await;
+
+    let collector = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(COLLECT_INTERVAL);
+        loop {
+            interval.tick().await;
+            collector.collect();
+        }
+    });
+
+    tokio::spawn(backend::sale_calendar::run_sale_reminders());
+
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|e| tr("daemon.listen_failed", &[&addr, &e]))?;
+    log::info!("守护进程已启动，监听 http://{}", addr);
+
+    axum::serve(listener, routes::router(state.clone()))
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+            log::info!("收到退出信号，正在停止守护进程");
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if let Ok(mut task_manager) = state.task_manager.lock() {
+        task_manager.shutdown();
+    }
+    Ok(())
+}
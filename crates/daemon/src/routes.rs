@@ -0,0 +1,422 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{StatusCode, header};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use backend::api::{get_address_list, get_buyer_info, get_project, get_seat_map};
+use backend::grab_request::{build_grab_request, build_profile_request, resolve_address};
+use backend::sale_calendar::fetch_upcoming_sales;
+use backend::show_orderlist::{export_orders_calendar, get_order_detail, get_orderlist};
+use common::account::Account;
+use common::diagnostics;
+use common::grab_profile::{find_profile, load_profiles};
+use common::i18n::t;
+use common::sale_calendar::{SaleOpening, sale_events, saved_project_ids};
+use common::seat::SeatSelection;
+use common::taskmanager::TaskManager;
+use common::utils::Config;
+use common::validate::{ValidationLevel, validate_task};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::state::{DaemonState, TrackedTask};
+
+type AppState = Arc<DaemonState>;
+type ApiResult = Result<Json<Value>, ApiError>;
+
+// 接口错误，以 {"error": 消息} 返回
+pub struct ApiError(StatusCode, String);
+
+impl ApiError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self(StatusCode::BAD_REQUEST, message.into())
+    }
+
+    fn not_found(message: impl Into<String>) -> Self {
+        Self(StatusCode::NOT_FOUND, message.into())
+    }
+
+    // 守护进程自身的错误（锁定失败等）
+    fn internal(message: impl Into<String>) -> Self {
+        Self(StatusCode::INTERNAL_SERVER_ERROR, message.into())
+    }
+}
+
+// 后端请求失败（B站接口错误等）
+impl From<String> for ApiError {
+    fn from(message: String) -> Self {
+        Self(StatusCode::BAD_GATEWAY, message)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+pub fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/api/accounts", get(list_accounts))
+        .route("/api/accounts/reload", post(reload_accounts))
+        .route("/api/accounts/:uid/buyers", get(list_buyers))
+        .route("/api/accounts/:uid/addresses", get(list_addresses))
+        .route("/api/projects/:project_id", get(project_detail))
+        .route(
+            "/api/projects/:project_id/screens/:screen_id/seats",
+            get(seat_map),
+        )
+        .route("/api/tasks", get(list_tasks).post(submit_task))
+        .route("/api/profiles", get(list_profiles))
+        .route("/api/tasks/:task_id", get(task_status).delete(cancel_task))
+        .route("/api/events", get(poll_events))
+        .route("/api/orders", get(list_orders))
+        .route("/api/orders/calendar", get(orders_calendar))
+        .route("/api/orders/:order_id", get(order_detail))
+        .route("/api/sales", get(list_sales))
+        .route("/api/sales/calendar", get(sales_calendar))
+        .route("/api/logs", get(poll_logs))
+        .layer(middleware::from_fn_with_state(state.clone(), require_token))
+        .with_state(state)
+}
+
+// 所有接口都需要 Authorization: Bearer <token>
+async fn require_token(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| state.check_token(token.trim()));
+    if !authorized {
+        return ApiError(
+            StatusCode::UNAUTHORIZED,
+            t("daemon.unauthorized").to_string(),
+        )
+        .into_response();
+    }
+    next.run(request).await
+}
+
+#[derive(Deserialize)]
+struct AccountQuery {
+    uid: Option<i64>,
+}
+
+#[derive(Deserialize)]
+struct SinceQuery {
+    #[serde(default)]
+    since: u64,
+}
+
+// 指定UID的账号，未指定时取第一个启用的账号（没有启用的则取第一个）
+async fn find_account(state: &DaemonState, uid: Option<i64>) -> Result<Account, ApiError> {
+    let accounts = state.accounts.lock().await;
+    let account = match uid {
+        Some(uid) => accounts.iter().find(|a| a.uid == uid),
+        None => accounts
+            .iter()
+            .find(|a| a.is_active)
+            .or_else(|| accounts.first()),
+    };
+    account
+        .cloned()
+        .ok_or_else(|| ApiError::not_found(t("daemon.account_not_found")))
+}
+
+fn cookie_manager_of(
+    account: &Account,
+) -> Result<Arc<common::cookie_manager::CookieManager>, ApiError> {
+    account
+        .cookie_manager
+        .clone()
+        .ok_or_else(|| ApiError::bad_request(t("daemon.account_not_ready")))
+}
+
+// 账号列表不返回cookie
+async fn list_accounts(State(state): State<AppState>) -> ApiResult {
+    let accounts = state.accounts.lock().await;
+    let accounts: Vec<Value> = accounts
+        .iter()
+        .map(|a| {
+            json!({
+                "uid": a.uid,
+                "name": a.name,
+                "level": a.level,
+                "is_login": a.is_login,
+                "is_active": a.is_active,
+                "vip_label": a.vip_label,
+            })
+        })
+        .collect();
+    Ok(Json(json!(accounts)))
+}
+
+async fn reload_accounts(State(state): State<AppState>) -> ApiResult {
+    let count = state.reload_accounts().await;
+    Ok(Json(json!({ "count": count })))
+}
+
+async fn list_buyers(State(state): State<AppState>, Path(uid): Path<i64>) -> ApiResult {
+    let account = find_account(&state, Some(uid)).await?;
+    let buyers = get_buyer_info(cookie_manager_of(&account)?).await?;
+    Ok(Json(json!(buyers.data.list)))
+}
+
+async fn list_addresses(State(state): State<AppState>, Path(uid): Path<i64>) -> ApiResult {
+    let account = find_account(&state, Some(uid)).await?;
+    let addresses = get_address_list(cookie_manager_of(&account)?).await?;
+    Ok(Json(json!(addresses)))
+}
+
+async fn project_detail(
+    State(state): State<AppState>,
+    Path(project_id): Path<String>,
+    Query(query): Query<AccountQuery>,
+) -> ApiResult {
+    let account = find_account(&state, query.uid).await?;
+    let info = get_project(cookie_manager_of(&account)?, &project_id).await?;
+    Ok(Json(json!({
+        "project_detail": info.data.project_detail(),
+        "ticket_info": info.data,
+    })))
+}
+
+#[derive(Deserialize)]
+struct SeatMapQuery {
+    uid: Option<i64>,
+    #[serde(default)]
+    ticket_id: String,
+}
+
+// 选座项目某个场次的座位图
+async fn seat_map(
+    State(state): State<AppState>,
+    Path((project_id, screen_id)): Path<(String, String)>,
+    Query(query): Query<SeatMapQuery>,
+) -> ApiResult {
+    let account = find_account(&state, query.uid).await?;
+    let seat_map = get_seat_map(
+        cookie_manager_of(&account)?,
+        &project_id,
+        &screen_id,
+        &query.ticket_id,
+    )
+    .await?;
+    Ok(Json(json!(seat_map)))
+}
+
+#[derive(Deserialize)]
+struct SubmitTaskBody {
+    uid: Option<i64>,
+    profile_id: Option<String>, //指定时按保存的抢票配置提交，忽略项目、场次、票种等字段
+    #[serde(default)]
+    project_id: String,
+    #[serde(default)]
+    screen_id: String,
+    #[serde(default)]
+    ticket_id: String,
+    #[serde(default)]
+    buyer_ids: Vec<String>,
+    #[serde(default)]
+    grab_mode: u8,
+    #[serde(default)]
+    dry_run: bool, //试运行：只检查配置，不会下单
+    #[serde(default)]
+    seats: Option<SeatSelection>, //选座项目的指定座位或区域偏好，使用抢票配置时覆盖配置中的设置
+    #[serde(default)]
+    address_id: Option<String>, //快递配送的收货地址ID，未指定时使用账号的默认地址
+}
+
+// 提交抢票或试运行任务，抢票任务提交前先做配置检查
+async fn submit_task(State(state): State<AppState>, Json(body): Json<SubmitTaskBody>) -> ApiResult {
+    if body.grab_mode > 2 {
+        return Err(ApiError::bad_request(t("daemon.invalid_grab_mode")));
+    }
+    let mut request = match &body.profile_id {
+        Some(profile_id) => {
+            let config = Config::load_config().unwrap_or_else(|_| Config::new());
+            let profile = find_profile(&config, profile_id).map_err(ApiError::not_found)?;
+            let account = find_account(&state, body.uid.or(profile.uid)).await?;
+            build_profile_request(&account, cookie_manager_of(&account)?, &profile).await?
+        }
+        None => {
+            let account = find_account(&state, body.uid).await?;
+            build_grab_request(
+                &account,
+                cookie_manager_of(&account)?,
+                &body.project_id,
+                &body.screen_id,
+                &body.ticket_id,
+                &body.buyer_ids,
+                body.grab_mode,
+            )
+            .await?
+        }
+    };
+    if let Some(seats) = body.seats {
+        request.seat_selection = seats;
+    }
+    if let Some(address_id) = &body.address_id {
+        let address = resolve_address(request.cookie_manager.clone(), Some(address_id)).await?;
+        request.biliticket.deliver_info = Some(address);
+    }
+
+    let mut task_manager = state
+        .task_manager
+        .lock()
+        .map_err(|_| ApiError::internal(t("daemon.task_manager_lock_failed")))?;
+    let task = if body.dry_run {
+        TrackedTask::DryRun(
+            task_manager
+                .submit_dry_run(request)
+                .map_err(ApiError::bad_request)?,
+        )
+    } else {
+        let report = validate_task(&request);
+        if report.has_errors() {
+            let errors: Vec<String> = report
+                .issues
+                .iter()
+                .filter(|issue| issue.level == ValidationLevel::Error)
+                .map(|issue| format!("{}: {}", issue.field, issue.message))
+                .collect();
+            return Err(ApiError::bad_request(errors.join("; ")));
+        }
+        TrackedTask::Grab(
+            task_manager
+                .submit_grab_ticket(request)
+                .map_err(ApiError::bad_request)?,
+        )
+    };
+    drop(task_manager);
+
+    let summary = task.summary();
+    log::info!("守护进程已提交任务 ID: {}", task.id());
+    state.track(task).map_err(ApiError::internal)?;
+    Ok(Json(summary))
+}
+
+// 保存的抢票配置，提交任务时通过 profile_id 引用
+async fn list_profiles() -> ApiResult {
+    let config = Config::load_config().unwrap_or_else(|_| Config::new());
+    Ok(Json(json!(load_profiles(&config))))
+}
+
+// 运行中的任务和最近结束的任务
+async fn list_tasks(State(state): State<AppState>) -> ApiResult {
+    let running: Vec<Value> = state
+        .tasks
+        .lock()
+        .map_err(|_| ApiError::internal(t("daemon.task_list_lock_failed")))?
+        .iter()
+        .map(TrackedTask::summary)
+        .collect();
+    Ok(Json(json!({
+        "running": running,
+        "history": diagnostics::task_history(),
+    })))
+}
+
+async fn task_status(State(state): State<AppState>, Path(task_id): Path<String>) -> ApiResult {
+    let status = state
+        .task_manager
+        .lock()
+        .map_err(|_| ApiError::internal(t("daemon.task_manager_lock_failed")))?
+        .get_task_status(&task_id);
+    if let Some(status) = status {
+        return Ok(Json(json!({
+            "task_id": task_id,
+            "status": format!("{:?}", status),
+        })));
+    }
+    diagnostics::task_history()
+        .into_iter()
+        .rev()
+        .find(|record| record.task_id == task_id)
+        .map(|record| Json(json!(record)))
+        .ok_or_else(|| ApiError::not_found(t("task.not_found")))
+}
+
+async fn cancel_task(State(state): State<AppState>, Path(task_id): Path<String>) -> ApiResult {
+    state
+        .task_manager
+        .lock()
+        .map_err(|_| ApiError::internal(t("daemon.task_manager_lock_failed")))?
+        .cancel_task(&task_id)
+        .map_err(ApiError::not_found)?;
+    log::info!("守护进程已取消任务 ID: {}", task_id);
+    Ok(Json(json!({ "task_id": task_id, "cancelled": true })))
+}
+
+// 任务进度和结果，客户端保存返回的 next 作为下次的 since
+async fn poll_events(State(state): State<AppState>, Query(query): Query<SinceQuery>) -> ApiResult {
+    let (events, next) = state
+        .events
+        .lock()
+        .map_err(|_| ApiError::internal(t("daemon.event_list_lock_failed")))?
+        .since(query.since);
+    Ok(Json(json!({ "events": events, "next": next })))
+}
+
+async fn list_orders(
+    State(state): State<AppState>,
+    Query(query): Query<AccountQuery>,
+) -> ApiResult {
+    let account = find_account(&state, query.uid).await?;
+    let orders = get_orderlist(cookie_manager_of(&account)?).await?;
+    Ok(Json(json!(orders.data.list)))
+}
+
+async fn order_detail(
+    State(state): State<AppState>,
+    Path(order_id): Path<String>,
+    Query(query): Query<AccountQuery>,
+) -> ApiResult {
+    let account = find_account(&state, query.uid).await?;
+    let detail = get_order_detail(cookie_manager_of(&account)?, &order_id).await?;
+    Ok(Json(json!(detail)))
+}
+
+// 所有账号已支付订单的 iCalendar 日历
+async fn orders_calendar(State(state): State<AppState>) -> Result<Response, ApiError> {
+    let accounts = state.accounts.lock().await.clone();
+    let (content, _) = export_orders_calendar(&accounts).await?;
+    Ok((
+        [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+        content,
+    )
+        .into_response())
+}
+
+// 项目列表中保存的项目尚未开售的票档
+async fn upcoming_sales(state: &DaemonState) -> Result<Vec<SaleOpening>, ApiError> {
+    let config = Config::load_config().unwrap_or_else(|_| Config::new());
+    let account = find_account(state, None).await?;
+    Ok(fetch_upcoming_sales(cookie_manager_of(&account)?, &saved_project_ids(&config)).await)
+}
+
+async fn list_sales(State(state): State<AppState>) -> ApiResult {
+    Ok(Json(json!(upcoming_sales(&state).await?)))
+}
+
+async fn sales_calendar(State(state): State<AppState>) -> Result<Response, ApiError> {
+    let openings = upcoming_sales(&state).await?;
+    Ok((
+        [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+        common::ics::build_calendar(&sale_events(&openings)),
+    )
+        .into_response())
+}
+
+async fn poll_logs(State(state): State<AppState>, Query(query): Query<SinceQuery>) -> ApiResult {
+    let (logs, next) = state
+        .logs
+        .lock()
+        .map_err(|_| ApiError::internal(t("daemon.log_lock_failed")))?
+        .since(query.since);
+    Ok(Json(json!({ "logs": logs, "next": next })))
+}
@@ -0,0 +1,225 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use backend::taskmanager::TaskManagerImpl;
+use common::account::Account;
+use common::i18n::t;
+use common::task_handle::TaskHandle;
+use common::taskmanager::{DryRunResult, GrabTicketResult};
+use common::utils::Config;
+use serde_json::{Value, json};
+
+// 保留的事件和日志条数，轮询方跟不上时丢弃最旧的
+const MAX_EVENTS: usize = 1000;
+const MAX_LOGS: usize = 5000;
+
+// 带序号的环形缓冲，客户端按 since 增量拉取
+pub struct SeqBuffer {
+    next_seq: u64,
+    capacity: usize,
+    items: VecDeque<(u64, Value)>,
+}
+
+impl SeqBuffer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            next_seq: 1,
+            capacity,
+            items: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn push(&mut self, value: Value) {
+        if self.items.len() >= self.capacity {
+            self.items.pop_front();
+        }
+        self.items.push_back((self.next_seq, value));
+        self.next_seq += 1;
+    }
+
+    // 返回序号大于 since 的条目和下次拉取使用的序号
+    pub fn since(&self, since: u64) -> (Vec<Value>, u64) {
+        let items = self
+            .items
+            .iter()
+            .filter(|(seq, _)| *seq > since)
+            .map(|(seq, value)| {
+                let mut value = value.clone();
+                value["seq"] = json!(seq);
+                value
+            })
+            .collect();
+        (items, self.next_seq - 1)
+    }
+}
+
+// 守护进程提交的任务
+pub enum TrackedTask {
+    Grab(TaskHandle<GrabTicketResult>),
+    DryRun(TaskHandle<DryRunResult>),
+}
+
+impl TrackedTask {
+    pub fn id(&self) -> &str {
+        match self {
+            TrackedTask::Grab(handle) => handle.id(),
+            TrackedTask::DryRun(handle) => handle.id(),
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TrackedTask::Grab(_) => "grab",
+            TrackedTask::DryRun(_) => "dry_run",
+        }
+    }
+
+    pub fn summary(&self) -> Value {
+        let status = match self {
+            TrackedTask::Grab(handle) => handle.status(),
+            TrackedTask::DryRun(handle) => handle.status(),
+        };
+        json!({
+            "task_id": self.id(),
+            "kind": self.kind(),
+            "status": format!("{:?}", status),
+        })
+    }
+
+    // 取出新的进度消息和结果，返回任务是否已结束
+    fn collect(&mut self, events: &mut SeqBuffer) -> bool {
+        let task_id = self.id().to_string();
+        let progress = match self {
+            TrackedTask::Grab(handle) => handle.try_progress(),
+            TrackedTask::DryRun(handle) => handle.try_progress(),
+        };
+        for p in progress {
+            events.push(json!({
+                "type": "progress",
+                "task_id": p.task_id,
+                "message": p.message,
+            }));
+        }
+
+        let result = match self {
+            TrackedTask::Grab(handle) => handle.try_result().map(|r| r.map(grab_result_json)),
+            TrackedTask::DryRun(handle) => handle.try_result().map(|r| r.map(dry_run_result_json)),
+        };
+        match result {
+            None => false,
+            Some(Ok(value)) => {
+                events.push(value);
+                true
+            }
+            Some(Err(e)) => {
+                events.push(json!({
+                    "type": "error",
+                    "task_id": task_id,
+                    "message": e,
+                }));
+                true
+            }
+        }
+    }
+}
+
+fn grab_result_json(r: GrabTicketResult) -> Value {
+    json!({
+        "type": "GrabTicketResult",
+        "task_id": r.task_id,
+        "uid": r.uid,
+        "success": r.success,
+        "order_id": r.order_id,
+        "message": r.message,
+        "pay_result": r.pay_result,
+        "confirm_result": r.confirm_result,
+        "attempts": r.attempts,
+    })
+}
+
+fn dry_run_result_json(r: DryRunResult) -> Value {
+    json!({
+        "type": "DryRunResult",
+        "task_id": r.task_id,
+        "uid": r.uid,
+        "success": r.success,
+        "checks": r.checks,
+    })
+}
+
+pub struct DaemonState {
+    token: String,
+    pub task_manager: Mutex<TaskManagerImpl>,
+    pub accounts: tokio::sync::Mutex<Vec<Account>>,
+    pub tasks: Mutex<Vec<TrackedTask>>,
+    pub events: Mutex<SeqBuffer>,
+    pub logs: Mutex<SeqBuffer>,
+}
+
+impl DaemonState {
+    pub fn new(task_manager: TaskManagerImpl, token: String) -> Self {
+        Self {
+            token,
+            task_manager: Mutex::new(task_manager),
+            accounts: tokio::sync::Mutex::new(Vec::new()),
+            tasks: Mutex::new(Vec::new()),
+            events: Mutex::new(SeqBuffer::new(MAX_EVENTS)),
+            logs: Mutex::new(SeqBuffer::new(MAX_LOGS)),
+        }
+    }
+
+    // 逐字节比较，耗时与不匹配的位置无关
+    pub fn check_token(&self, token: &str) -> bool {
+        let expected = self.token.as_bytes();
+        let token = token.as_bytes();
+        expected.len() == token.len()
+            && expected
+                .iter()
+                .zip(token)
+                .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+                == 0
+    }
+
+    // 从配置重新读取账号并初始化请求客户端
+    pub async fn reload_accounts(&self) -> usize {
+        // CookieManager::new 的 future 不是 Send，放到阻塞线程中执行
+        let accounts = tokio::task::spawn_blocking(|| {
+            let handle = tokio::runtime::Handle::current();
+            let mut accounts = Config::load_all_accounts();
+            for account in &mut accounts {
+                handle.block_on(account.ensure_client());
+            }
+            accounts
+        })
+        .await
+        .unwrap_or_default();
+        let count = accounts.len();
+        *self.accounts.lock().await = accounts;
+        log::info!("已加载{}个账号", count);
+        count
+    }
+
+    pub fn track(&self, task: TrackedTask) -> Result<(), String> {
+        self.tasks
+            .lock()
+            .map_err(|_| t("daemon.task_list_lock_failed").to_string())?
+            .push(task);
+        Ok(())
+    }
+
+    // 定时收集任务事件和日志
+    pub fn collect(&self) {
+        if let (Ok(mut tasks), Ok(mut events)) = (self.tasks.lock(), self.events.lock()) {
+            tasks.retain_mut(|task| !task.collect(&mut events));
+        }
+        let logs = common::LOG_COLLECTOR
+            .lock()
+            .ok()
+            .and_then(|mut c| c.get_logs());
+        if let (Some(new_logs), Ok(mut buffer)) = (logs, self.logs.lock()) {
+            for line in new_logs {
+                buffer.push(json!({ "line": line }));
+            }
+        }
+    }
+}
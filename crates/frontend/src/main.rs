@@ -13,20 +13,39 @@ use serde_json::{Value, json};
 
 use tauri::State;
 
+use backend::grab_request::build_profile_request;
 use backend::taskmanager::TaskManagerImpl;
 use common::PushType;
 use common::account::{Account, add_account};
+use common::address::DeliveryAddress;
 use common::captcha::LocalCaptcha;
+use common::cookie_import::{ImportReport, apply_imported, import_sessions, parse_cookie_export};
+use common::grab_profile::{
+    GrabProfile, find_profile, load_profiles, remove_profile, upsert_profile,
+};
+use common::hooks::{self, CommandHook, HookPayload};
+use common::http_record;
+use common::i18n::{Lang, set_lang, t, tr};
 use common::login::LoginInput;
-use common::push::PushConfig;
-
+use common::money::Money;
+use common::notification::{NotifyEvent, QuietHours, RoutingRule};
+use common::pacer::{self, PacerConfig};
+use common::push::{ChannelPushResult, PushConfig};
+use common::retry::RetryPolicy;
+use common::sale_calendar::{SaleOpening, SaleReminderConfig, sale_events, saved_project_ids};
+use common::seat::{SeatMap, SeatSelection};
+use common::show_orderlist::OrderDetail;
+
+use common::task_handle::{TaskHandle, TaskOutput, TaskResultQueue};
 use common::taskmanager::{
-    GetAllorderRequest, GetBuyerInfoRequest, GetTicketInfoRequest, TaskManager, TaskRequest,
+    GetAllorderRequest, GetBuyerInfoRequest, GetTicketInfoRequest, GrabTicketRequest, TaskManager,
     TaskStatus,
 };
 use common::ticket::{BilibiliTicket, TicketInfo};
+use common::ticket_preference::TicketPreference;
 use common::utility::CustomConfig;
 use common::utils::{Config, save_config};
+use common::validate::{ValidationReport, validate_task};
 
 const APP_NAME: &str = "BTR";
 const APP_VERSION: &str = "6.6.2-indev";
@@ -82,6 +101,7 @@ struct AppStateInner {
 
     // Task management
     task_manager: Arc<Mutex<Box<dyn TaskManager>>>,
+    task_results: Arc<Mutex<TaskResultQueue>>, //界面轮询的任务结果
 
     // Config
     config: Config,
@@ -103,7 +123,11 @@ struct AppStateInner {
     confirm_ticket_info: Option<String>,
     selected_buyer_list: Option<Vec<common::ticket::BuyerInfo>>,
     selected_no_bind_buyer_info: Option<common::ticket::NoBindBuyerInfo>,
-    buyer_type: u8, // 0: 非实名购票人, 1: 实名购票人
+    buyer_type: u8,                            // 0: 非实名购票人, 1: 实名购票人
+    max_total_price: Option<Money>,            // 预算上限（含快递费）
+    ticket_preferences: Vec<TicketPreference>, // 捡漏模式偏好列表
+    seat_selection: SeatSelection,             // 选座项目的指定座位或区域偏好
+    delivery_address: Option<DeliveryAddress>, // 快递配送的收货地址
 
     // Buyer management
     show_add_buyer_window: Option<String>,
@@ -139,9 +163,37 @@ struct AccountSwitch {
     switch: bool,
 }
 
+impl AppStateInner {
+    fn app_info(&self) -> Value {
+        json!({
+            "app": self.app,
+            "version": self.version,
+            "running_status": self.running_status,
+            "machine_id": self.machine_id,
+            "announce1": self.announce1,
+            "announce2": self.announce2,
+            "announce3": self.announce3,
+            "announce4": self.announce4
+        })
+    }
+
+    // 登记任务句柄，结果由 poll_task_results 统一返回给界面
+    fn track_task<T: TaskOutput>(&self, handle: TaskHandle<T>) -> Result<String, String> {
+        Ok(self
+            .task_results
+            .lock()
+            .map_err(|_| "Failed to lock task results".to_string())?
+            .track(handle))
+    }
+}
+
 impl AppState {
     pub fn new() -> Self {
         let config = Config::load_config().unwrap_or_else(|_| Config::new());
+        pacer::set_config(PacerConfig::load(&config));
+        if let Some(lang) = config["language"].as_str().and_then(Lang::parse) {
+            set_lang(lang);
+        }
 
         let mut state = AppStateInner {
             app: APP_NAME.to_string(),
@@ -151,11 +203,11 @@ impl AppState {
             machine_id: common::machine_id::get_machine_id_ob(),
             selected_tab: 0,
             is_loading: false,
-            running_status: "空闲".to_string(),
+            running_status: t("gui.status.idle").to_string(),
             logs: Vec::new(),
             show_log_window: false,
             show_login_window: false,
-            login_method: "扫码登录".to_string(),
+            login_method: t("gui.login_method.qrcode").to_string(),
             client: Client::new(),
             default_ua: default_user_agent(),
             login_qrcode_url: None,
@@ -174,13 +226,15 @@ impl AppState {
             delete_account: None,
             account_switch: None,
             task_manager: Arc::new(Mutex::new(Box::new(TaskManagerImpl::new()))),
+            task_results: Arc::new(Mutex::new(TaskResultQueue::new())),
             config: config.clone(),
             push_config: serde_json::from_value::<PushConfig>(config["push_config"].clone())
                 .unwrap_or_else(|_| PushConfig::new()),
             custom_config: serde_json::from_value::<CustomConfig>(config["custom_config"].clone())
                 .unwrap_or_else(|_| CustomConfig::new()),
             ticket_id: String::new(),
-            status_delay: 2,
+            // 延迟时间只用于捡漏模式
+            status_delay: (RetryPolicy::load(&config, 2).poll_interval_ms / 1000).max(1) as usize,
             grab_mode: 0,
             selected_account_uid: None,
             bilibiliticket_list: Vec::new(),
@@ -194,6 +248,10 @@ impl AppState {
             selected_buyer_list: None,
             selected_no_bind_buyer_info: None,
             buyer_type: 1, // 默认使用实名购票人
+            max_total_price: None,
+            ticket_preferences: Vec::new(),
+            seat_selection: SeatSelection::default(),
+            delivery_address: None,
             show_add_buyer_window: None,
             show_orderlist_window: None,
             total_order_data: None,
@@ -224,9 +282,16 @@ impl AppState {
         state.client = new_client;
 
         // Initialize accounts
+        let handle = state.task_manager.lock().unwrap().runtime_handle();
         for account in &mut state.accounts {
-            account.ensure_client();
+            handle.block_on(account.ensure_client());
         }
+        state
+            .task_manager
+            .lock()
+            .unwrap()
+            .set_hooks(hooks::load_hooks(&state.config));
+        handle.spawn(backend::sale_calendar::run_sale_reminders());
 
         Self {
             inner: Arc::new(Mutex::new(state)),
@@ -234,6 +299,18 @@ impl AppState {
     }
 }
 
+impl AppStateInner {
+    // 在任务管理器的运行时上同步执行异步请求，仅供界面命令使用
+    fn block_on<F: std::future::Future>(&self, future: F) -> Result<F::Output, String> {
+        let handle = self
+            .task_manager
+            .lock()
+            .map_err(|_| "task manager lock failed".to_string())?
+            .runtime_handle();
+        Ok(handle.block_on(future))
+    }
+}
+
 #[tauri::command]
 fn get_accounts(state: State<'_, AppState>) -> Result<Vec<Account>, String> {
     let state = state
@@ -259,13 +336,109 @@ fn add_account_by_cookie(state: State<'_, AppState>, cookie: String) -> Result<A
         .inner
         .lock()
         .map_err(|_| "state lock failed".to_string())?;
-    let account = add_account(&cookie, &state.client, &state.default_ua)?;
+    let account = state.block_on(add_account(&cookie, &state.client, &state.default_ua))??;
     save_config(&mut state.config, None, None, Some(account.clone()))
         .map_err(|e| format!("save config failed: {}", e))?;
     state.accounts.push(account.clone());
     Ok(account)
 }
 
+#[tauri::command]
+fn import_accounts(
+    state: State<'_, AppState>,
+    content: String,
+) -> Result<Vec<ImportReport>, String> {
+    let mut state = state
+        .inner
+        .lock()
+        .map_err(|_| "state lock failed".to_string())?;
+    let sessions = parse_cookie_export(&content)?;
+    let results = state.block_on(import_sessions(sessions, &state.client, &state.default_ua))?;
+    let AppStateInner {
+        config, accounts, ..
+    } = &mut *state;
+    apply_imported(config, accounts, results)
+}
+
+#[tauri::command]
+fn export_accounts(state: State<'_, AppState>, uids: Option<Vec<i64>>) -> Result<String, String> {
+    let state = state
+        .inner
+        .lock()
+        .map_err(|_| "state lock failed".to_string())?;
+    common::cookie_import::export_accounts(&state.accounts, &uids.unwrap_or_default())
+}
+
+// 导出所有账号已支付订单的 iCalendar 日历
+#[tauri::command]
+fn export_orders_calendar(state: State<'_, AppState>) -> Result<Value, String> {
+    let state = state
+        .inner
+        .lock()
+        .map_err(|_| "state lock failed".to_string())?;
+    let (content, count) = state.block_on(backend::show_orderlist::export_orders_calendar(
+        &state.accounts,
+    ))??;
+    Ok(json!({ "content": content, "count": count }))
+}
+
+impl AppStateInner {
+    // 获取已保存项目中尚未开售的票档
+    fn upcoming_sales(&self) -> Result<Vec<SaleOpening>, String> {
+        let cookie_manager = backend::sale_calendar::query_account(&self.accounts)
+            .and_then(|account| account.cookie_manager.clone())
+            .ok_or_else(|| t("sale.no_account").to_string())?;
+        let project_ids = saved_project_ids(&self.config);
+        self.block_on(backend::sale_calendar::fetch_upcoming_sales(
+            cookie_manager,
+            &project_ids,
+        ))
+    }
+}
+
+// 已保存项目的开售日历和提醒设置
+#[tauri::command]
+fn get_sale_calendar(state: State<'_, AppState>) -> Result<Value, String> {
+    let state = state
+        .inner
+        .lock()
+        .map_err(|_| "state lock failed".to_string())?;
+    let openings = state.upcoming_sales()?;
+    Ok(json!({
+        "openings": openings,
+        "reminders": SaleReminderConfig::load(&state.config),
+    }))
+}
+
+// 导出开售日历为 iCalendar 文件
+#[tauri::command]
+fn export_sale_calendar(state: State<'_, AppState>) -> Result<Value, String> {
+    let state = state
+        .inner
+        .lock()
+        .map_err(|_| "state lock failed".to_string())?;
+    let openings = state.upcoming_sales()?;
+    let content = common::ics::build_calendar(&sale_events(&openings));
+    Ok(json!({ "content": content, "count": openings.len() }))
+}
+
+// 保存开售提醒设置，后台提醒在下次刷新时生效
+#[tauri::command]
+fn set_sale_reminders(
+    state: State<'_, AppState>,
+    reminders: SaleReminderConfig,
+) -> Result<(), String> {
+    let mut state = state
+        .inner
+        .lock()
+        .map_err(|_| "state lock failed".to_string())?;
+    state.config["sale_reminders"] = json!(reminders);
+    state
+        .config
+        .save_config()
+        .map_err(|e| tr("gui.save_config_failed", &[&e]))
+}
+
 #[tauri::command]
 fn delete_account_by_uid(state: State<'_, AppState>, uid: i64) -> Result<bool, String> {
     let mut state = state
@@ -304,8 +477,9 @@ fn qrcode_login(state: State<'_, AppState>) -> Result<serde_json::Value, String>
         .lock()
         .map_err(|_| "state lock failed".to_string())?;
 
-    let qrcode_key =
-        common::login::qrcode_login(&state.client).map_err(|e| format!("生成二维码失败: {}", e))?;
+    let qrcode_key = state
+        .block_on(common::login::qrcode_login(&state.client))?
+        .map_err(|e| tr("gui.qrcode.generate_failed", &[&e]))?;
 
     let qrcode_url = format!(
         "https://passport.bilibili.com/h5-app/passport/login/scan?qrcode_key={}",
@@ -315,7 +489,8 @@ fn qrcode_login(state: State<'_, AppState>) -> Result<serde_json::Value, String>
     use image::Luma;
     use qrcode::QrCode;
 
-    let code = QrCode::new(qrcode_url.as_bytes()).map_err(|e| format!("生成二维码失败: {}", e))?;
+    let code =
+        QrCode::new(qrcode_url.as_bytes()).map_err(|e| tr("gui.qrcode.generate_failed", &[&e]))?;
 
     let image = code
         .render::<Luma<u8>>()
@@ -329,30 +504,31 @@ fn qrcode_login(state: State<'_, AppState>) -> Result<serde_json::Value, String>
             &mut std::io::Cursor::new(&mut png_data),
             image::ImageFormat::Png,
         )
-        .map_err(|e| format!("转换图片失败: {}", e))?;
+        .map_err(|e| tr("gui.qrcode.image_failed", &[&e]))?;
 
     let base64_image =
         base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &png_data);
     let data_url = format!("data:image/png;base64,{}", base64_image);
 
-    let request = TaskRequest::QrCodeLoginRequest(common::taskmanager::QrCodeLoginRequest {
+    let request = common::taskmanager::QrCodeLoginRequest {
         qrcode_key: qrcode_key.clone(),
         qrcode_url: qrcode_url.clone(),
         user_agent: Some(state.default_ua.clone()),
-    });
+    };
 
-    let task_id = state
+    let handle = state
         .task_manager
         .lock()
         .map_err(|_| "Failed to lock task manager".to_string())?
-        .submit_task(request)
-        .map_err(|e| format!("提交二维码登录任务失败: {}", e))?;
+        .submit_qrcode_login(request)
+        .map_err(|e| tr("gui.qrcode.submit_failed", &[&e]))?;
+    let task_id = state.track_task(handle)?;
 
     Ok(json!({
         "key": qrcode_key,
         "url": data_url,
         "task_id": task_id,
-        "message": "二维码生成成功，请使用B站APP扫描"
+        "message": t("gui.qrcode.generated")
     }))
 }
 
@@ -363,21 +539,21 @@ fn sms_login(state: State<'_, AppState>, phone: String) -> Result<String, String
         .lock()
         .map_err(|_| "state lock failed".to_string())?;
 
-    let request = TaskRequest::LoginSmsRequest(common::taskmanager::LoginSmsRequest {
+    let request = common::taskmanager::LoginSmsRequest {
         phone: phone.clone(),
         client: state.client.clone(),
         custom_config: state.custom_config.clone(),
         local_captcha: common::captcha::LocalCaptcha::new(),
-    });
+    };
 
-    let result = state
+    let handle = state
         .task_manager
         .lock()
         .map_err(|_| "Failed to lock task manager".to_string())?
-        .submit_task(request)
-        .map_err(|e| format!("submit sms login failed: {}", e));
+        .submit_login_sms(request)
+        .map_err(|e| format!("submit sms login failed: {}", e))?;
 
-    result
+    state.track_task(handle)
 }
 
 #[tauri::command]
@@ -391,21 +567,21 @@ fn submit_sms_code(
         .lock()
         .map_err(|_| "state lock failed".to_string())?;
 
-    let request = TaskRequest::SubmitLoginSmsRequest(common::taskmanager::SubmitLoginSmsRequest {
+    let request = common::taskmanager::SubmitLoginSmsRequest {
         phone: "".to_string(),
         code: sms_code,
         captcha_key,
         client: state.client.clone(),
-    });
+    };
 
-    let result = state
+    let handle = state
         .task_manager
         .lock()
         .map_err(|_| "Failed to lock task manager".to_string())?
-        .submit_task(request)
-        .map_err(|e| format!("submit sms code failed: {}", e));
+        .submit_sms_login(request)
+        .map_err(|e| format!("submit sms code failed: {}", e))?;
 
-    result
+    state.track_task(handle)
 }
 
 #[tauri::command]
@@ -431,21 +607,21 @@ fn get_ticket_info(
         .ok_or_else(|| "cookie manager not initialized".to_string())?;
 
     let task_id = uuid::Uuid::new_v4().to_string();
-    let request = TaskRequest::GetTicketInfoRequest(GetTicketInfoRequest {
+    let request = GetTicketInfoRequest {
         uid,
         task_id: task_id.clone(),
         project_id,
         cookie_manager,
-    });
+    };
 
-    let result = state
+    let handle = state
         .task_manager
         .lock()
         .map_err(|_| "Failed to lock task manager".to_string())?
-        .submit_task(request)
-        .map_err(|e| format!("submit ticket info request failed: {}", e));
+        .submit_get_ticket_info(request)
+        .map_err(|e| format!("submit ticket info request failed: {}", e))?;
 
-    result
+    state.track_task(handle)
 }
 
 #[tauri::command]
@@ -467,20 +643,20 @@ fn get_buyer_info(state: State<'_, AppState>, uid: i64) -> Result<String, String
         .ok_or_else(|| "cookie manager not initialized".to_string())?;
 
     let task_id = uuid::Uuid::new_v4().to_string();
-    let request = TaskRequest::GetBuyerInfoRequest(GetBuyerInfoRequest {
+    let request = GetBuyerInfoRequest {
         uid,
         task_id: task_id.clone(),
         cookie_manager,
-    });
+    };
 
-    let result = state
+    let handle = state
         .task_manager
         .lock()
         .map_err(|_| "Failed to lock task manager".to_string())?
-        .submit_task(request)
-        .map_err(|e| format!("submit buyer info request failed: {}", e));
+        .submit_get_buyer_info(request)
+        .map_err(|e| format!("submit buyer info request failed: {}", e))?;
 
-    result
+    state.track_task(handle)
 }
 
 #[tauri::command]
@@ -501,23 +677,49 @@ fn get_order_list(state: State<'_, AppState>, uid: i64) -> Result<String, String
         .clone()
         .ok_or_else(|| "cookie manager not initialized".to_string())?;
 
-    let request = TaskRequest::GetAllorderRequest(GetAllorderRequest {
+    let request = GetAllorderRequest {
         task_id: "".to_string(),
         cookie_manager,
         status: TaskStatus::Pending,
         cookies: account.cookie.clone(),
         account_id: uid.to_string(),
         start_time: None,
-    });
+    };
 
-    let result = state
+    let handle = state
         .task_manager
         .lock()
         .map_err(|_| "Failed to lock task manager".to_string())?
-        .submit_task(request)
-        .map_err(|e| format!("submit order list request failed: {}", e));
+        .submit_get_orders(request)
+        .map_err(|e| format!("submit order list request failed: {}", e))?;
+
+    state.track_task(handle)
+}
+
+#[tauri::command]
+fn get_order_detail(
+    state: State<'_, AppState>,
+    uid: i64,
+    order_id: String,
+) -> Result<OrderDetail, String> {
+    let state = state
+        .inner
+        .lock()
+        .map_err(|_| "state lock failed".to_string())?;
 
-    result
+    let cookie_manager = state
+        .accounts
+        .iter()
+        .find(|a| a.uid == uid)
+        .ok_or_else(|| "account not found".to_string())?
+        .cookie_manager
+        .clone()
+        .ok_or_else(|| "cookie manager not initialized".to_string())?;
+
+    state.block_on(backend::show_orderlist::get_order_detail(
+        cookie_manager,
+        &order_id,
+    ))?
 }
 
 #[tauri::command]
@@ -528,10 +730,10 @@ fn poll_task_results(state: State<'_, AppState>) -> Result<Value, String> {
         .map_err(|_| "state lock failed".to_string())?;
 
     let results = state
-        .task_manager
+        .task_results
         .lock()
-        .map_err(|_| "Failed to lock task manager".to_string())?
-        .get_results();
+        .map_err(|_| "Failed to lock task results".to_string())?
+        .drain();
     let json_results: Vec<Value> = results
         .into_iter()
         .map(|result| match result {
@@ -556,7 +758,8 @@ fn poll_task_results(state: State<'_, AppState>) -> Result<Value, String> {
             common::taskmanager::TaskResult::PushResult(r) => json!({
                 "type": "PushResult",
                 "success": r.success,
-                "message": r.message
+                "message": r.message,
+                "channels": r.channels
             }),
             common::taskmanager::TaskResult::GetAllorderRequestResult(r) => json!({
                 "type": "GetAllorderRequestResult",
@@ -572,6 +775,7 @@ fn poll_task_results(state: State<'_, AppState>) -> Result<Value, String> {
                 "success": r.success,
                 "uid": r.uid,
                 "message": r.message,
+                "project_detail": r.ticket_info.as_ref().map(|info| info.data.project_detail()),
                 "ticket_info": r.ticket_info
             }),
             common::taskmanager::TaskResult::GetBuyerInfoResult(r) => json!({
@@ -582,13 +786,22 @@ fn poll_task_results(state: State<'_, AppState>) -> Result<Value, String> {
                 "message": r.message,
                 "buyer_info": r.buyer_info
             }),
+            common::taskmanager::TaskResult::DryRunResult(r) => json!({
+                "type": "DryRunResult",
+                "task_id": r.task_id,
+                "uid": r.uid,
+                "success": r.success,
+                "checks": r.checks
+            }),
             common::taskmanager::TaskResult::GrabTicketResult(r) => json!({
                 "type": "GrabTicketResult",
+                "task_id": r.task_id,
                 "success": r.success,
                 "order_id": r.order_id,
                 "message": r.message,
                 "pay_result": r.pay_result,
-                "confirm_result": r.confirm_result
+                "confirm_result": r.confirm_result,
+                "attempts": r.attempts
             }),
         })
         .collect();
@@ -596,41 +809,45 @@ fn poll_task_results(state: State<'_, AppState>) -> Result<Value, String> {
     Ok(json!(json_results))
 }
 
+// 测试推送：未指定渠道时推送到全部已启用渠道；传入 push_config 时使用设置页中尚未保存的配置
 #[tauri::command]
-fn push_test(state: State<'_, AppState>, title: String, message: String) -> Result<(), String> {
-    let push_config = {
-        let state = state
-            .inner
-            .lock()
-            .map_err(|_| "state lock failed".to_string())?;
-        if !state.push_config.enabled {
-            return Err("push is disabled".to_string());
+async fn push_test(
+    state: State<'_, AppState>,
+    push_type: Option<String>,
+    push_config: Option<PushConfig>,
+) -> Result<Vec<ChannelPushResult>, String> {
+    let push_type = match push_type {
+        Some(method) => {
+            PushType::from_method(&method).ok_or_else(|| tr("push.unknown_channel", &[&method]))?
         }
-        state.push_config.clone()
+        None => PushType::All,
     };
+    let push_config = match push_config {
+        Some(config) => config,
+        None => {
+            let state = state
+                .inner
+                .lock()
+                .map_err(|_| "state lock failed".to_string())?;
+            state.push_config.clone()
+        }
+    };
+    if push_type == PushType::All && !push_config.enabled {
+        return Err(t("push.disabled").to_string());
+    }
 
-    let request = TaskRequest::PushRequest(common::taskmanager::PushRequest {
-        title,
-        message,
-        jump_url: None,
-        push_config,
-        push_type: PushType::All,
-    });
-
-    {
-        let state = state
-            .inner
-            .lock()
-            .map_err(|_| "state lock failed".to_string())?;
-        let result = state
-            .task_manager
-            .lock()
-            .map_err(|_| "Failed to lock task manager".to_string())?
-            .submit_task(request)
-            .map(|_| ())
-            .map_err(|e| format!("submit push failed: {}", e));
-        result
+    let results = push_config
+        .push_channels(
+            &push_type,
+            t("push.test.title"),
+            &tr("push.test.body", &[&push_type.display_name()]),
+            &None,
+        )
+        .await;
+    if results.is_empty() {
+        return Err(t("push.summary.no_channel").to_string());
     }
+    Ok(results)
 }
 
 #[tauri::command]
@@ -794,16 +1011,23 @@ fn get_app_info(state: State<'_, AppState>) -> Result<Value, String> {
         .inner
         .lock()
         .map_err(|_| "state lock failed".to_string())?;
-    Ok(json!({
-        "app": state.app,
-        "version": state.version,
-        "running_status": state.running_status,
-        "machine_id": state.machine_id,
-        "announce1": state.announce1,
-        "announce2": state.announce2,
-        "announce3": state.announce3,
-        "announce4": state.announce4
-    }))
+    Ok(state.app_info())
+}
+
+// 生成诊断包：脱敏后的日志、配置摘要、版本信息、任务历史和错误码统计
+#[tauri::command]
+fn export_diagnostics(state: State<'_, AppState>) -> Result<String, String> {
+    // 读取日志和写入压缩包较慢，先复制所需数据再释放状态锁
+    let (mut app_info, config) = {
+        let state = state
+            .inner
+            .lock()
+            .map_err(|_| "state lock failed".to_string())?;
+        (state.app_info(), state.config.clone())
+    };
+    app_info["language"] = json!(common::i18n::current_lang().code());
+    app_info["http_record_session"] = json!(http_record::current_session());
+    common::diagnostics::build_bundle(app_info, &config, None)
 }
 
 #[tauri::command]
@@ -834,6 +1058,113 @@ fn set_grab_mode(state: State<'_, AppState>, mode: u8) -> Result<(), String> {
     Ok(())
 }
 
+#[tauri::command]
+fn set_max_total_price(state: State<'_, AppState>, price: Option<String>) -> Result<(), String> {
+    let mut state = state
+        .inner
+        .lock()
+        .map_err(|_| "state lock failed".to_string())?;
+    state.max_total_price = match price.as_deref().map(str::trim) {
+        Some(text) if !text.is_empty() => Some(Money::parse_yuan(text)?),
+        _ => None,
+    };
+    Ok(())
+}
+
+// 获取选座项目某个场次的座位图，使用选中的账号或启用的账号
+#[tauri::command]
+fn get_seat_map(
+    state: State<'_, AppState>,
+    project_id: String,
+    screen_id: String,
+    ticket_id: String,
+) -> Result<SeatMap, String> {
+    let state = state
+        .inner
+        .lock()
+        .map_err(|_| "state lock failed".to_string())?;
+    let account = match state.selected_account_uid {
+        Some(uid) => state.accounts.iter().find(|acc| acc.uid == uid),
+        None => state.accounts.iter().find(|acc| acc.is_active),
+    }
+    .ok_or_else(|| t("task.no_active_account").to_string())?;
+    let cookie_manager = account
+        .cookie_manager
+        .clone()
+        .ok_or_else(|| t("task.account_uninitialized").to_string())?;
+    state.block_on(backend::api::get_seat_map(
+        cookie_manager,
+        &project_id,
+        &screen_id,
+        &ticket_id,
+    ))?
+}
+
+#[tauri::command]
+fn set_seat_selection(state: State<'_, AppState>, selection: SeatSelection) -> Result<(), String> {
+    let mut state = state
+        .inner
+        .lock()
+        .map_err(|_| "state lock failed".to_string())?;
+    log::info!(
+        "设置选座：指定座位{}个，区域偏好{}个",
+        selection.seat_ids.len(),
+        selection.areas.len()
+    );
+    state.seat_selection = selection;
+    Ok(())
+}
+
+// 获取账号保存的收货地址，使用选中的账号或启用的账号
+#[tauri::command]
+fn get_address_list(state: State<'_, AppState>) -> Result<Vec<DeliveryAddress>, String> {
+    let state = state
+        .inner
+        .lock()
+        .map_err(|_| "state lock failed".to_string())?;
+    let account = match state.selected_account_uid {
+        Some(uid) => state.accounts.iter().find(|acc| acc.uid == uid),
+        None => state.accounts.iter().find(|acc| acc.is_active),
+    }
+    .ok_or_else(|| t("task.no_active_account").to_string())?;
+    let cookie_manager = account
+        .cookie_manager
+        .clone()
+        .ok_or_else(|| t("task.account_uninitialized").to_string())?;
+    state.block_on(backend::api::get_address_list(cookie_manager))?
+}
+
+#[tauri::command]
+fn set_delivery_address(
+    state: State<'_, AppState>,
+    address: Option<DeliveryAddress>,
+) -> Result<(), String> {
+    let mut state = state
+        .inner
+        .lock()
+        .map_err(|_| "state lock failed".to_string())?;
+    match &address {
+        Some(address) => log::info!("设置收货地址ID：{}", address.id),
+        None => log::info!("清除收货地址"),
+    }
+    state.delivery_address = address;
+    Ok(())
+}
+
+#[tauri::command]
+fn set_ticket_preferences(
+    state: State<'_, AppState>,
+    preferences: Vec<TicketPreference>,
+) -> Result<(), String> {
+    let mut state = state
+        .inner
+        .lock()
+        .map_err(|_| "state lock failed".to_string())?;
+    log::info!("设置捡漏偏好{}条", preferences.len());
+    state.ticket_preferences = preferences;
+    Ok(())
+}
+
 #[tauri::command]
 fn cancel_task(state: State<'_, AppState>, task_id: String) -> Result<(), String> {
     let mut state = state
@@ -852,20 +1183,81 @@ fn cancel_task(state: State<'_, AppState>, task_id: String) -> Result<(), String
     Ok(())
 }
 
+// 指定 profile_id 时按保存的抢票配置提交，否则使用界面当前的选择
+#[tauri::command]
+fn start_grab_ticket(
+    state: State<'_, AppState>,
+    profile_id: Option<String>,
+) -> Result<String, String> {
+    submit_grab_ticket(&state, false, profile_id)
+}
+
+// 试运行：检查账号、项目、购票人和确认订单，不会下单
+#[tauri::command]
+fn dry_run_grab_ticket(
+    state: State<'_, AppState>,
+    profile_id: Option<String>,
+) -> Result<String, String> {
+    submit_grab_ticket(&state, true, profile_id)
+}
+
+// 提交前检查抢票配置，返回错误和警告列表
 #[tauri::command]
-fn start_grab_ticket(state: State<'_, AppState>) -> Result<String, String> {
+fn validate_grab_ticket(state: State<'_, AppState>) -> Result<ValidationReport, String> {
     let state = state
         .inner
         .lock()
         .map_err(|_| "state lock failed".to_string())?;
+    let grab_request = build_grab_request(&state, false)?;
+    Ok(validate_task(&grab_request))
+}
 
+fn submit_grab_ticket(
+    state: &AppState,
+    dry_run: bool,
+    profile_id: Option<String>,
+) -> Result<String, String> {
+    let state = state
+        .inner
+        .lock()
+        .map_err(|_| "state lock failed".to_string())?;
+
+    let grab_request = match profile_id {
+        Some(id) => build_profile_grab_request(&state, &id, dry_run)?,
+        None => build_grab_request(&state, dry_run)?,
+    };
+    let task_manager = state.task_manager.clone();
+    let mut task_manager = task_manager
+        .lock()
+        .map_err(|_| "Failed to lock task manager".to_string())?;
+
+    // 试运行本身就是检查，不因检查错误拒绝提交
+    if dry_run {
+        let handle = task_manager
+            .submit_dry_run(grab_request)
+            .map_err(|e| tr("task.submit_failed", &[&e]))?;
+        return state.track_task(handle);
+    }
+    let report = validate_task(&grab_request);
+    if report.has_errors() {
+        return Err(tr("validate.rejected", &[&report.error_summary()]));
+    }
+
+    // 提交任务，开始和结束的推送由任务管理器发出
+    let handle = task_manager
+        .submit_grab_ticket(grab_request)
+        .map_err(|e| tr("task.submit_failed", &[&e]))?;
+    state.track_task(handle)
+}
+
+fn build_grab_request(state: &AppStateInner, dry_run: bool) -> Result<GrabTicketRequest, String> {
     // 验证必要信息
     if state.ticket_id.is_empty() {
-        return Err("请先选择项目".to_string());
+        return Err(t("task.no_project").to_string());
     }
 
     if state.accounts.is_empty() {
-        return Err("请先添加账号".to_string());
+        return Err(t("task.no_account").to_string());
     }
 
     // 获取选中的账号或使用第一个活跃账号
@@ -875,42 +1267,45 @@ fn start_grab_ticket(state: State<'_, AppState>) -> Result<String, String> {
         state.accounts.iter().find(|acc| acc.is_active)
     };
 
-    let account = selected_account
-        .ok_or_else(|| "没有可用的账号，请确保至少有一个账号是激活状态".to_string())?;
+    let account = selected_account.ok_or_else(|| t("task.no_active_account").to_string())?;
 
     // 验证账号有 cookie_manager
     let cookie_manager = account
         .cookie_manager
         .clone()
-        .ok_or_else(|| "账号未初始化，请重新添加账号".to_string())?;
+        .ok_or_else(|| t("task.account_uninitialized").to_string())?;
 
     let (id_bind, buyer_info, no_bind_buyer_info) = match state.buyer_type {
         0 => {
             // 非实名购票人信息
             if state.selected_no_bind_buyer_info.is_none() {
-                return Err("请先设置非实名购票人信息".to_string());
+                return Err(t("task.no_bind_buyer_missing").to_string());
             }
             (0, None, state.selected_no_bind_buyer_info.clone())
         }
         1 => {
             // 实名购票人信息
             if state.selected_buyer_list.is_none() {
-                return Err("请先选择实名购票人信息".to_string());
+                return Err(t("task.buyer_missing").to_string());
             }
             (1, state.selected_buyer_list.clone(), None)
         }
         2 => {
             // 实名购票人信息（备用模式）
             if state.selected_buyer_list.is_none() {
-                return Err("请先选择实名购票人信息".to_string());
+                return Err(t("task.buyer_missing").to_string());
             }
             (2, state.selected_buyer_list.clone(), None)
         }
         _ => {
-            return Err("无效的购票人类型".to_string());
+            return Err(t("task.invalid_buyer_type").to_string());
         }
     };
 
+    // 实名票每位购票人一张，非实名票只能买一张
+    let count = buyer_info.as_ref().map_or(1, |buyers| buyers.len().max(1));
+    let buyers = buyer_info.clone().unwrap_or_default();
+
     let biliticket = BilibiliTicket {
         uid: account.uid,
         method: 0,
@@ -931,9 +1326,10 @@ fn start_grab_ticket(state: State<'_, AppState>) -> Result<String, String> {
         all_buyer_info: None,
         buyer_info,
         no_bind_buyer_info,
+        deliver_info: state.delivery_address.clone(),
         select_ticket_id: state.selected_ticket_id.map(|id| id.to_string()),
         pay_money: None,
-        count: Some(1),
+        count: Some(count as i32),
         device_id: String::new(),
     };
 
@@ -948,8 +1344,8 @@ fn start_grab_ticket(state: State<'_, AppState>) -> Result<String, String> {
     );
 
     // 创建抢票请求
-    let grab_request = TaskRequest::GrabTicketRequest(common::taskmanager::GrabTicketRequest {
-        task_id: task_id.clone(),
+    Ok(GrabTicketRequest {
+        task_id,
         uid: account.uid,
         project_id: state.ticket_id.clone(),
         screen_id: state
@@ -960,8 +1356,8 @@ fn start_grab_ticket(state: State<'_, AppState>) -> Result<String, String> {
             .selected_ticket_id
             .map(|id| id.to_string())
             .unwrap_or_default(),
-        count: 1,
-        buyer_info: vec![],
+        count: count as i16,
+        buyer_info: buyers,
         cookie_manager,
         biliticket,
         grab_mode: state.grab_mode,
@@ -970,17 +1366,138 @@ fn start_grab_ticket(state: State<'_, AppState>) -> Result<String, String> {
         is_hot: false,
         local_captcha: LocalCaptcha::new(),
         skip_words: None,
-    });
+        max_total_price: state.max_total_price,
+        preferences: state.ticket_preferences.clone(),
+        dry_run,
+        hooks: Default::default(),
+        retry_policy: RetryPolicy::load(&state.config, state.grab_mode),
+        seat_selection: state.seat_selection.clone(),
+    })
+}
+
+// 按抢票配置构造请求，项目和购票人信息重新获取，不影响界面当前的选择
+fn build_profile_grab_request(
+    state: &AppStateInner,
+    profile_id: &str,
+    dry_run: bool,
+) -> Result<GrabTicketRequest, String> {
+    let profile = find_profile(&state.config, profile_id)?;
+    let account = match profile.uid {
+        Some(uid) => state.accounts.iter().find(|acc| acc.uid == uid),
+        None => state.accounts.iter().find(|acc| acc.is_active),
+    }
+    .ok_or_else(|| t("task.no_active_account").to_string())?;
+    let cookie_manager = account
+        .cookie_manager
+        .clone()
+        .ok_or_else(|| t("task.account_uninitialized").to_string())?;
 
-    // 提交任务
+    let mut request =
+        state.block_on(build_profile_request(account, cookie_manager, &profile))??;
+    request.dry_run = dry_run;
+    Ok(request)
+}
+
+#[tauri::command]
+fn get_grab_profiles(state: State<'_, AppState>) -> Result<Vec<GrabProfile>, String> {
+    let state = state
+        .inner
+        .lock()
+        .map_err(|_| "state lock failed".to_string())?;
+    Ok(load_profiles(&state.config))
+}
+
+// 新增或更新抢票配置，id 为空时新增
+#[tauri::command]
+fn save_grab_profile(
+    state: State<'_, AppState>,
+    profile: GrabProfile,
+) -> Result<GrabProfile, String> {
+    let mut state = state
+        .inner
+        .lock()
+        .map_err(|_| "state lock failed".to_string())?;
+    let profile = upsert_profile(&mut state.config, profile)?;
     state
-        .task_manager
+        .config
+        .save_config()
+        .map_err(|e| tr("profile.save_failed", &[&e]))?;
+    log::info!("抢票配置已保存: {}（ID: {}）", profile.name, profile.id);
+    Ok(profile)
+}
+
+// 把界面当前的账号、项目、场次、票种、购票人等选择保存为抢票配置
+#[tauri::command]
+fn save_current_grab_profile(
+    state: State<'_, AppState>,
+    name: String,
+    id: Option<String>,
+) -> Result<GrabProfile, String> {
+    let mut state = state
+        .inner
         .lock()
-        .map_err(|_| "Failed to lock task manager".to_string())?
-        .submit_task(grab_request)
-        .map_err(|e| format!("提交抢票任务失败: {}", e))?;
+        .map_err(|_| "state lock failed".to_string())?;
+
+    let buyer_ids = match state.buyer_type {
+        0 => Vec::new(),
+        _ => state
+            .selected_buyer_list
+            .as_ref()
+            .ok_or_else(|| t("task.buyer_missing").to_string())?
+            .iter()
+            .map(|buyer| buyer.id.to_string())
+            .collect(),
+    };
+    let profile = GrabProfile {
+        id: id.unwrap_or_default(),
+        name,
+        uid: state.selected_account_uid,
+        project_id: state.ticket_id.clone(),
+        screen_id: state
+            .selected_screen_id
+            .map(|id| id.to_string())
+            .unwrap_or_default(),
+        ticket_id: state
+            .selected_ticket_id
+            .map(|id| id.to_string())
+            .unwrap_or_default(),
+        buyer_type: Some(state.buyer_type),
+        buyer_ids,
+        no_bind_buyer: (state.buyer_type == 0)
+            .then(|| state.selected_no_bind_buyer_info.clone())
+            .flatten(),
+        grab_mode: state.grab_mode,
+        skip_words: state.skip_words.clone().unwrap_or_default(),
+        retry_policy: RetryPolicy::configured(&state.config), //未保存时按配置的模式使用默认策略
+        max_total_price: state.max_total_price,
+        preferences: state.ticket_preferences.clone(),
+        seats: state.seat_selection.clone(),
+        address_id: state.delivery_address.as_ref().map(|a| a.id.clone()),
+        updated_at: 0,
+    };
 
-    Ok(task_id)
+    let profile = upsert_profile(&mut state.config, profile)?;
+    state
+        .config
+        .save_config()
+        .map_err(|e| tr("profile.save_failed", &[&e]))?;
+    log::info!("抢票配置已保存: {}（ID: {}）", profile.name, profile.id);
+    Ok(profile)
+}
+
+#[tauri::command]
+fn delete_grab_profile(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    let mut state = state
+        .inner
+        .lock()
+        .map_err(|_| "state lock failed".to_string())?;
+    remove_profile(&mut state.config, &id)?;
+    state
+        .config
+        .save_config()
+        .map_err(|e| tr("profile.delete_save_failed", &[&e]))?;
+    log::info!("抢票配置已删除: ID={}", id);
+    Ok(())
 }
 
 #[tauri::command]
@@ -1234,6 +1751,15 @@ fn get_state(state: State<'_, AppState>) -> Result<Value, String> {
         "show_orderlist_window": state.show_orderlist_window,
         "show_qr_windows": state.show_qr_windows,
         "skip_words_input": state.skip_words_input,
+        "max_total_price": state.max_total_price.map(|m| m.yuan_string()),
+        "ticket_preferences": state.ticket_preferences,
+        "seat_selection": state.seat_selection,
+        "delivery_address": state.delivery_address,
+        "language": common::i18n::current_lang().code(),
+        "http_record_session": http_record::current_session(),
+        "command_hooks": hooks::load_hooks(&state.config),
+        "retry_policy": RetryPolicy::load(&state.config, state.grab_mode),
+        "request_pacing": PacerConfig::load(&state.config),
         "login_input": {
             "phone": state.login_input.phone,
             "account": state.login_input.account,
@@ -1359,19 +1885,19 @@ fn add_project(
         // 检查是否已存在相同ID的项目
         for existing_project in projects.iter() {
             if existing_project["id"].as_str() == Some(&id) {
-                return Err("项目ID已存在".to_string());
+                return Err(t("gui.project.exists").to_string());
             }
         }
 
-        let project_json =
-            serde_json::to_value(&project).map_err(|e| format!("序列化项目失败: {}", e))?;
+        let project_json = serde_json::to_value(&project)
+            .map_err(|e| tr("gui.project.serialize_failed", &[&e]))?;
         projects.push(project_json);
     }
 
     // 保存配置
     if let Err(e) = state.config.save_config() {
         log::error!("保存项目失败: {}", e);
-        return Err(format!("保存项目失败: {}", e));
+        return Err(tr("gui.project.save_failed", &[&e]));
     }
 
     log::info!("项目添加成功: ID={}, 名称={}", id, name);
@@ -1389,7 +1915,7 @@ fn get_projects(state: State<'_, AppState>) -> Result<Vec<Project>, String> {
     let projects = if state.config["projects"].is_array() {
         let projects_json = &state.config["projects"];
         serde_json::from_value(projects_json.clone())
-            .map_err(|e| format!("解析项目列表失败: {}", e))?
+            .map_err(|e| tr("gui.project.parse_failed", &[&e]))?
     } else {
         Vec::new()
     };
@@ -1405,7 +1931,7 @@ fn delete_project(state: State<'_, AppState>, id: String) -> Result<(), String>
         .map_err(|_| "state lock failed".to_string())?;
 
     if !state.config["projects"].is_array() {
-        return Err("项目列表不存在".to_string());
+        return Err(t("gui.project.list_missing").to_string());
     }
 
     if let Value::Array(ref mut projects) = state.config["projects"] {
@@ -1413,19 +1939,19 @@ fn delete_project(state: State<'_, AppState>, id: String) -> Result<(), String>
         projects.retain(|project| project["id"].as_str() != Some(&id));
 
         if projects.len() == original_len {
-            return Err("未找到指定ID的项目".to_string());
+            return Err(t("gui.project.not_found").to_string());
         }
 
         // 保存配置
         if let Err(e) = state.config.save_config() {
             log::error!("删除项目后保存失败: {}", e);
-            return Err(format!("删除项目后保存失败: {}", e));
+            return Err(tr("gui.project.delete_save_failed", &[&e]));
         }
 
         log::info!("项目删除成功: ID={}", id);
         Ok(())
     } else {
-        Err("项目列表格式错误".to_string())
+        Err(t("gui.project.list_invalid").to_string())
     }
 }
 
@@ -1436,7 +1962,7 @@ fn get_monitor_stats(state: State<'_, AppState>) -> Result<serde_json::Value, St
     let state = state
         .inner
         .lock()
-        .map_err(|_| "state lock失败".to_string())?;
+        .map_err(|_| "state lock failed".to_string())?;
 
     // 简化实现：返回基本统计信息
     // TODO: 未来从任务管理器获取实时统计
@@ -1444,7 +1970,8 @@ fn get_monitor_stats(state: State<'_, AppState>) -> Result<serde_json::Value, St
         "attempts": 0,
         "success": 0,
         "failures": 0,
-        "running": state.running_status.contains("运行") || state.running_status.contains("抢票"),
+        "running": state.running_status.contains(t("gui.status.running"))
+            || state.running_status.contains(t("gui.status.grabbing")),
         "active_tasks": 0,
         "completed_tasks": 0
     }))
@@ -1468,6 +1995,8 @@ fn save_settings(
     grab_mode: u8,
     delay_time: usize,
     max_attempts: i32,
+    retry_policy: RetryPolicy,
+    request_pacing: PacerConfig,
     enable_push: bool,
     enabled_methods: Vec<String>,
     bark_token: String,
@@ -1493,10 +2022,15 @@ fn save_settings(
 
     state.grab_mode = grab_mode;
 
+    retry_policy.validate()?;
     state.status_delay = delay_time;
 
     state.config["max_attempts"] = json!(max_attempts);
+    state.config["status_delay"] = json!(delay_time);
     log::info!("最大尝试次数设置: {}", max_attempts);
+    state.config["retry_policy"] = json!(retry_policy);
+    state.config["request_pacing"] = json!(request_pacing);
+    pacer::set_config(request_pacing);
 
     state.push_config.enabled = enable_push;
     state.push_config.enabled_methods = enabled_methods;
@@ -1527,13 +2061,120 @@ fn save_settings(
 
     if let Err(e) = state.config.save_config() {
         log::error!("保存配置失败: {}", e);
-        return Err(format!("保存配置失败: {}", e));
+        return Err(tr("gui.save_config_failed", &[&e]));
     }
 
     log::info!("设置已保存!");
     Ok(())
 }
 
+// 保存通知路由规则和免打扰时段，规则为空时重要事件推送到全部已启用渠道
+#[tauri::command]
+fn set_notification_rules(
+    state: State<'_, AppState>,
+    routing_rules: Vec<RoutingRule>,
+    quiet_hours: QuietHours,
+) -> Result<(), String> {
+    QuietHours::parse_time(&quiet_hours.start)?;
+    QuietHours::parse_time(&quiet_hours.end)?;
+
+    let mut state = state
+        .inner
+        .lock()
+        .map_err(|_| "state lock failed".to_string())?;
+    state.push_config.routing_rules = routing_rules;
+    state.push_config.quiet_hours = quiet_hours;
+
+    let push_config = state.push_config.clone();
+    save_config(&mut state.config, Some(&push_config), None, None)?;
+    log::info!("通知规则已保存");
+    Ok(())
+}
+
+// 保存本地命令钩子，立即对运行中的抢票任务生效
+#[tauri::command]
+fn set_command_hooks(state: State<'_, AppState>, hooks: Vec<CommandHook>) -> Result<(), String> {
+    let mut state = state
+        .inner
+        .lock()
+        .map_err(|_| "state lock failed".to_string())?;
+    state.config["hooks"] = json!(hooks);
+    state
+        .config
+        .save_config()
+        .map_err(|e| tr("gui.save_config_failed", &[&e]))?;
+    state
+        .task_manager
+        .lock()
+        .map_err(|_| "task manager lock failed".to_string())?
+        .set_hooks(hooks);
+    Ok(())
+}
+
+// 用示例数据运行一次钩子，结果见日志
+#[tauri::command]
+fn test_command_hook(state: State<'_, AppState>, hook: CommandHook) -> Result<(), String> {
+    // 钩子最长运行到超时，期间不占用界面状态锁
+    let handle = state
+        .inner
+        .lock()
+        .map_err(|_| "state lock failed".to_string())?
+        .task_manager
+        .lock()
+        .map_err(|_| "task manager lock failed".to_string())?
+        .runtime_handle();
+    let payload = HookPayload {
+        event: Some(NotifyEvent::GrabSuccess),
+        task_id: "test".to_string(),
+        project_name: t("hook.test.project").to_string(),
+        screen_name: t("hook.test.screen").to_string(),
+        ticket_name: t("hook.test.ticket").to_string(),
+        order_id: Some("0".to_string()),
+        pay_url: Some("https://mall.bilibili.com".to_string()),
+        message: t("hook.test.message").to_string(),
+        timestamp: chrono::Local::now().timestamp(),
+        ..Default::default()
+    };
+    handle.block_on(hooks::run_hook(&hook, &payload))
+}
+
+// 切换界面语言，影响抢票结果、推送内容等后端生成的消息
+#[tauri::command]
+fn set_language(state: State<'_, AppState>, lang: String) -> Result<(), String> {
+    let lang = Lang::parse(&lang).ok_or_else(|| format!("unsupported language: {}", lang))?;
+    set_lang(lang);
+
+    let mut state = state
+        .inner
+        .lock()
+        .map_err(|_| "state lock failed".to_string())?;
+    state.config["language"] = json!(lang.code());
+    state
+        .config
+        .save_config()
+        .map_err(|e| tr("gui.save_config_failed", &[&e]))?;
+    log::info!("界面语言已切换为{}", lang.code());
+    Ok(())
+}
+
+// 开启或关闭HTTP录制，返回录制文件路径（仅调试用，回放需通过环境变量 BTR_HTTP_REPLAY 开启）
+#[tauri::command]
+fn set_http_recording(enabled: bool) -> Result<Option<String>, String> {
+    if http_record::is_replaying() {
+        return Err(t("http_record.replaying").to_string());
+    }
+    if enabled {
+        if let Some(path) = http_record::current_session() {
+            return Ok(Some(path));
+        }
+        http_record::start_recording(None).map(Some)
+    } else {
+        http_record::stop();
+        log::info!("已停止录制HTTP请求");
+        Ok(None)
+    }
+}
+
 #[tauri::command]
 fn clear_logs(state: State<'_, AppState>) -> Result<(), String> {
     let mut state = state
@@ -1557,41 +2198,41 @@ fn poll_qrcode_status(
         .lock()
         .map_err(|_| "state lock failed".to_string())?;
 
-    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("创建运行时失败: {}", e))?;
-
-    let status =
-        rt.block_on(async { backend::api::poll_qrcode_login(&key, Some(&state.default_ua)).await });
+    let status = state.block_on(backend::api::poll_qrcode_login(
+        &key,
+        Some(&state.default_ua),
+    ))?;
 
     match status {
         common::login::QrCodeLoginStatus::Pending => Ok(json!({
             "status": "pending",
-            "message": "二维码已生成，等待扫描",
+            "message": t("gui.qrcode.pending"),
             "key": key
         })),
         common::login::QrCodeLoginStatus::Scanning => Ok(json!({
             "status": "scanning",
-            "message": "二维码已扫描，等待确认",
+            "message": t("gui.qrcode.scanning"),
             "key": key
         })),
         common::login::QrCodeLoginStatus::Confirming => Ok(json!({
             "status": "confirming",
-            "message": "二维码已确认，正在登录",
+            "message": t("gui.qrcode.confirming"),
             "key": key
         })),
         common::login::QrCodeLoginStatus::Success(cookie) => Ok(json!({
             "status": "success",
-            "message": "登录成功",
+            "message": t("gui.qrcode.success"),
             "key": key,
             "cookie": cookie
         })),
         common::login::QrCodeLoginStatus::Failed(error) => Ok(json!({
             "status": "error",
-            "message": format!("登录失败: {}", error),
+            "message": tr("gui.qrcode.failed", &[&error]),
             "key": key
         })),
         common::login::QrCodeLoginStatus::Expired => Ok(json!({
             "status": "expired",
-            "message": "二维码已过期",
+            "message": t("gui.qrcode.expired"),
             "key": key
         })),
     }
@@ -1602,6 +2243,7 @@ fn main() {
         eprintln!("初始化日志失败，原因: {}", e);
     }
     log::info!("日志初始化成功");
+    http_record::init_from_env();
 
     std::panic::set_hook(Box::new(|panic_info| {
         if let Some(s) = panic_info.payload().downcast_ref::<&str>() {
@@ -1627,6 +2269,9 @@ fn main() {
             get_accounts,
             reload_accounts,
             add_account_by_cookie,
+            import_accounts,
+            export_accounts,
+            export_orders_calendar,
             delete_account_by_uid,
             set_account_active,
             qrcode_login,
@@ -1635,6 +2280,7 @@ fn main() {
             get_ticket_info,
             get_buyer_info,
             get_order_list,
+            get_order_detail,
             poll_task_results,
             push_test,
             get_policy,
@@ -1642,10 +2288,17 @@ fn main() {
             get_grab_logs,
             add_log,
             get_app_info,
+            export_diagnostics,
             clear_grab_logs,
             cancel_task,
             set_ticket_id,
             set_grab_mode,
+            set_max_total_price,
+            set_ticket_preferences,
+            get_seat_map,
+            set_seat_selection,
+            get_address_list,
+            set_delivery_address,
             set_selected_account,
             set_show_screen_info,
             set_confirm_ticket_info,
@@ -1657,6 +2310,12 @@ fn main() {
             set_login_input,
             set_cookie_login,
             start_grab_ticket,
+            dry_run_grab_ticket,
+            get_grab_profiles,
+            save_grab_profile,
+            save_current_grab_profile,
+            delete_grab_profile,
+            validate_grab_ticket,
             set_delete_account,
             set_account_switch,
             set_selected_screen,
@@ -1668,9 +2327,17 @@ fn main() {
             add_project,
             get_projects,
             delete_project,
+            get_sale_calendar,
+            export_sale_calendar,
+            set_sale_reminders,
             get_monitor_stats,
             get_recent_logs,
             save_settings,
+            set_notification_rules,
+            set_language,
+            set_command_hooks,
+            test_command_hook,
+            set_http_recording,
             clear_logs,
             poll_qrcode_status,
             set_buyer_type,
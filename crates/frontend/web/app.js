@@ -75,14 +75,20 @@ function initializeEventListeners() {
     "add-account-btn": showAddAccountModal,
     "reload-accounts-btn": reloadAccounts,
     "qrcode-login-btn": showQrcodeLoginModal,
+    "import-accounts-btn": showImportAccountsModal,
+    "export-accounts-btn": exportAccounts,
+    "export-calendar-btn": exportOrdersCalendar,
 
     "start-grab-btn": startGrab,
     "stop-grab-btn": stopGrab,
     "refresh-monitor-btn": refreshMonitor,
+    "dry-run-btn": dryRunGrab,
+    "save-grab-profile-btn": saveGrabProfile,
 
     "load-logs-btn": loadLogs,
     "clear-logs-btn": clearLogs,
     "export-logs-btn": exportLogs,
+    "export-diagnostics-btn": exportDiagnostics,
   };
 
   Object.keys(buttonIds).forEach((id) => {
@@ -248,6 +254,101 @@ function closeAddAccountModal() {
   }
 }
 
+function showImportAccountsModal() {
+  document.getElementById("import-accounts-modal").classList.add("active");
+}
+
+function closeImportAccountsModal() {
+  document.getElementById("import-accounts-modal").classList.remove("active");
+  document.getElementById("import-accounts-file").value = "";
+  document.getElementById("import-accounts-content").value = "";
+  document.getElementById("import-accounts-report").innerHTML = "";
+}
+
+async function submitImportAccounts() {
+  const fileInput = document.getElementById("import-accounts-file");
+  let content = document.getElementById("import-accounts-content").value;
+  if (fileInput.files.length > 0) {
+    content = await fileInput.files[0].text();
+  }
+  if (!content.trim()) {
+    showWarning("请选择文件或粘贴导入内容");
+    return;
+  }
+
+  try {
+    if (!invoke) {
+      throw new Error("Tauri invoke function not available");
+    }
+    const reports = await invoke("import_accounts", { content });
+    const succeeded = reports.filter((r) => r.success).length;
+    document.getElementById("import-accounts-report").innerHTML = `
+      <div class="dry-run-summary ${succeeded === reports.length ? "color-success" : "color-warning"}">
+        导入完成：成功${succeeded}个，失败${reports.length - succeeded}个
+      </div>
+      <ul class="validation-issues">
+        ${reports
+          .map(
+            (r) =>
+              `<li class="${r.success ? "color-success" : "color-error"}">${r.success ? "✔" : "✘"} ${r.source}${r.uid ? ` ${r.name} (${r.uid})` : ""}：${r.message}</li>`,
+          )
+          .join("")}
+      </ul>`;
+    if (succeeded > 0) {
+      await reloadAccounts();
+    }
+  } catch (error) {
+    showError("导入失败: " + error);
+  }
+}
+
+async function exportAccounts() {
+  try {
+    if (!invoke) {
+      throw new Error("Tauri invoke function not available");
+    }
+    const content = await invoke("export_accounts", { uids: null });
+    const blob = new Blob([content], { type: "application/json" });
+    const url = URL.createObjectURL(blob);
+    const a = document.createElement("a");
+    a.href = url;
+    a.download = `btr_accounts_${new Date().toISOString().slice(0, 10)}.json`;
+    document.body.appendChild(a);
+    a.click();
+    document.body.removeChild(a);
+    URL.revokeObjectURL(url);
+    showSuccess("账号已导出，文件包含登录凭据，请妥善保管");
+  } catch (error) {
+    showError("导出失败: " + error);
+  }
+}
+
+// 所有账号的已支付订单导出为 .ics，可导入日历应用
+async function exportOrdersCalendar() {
+  try {
+    if (!invoke) {
+      throw new Error("Tauri invoke function not available");
+    }
+    const result = await invoke("export_orders_calendar");
+    if (result.count === 0) {
+      showError("没有可导出的已支付订单");
+      return;
+    }
+    const blob = new Blob([result.content], { type: "text/calendar" });
+    const url = URL.createObjectURL(blob);
+    const a = document.createElement("a");
+    a.href = url;
+    a.download = `btr_orders_${new Date().toISOString().slice(0, 10)}.ics`;
+    document.body.appendChild(a);
+    a.click();
+    document.body.removeChild(a);
+    URL.revokeObjectURL(url);
+    showSuccess(`已导出${result.count}个订单到日历`);
+  } catch (error) {
+    showError("导出日历失败: " + error);
+  }
+}
+
 function showQrcodeLoginModal() {
   document.getElementById("qrcode-login-modal").classList.add("active");
   refreshQrcode();
@@ -329,6 +430,106 @@ function startQrcodePolling(qrcodeKey) {
   }, 3000);
 }
 
+// 已保存项目尚未开售的票档，按开售时间排列
+async function showSaleCalendarModal() {
+  const loading = document.getElementById("sale-calendar-loading");
+  const list = document.getElementById("sale-calendar-list");
+  document.getElementById("sale-calendar-modal").classList.add("active");
+  loading.style.display = "block";
+  list.innerHTML = "";
+
+  try {
+    if (!invoke) {
+      throw new Error("Tauri invoke function not available");
+    }
+    const result = await invoke("get_sale_calendar");
+    document.getElementById("sale-reminder-enabled").checked =
+      result.reminders.enabled;
+    document.getElementById("sale-reminder-offsets").value =
+      result.reminders.offsets_minutes.join(",");
+
+    if (result.openings.length === 0) {
+      list.innerHTML =
+        '<li style="padding: 20px; text-align: center; color: var(--text-secondary);">已保存的项目中没有待开售的票档</li>';
+      return;
+    }
+    result.openings.forEach((opening) => {
+      const price =
+        opening.min_price === opening.max_price
+          ? formatFen(opening.min_price)
+          : `${formatFen(opening.min_price)}-${formatFen(opening.max_price)}`;
+      const li = document.createElement("li");
+      li.className = "account-item";
+      li.innerHTML = `
+                <div class="account-info">
+                    <div class="account-name">${opening.project_name} - ${opening.tier}</div>
+                    <div class="account-meta">${new Date(opening.sale_start * 1000).toLocaleString()} | ${price} | ${opening.screens.join("、")}</div>
+                </div>
+            `;
+      list.appendChild(li);
+    });
+  } catch (error) {
+    list.innerHTML = `<li style="padding: 20px; text-align: center; color: var(--error-color);">加载失败: ${error}</li>`;
+  } finally {
+    loading.style.display = "none";
+  }
+}
+
+function closeSaleCalendarModal() {
+  document.getElementById("sale-calendar-modal").classList.remove("active");
+}
+
+async function saveSaleReminders() {
+  const offsets = document
+    .getElementById("sale-reminder-offsets")
+    .value.split(/[,，\s]+/)
+    .filter((v) => v !== "")
+    .map(Number);
+  if (offsets.some((v) => !Number.isInteger(v) || v <= 0)) {
+    showWarning("提前提醒时间必须为正整数分钟");
+    return;
+  }
+  try {
+    if (!invoke) {
+      throw new Error("Tauri invoke function not available");
+    }
+    await invoke("set_sale_reminders", {
+      reminders: {
+        enabled: document.getElementById("sale-reminder-enabled").checked,
+        offsets_minutes: offsets,
+      },
+    });
+    showSuccess("开售提醒已保存");
+  } catch (error) {
+    showError("保存开售提醒失败: " + error);
+  }
+}
+
+async function exportSaleCalendar() {
+  try {
+    if (!invoke) {
+      throw new Error("Tauri invoke function not available");
+    }
+    const result = await invoke("export_sale_calendar");
+    if (result.count === 0) {
+      showError("没有待开售的票档");
+      return;
+    }
+    const blob = new Blob([result.content], { type: "text/calendar" });
+    const url = URL.createObjectURL(blob);
+    const a = document.createElement("a");
+    a.href = url;
+    a.download = `btr_sales_${new Date().toISOString().slice(0, 10)}.ics`;
+    document.body.appendChild(a);
+    a.click();
+    document.body.removeChild(a);
+    URL.revokeObjectURL(url);
+    showSuccess(`已导出${result.count}个开售时间到日历`);
+  } catch (error) {
+    showError("导出日历失败: " + error);
+  }
+}
+
 function showAddProjectModal() {
   document.getElementById("add-project-modal").classList.add("active");
 }
@@ -429,6 +630,7 @@ async function loadAccounts() {
                         <input type="checkbox" ${account.is_active ? "checked" : ""} onchange="toggleAccountActive(${account.uid}, this.checked)">
                         <span class="toggle-slider"></span>
                     </label>
+                    <button class="btn btn-primary" onclick="showOrdersModal(${account.uid})">订单</button>
                     <button class="btn btn-danger" onclick="deleteAccount(${account.uid})">删除</button>
                 </div>
             `;
@@ -444,6 +646,139 @@ async function loadAccounts() {
   }
 }
 
+function formatFen(fen) {
+  return "¥" + (fen / 100).toFixed(2);
+}
+
+async function showOrdersModal(uid) {
+  const loading = document.getElementById("orders-loading");
+  const list = document.getElementById("orders-list");
+  document.getElementById("orders-modal").classList.add("active");
+  document.getElementById("orders-modal-title").textContent = `订单 (UID: ${uid})`;
+  backToOrderList();
+  loading.style.display = "block";
+  list.innerHTML = "";
+
+  try {
+    if (!invoke) {
+      throw new Error("Tauri invoke function not available");
+    }
+    const taskId = await invoke("get_order_list", { uid });
+    const orderInfo = await pollForOrderList(taskId);
+    const orders = (orderInfo && orderInfo.data && orderInfo.data.list) || [];
+    if (orders.length === 0) {
+      list.innerHTML =
+        '<li style="padding: 20px; text-align: center; color: var(--text-secondary);">暂无订单</li>';
+      return;
+    }
+    list.innerHTML = orders
+      .map(
+        (order) => `
+        <li class="account-item">
+          <div class="account-info">
+            <div class="account-name">${order.item_info.name}</div>
+            <div class="account-meta">${order.order_id} | ${order.item_info.screen_name} | ${order.sub_status_name} | ${formatFen(order.pay_money)}</div>
+          </div>
+          <div>
+            <button class="btn btn-primary" onclick="showOrderDetail(${uid}, '${order.order_id}')">详情</button>
+          </div>
+        </li>`,
+      )
+      .join("");
+  } catch (error) {
+    list.innerHTML = `<li style="padding: 20px; text-align: center; color: var(--error-color);">加载失败: ${error.message || error}</li>`;
+  } finally {
+    loading.style.display = "none";
+  }
+}
+
+async function pollForOrderList(taskId) {
+  const maxAttempts = 30;
+  for (let i = 0; i < maxAttempts; i++) {
+    await new Promise((resolve) => setTimeout(resolve, 500));
+
+    const results = await invoke("poll_task_results");
+    const result = results.find((r) => r.task_id === taskId);
+    if (result) {
+      if (!result.success) {
+        throw new Error(result.message || "获取订单失败");
+      }
+      return result.order_info;
+    }
+  }
+  throw new Error("获取订单超时");
+}
+
+async function showOrderDetail(uid, orderId) {
+  const detailBox = document.getElementById("order-detail");
+  const loading = document.getElementById("orders-loading");
+  document.getElementById("orders-list").classList.add("display-none");
+  document.getElementById("order-detail-back-btn").classList.remove("display-none");
+  detailBox.classList.remove("display-none");
+  detailBox.innerHTML = "";
+  loading.style.display = "block";
+
+  try {
+    const d = await invoke("get_order_detail", { uid, orderId });
+    const rows = [
+      ["订单号", d.order_id],
+      ["状态", d.status_name],
+      ["项目", d.project_name],
+      ["场次", `${d.screen_name} ${d.screen_start_time}`],
+      ["票档", `${d.ticket_name} ${formatFen(d.unit_price)} × ${d.count}`],
+      ["订单金额", formatFen(d.total_money)],
+      ["实付", formatFen(d.pay_money)],
+      ["下单时间", d.ctime],
+    ];
+    if (d.express_fee > 0) {
+      rows.push(["快递费", formatFen(d.express_fee)]);
+    }
+    if (d.pay_deadline) {
+      rows.push(["支付截止", new Date(d.pay_deadline * 1000).toLocaleString()]);
+    }
+    rows.push([
+      "退票",
+      (d.refund.refundable ? "可退票" : "不可退票") +
+        (d.refund.reason ? `（${d.refund.reason}）` : ""),
+    ]);
+
+    let html = rows
+      .map(([label, value]) => `<p><strong>${label}:</strong> ${value}</p>`)
+      .join("");
+    if (d.buyers.length > 0) {
+      html += `<p><strong>购票人:</strong></p><ul>${d.buyers
+        .map((b) => `<li>${b.name} ${b.personal_id} ${b.tel}</li>`)
+        .join("")}</ul>`;
+    }
+    if (d.seats.length > 0) {
+      html += `<p><strong>座位:</strong></p><ul>${d.seats
+        .map((s) => `<li>${s.area} ${s.seat}</li>`)
+        .join("")}</ul>`;
+    }
+    if (d.delivery) {
+      html += `<p><strong>配送:</strong> ${d.delivery.name} ${d.delivery.tel} ${d.delivery.address}</p>`;
+      if (d.delivery.tracking_no) {
+        html += `<p><strong>物流:</strong> ${d.delivery.express_company} ${d.delivery.tracking_no} ${d.delivery.express_status}</p>`;
+      }
+    }
+    detailBox.innerHTML = html;
+  } catch (error) {
+    detailBox.innerHTML = `<p class="color-error">获取订单详情失败: ${error}</p>`;
+  } finally {
+    loading.style.display = "none";
+  }
+}
+
+function backToOrderList() {
+  document.getElementById("orders-list").classList.remove("display-none");
+  document.getElementById("order-detail").classList.add("display-none");
+  document.getElementById("order-detail-back-btn").classList.add("display-none");
+}
+
+function closeOrdersModal() {
+  document.getElementById("orders-modal").classList.remove("active");
+}
+
 async function toggleAccountActive(uid, active) {
   try {
     if (!invoke) {
@@ -575,6 +910,7 @@ async function pollForTicketInfo(taskId) {
       if (result) {
         if (result.success && result.ticket_info) {
           console.log("项目详情获取成功:", result.ticket_info);
+          window.currentProjectDetail = result.project_detail;
           return result.ticket_info.data;
         } else if (!result.success) {
           throw new Error(result.message || "获取项目详情失败");
@@ -619,6 +955,8 @@ function closeScreenTicketModal() {
   loading.style.display = "block";
 
   selector.style.display = "none";
+  document.getElementById("project-detail").innerHTML = "";
+  window.currentProjectDetail = null;
   document.getElementById("screen-select").innerHTML = "";
   document.getElementById("ticket-select").innerHTML = "";
 
@@ -648,6 +986,9 @@ async function showScreenTicketSelector(ticketInfo) {
   loading.style.display = "none";
   selector.style.display = "block";
 
+  renderProjectDetail(window.currentProjectDetail);
+  await loadTicketPreferences();
+
   const availableScreens = ticketInfo.screen_list.filter(
     (s) => s.clickable !== false,
   );
@@ -666,6 +1007,8 @@ async function showScreenTicketSelector(ticketInfo) {
     .join("");
 
   window.currentTicketInfo = ticketInfo;
+  // 每次打开重新获取收货地址，账号可能已切换
+  deliveryAddresses = [];
 
   screenSelect.onchange = function () {
     updateTicketList(parseInt(this.value));
@@ -698,6 +1041,74 @@ async function showScreenTicketSelector(ticketInfo) {
   await loadBuyerInfo();
 }
 
+function renderProjectDetailSection(title, items) {
+  if (!items || items.length === 0) return "";
+  return `
+    <div class="project-detail-section">
+      <div class="project-detail-subtitle">${title}</div>
+      ${items
+        .map(
+          (item) =>
+            `<div class="project-detail-row">${item.title ? `<strong>${item.title}：</strong>` : ""}${item.content}</div>`,
+        )
+        .join("")}
+    </div>`;
+}
+
+function renderProjectDetail(detail) {
+  const container = document.getElementById("project-detail");
+  if (!detail) {
+    container.innerHTML = "";
+    return;
+  }
+
+  const idBindText =
+    detail.id_bind === 0
+      ? "非实名"
+      : detail.id_bind === 1
+        ? "一单一证"
+        : detail.id_bind === 2
+          ? "一人一证"
+          : "未知";
+  const venue = detail.venue
+    ? `${detail.venue.name}${detail.venue.address ? ` (${detail.venue.address})` : ""}`
+    : "未知";
+
+  const screenTables = detail.screens
+    .map(
+      (screen) => `
+      <div class="project-detail-section">
+        <div class="project-detail-subtitle">${screen.name} · ${screen.start_time} · ${screen.sale_flag} · ${screen.delivery}</div>
+        <table class="price-table">
+          <thead><tr><th>票种</th><th>价格</th><th>状态</th></tr></thead>
+          <tbody>
+            ${screen.tickets
+              .map(
+                (t) =>
+                  `<tr class="${t.clickable ? "" : "text-secondary"}"><td>${t.desc}</td><td>¥${t.price_text}</td><td>${t.sale_flag}</td></tr>`,
+              )
+              .join("")}
+          </tbody>
+        </table>
+      </div>`,
+    )
+    .join("");
+
+  container.innerHTML = `
+    <div class="project-detail-title">${detail.name}</div>
+    <div class="project-detail-row"><strong>演出时间：</strong>${detail.show_time}</div>
+    <div class="project-detail-row"><strong>场馆：</strong>${venue}</div>
+    <div class="project-detail-row"><strong>售票时间：</strong>${detail.sale_time}</div>
+    <div class="project-detail-row"><strong>实名制：</strong>${idBindText}</div>
+    ${detail.express_fee > 0 ? `<div class="project-detail-row"><strong>快递费：</strong>¥${(detail.express_fee / 100).toFixed(2)}</div>` : ""}
+    ${renderProjectDetailSection("基础信息", detail.base_info)}
+    ${renderProjectDetailSection("购票须知", detail.purchase_notes)}
+    ${renderProjectDetailSection("实名规则", detail.real_name_rules)}
+    ${renderProjectDetailSection("退票政策", detail.refund_policy)}
+    ${screenTables}
+  `;
+}
+
 function updateTicketList(screenId) {
   const ticketSelect = document.getElementById("ticket-select");
   const ticketInfo = window.currentTicketInfo;
@@ -719,6 +1130,274 @@ function updateTicketList(screenId) {
       return `<option value="${t.id}">${t.desc} - ¥${price} [${status}]</option>`;
     })
     .join("");
+
+  // 选座项目显示选座区域，切换场次后清空已选座位
+  const pickSeat =
+    ticketInfo.pick_seat === 1 || selectedScreen.pick_seat === 1;
+  document.getElementById("seat-section").style.display = pickSeat
+    ? "block"
+    : "none";
+  selectedSeatIds = [];
+  document.getElementById("seat-map").innerHTML = "";
+  document.getElementById("seat-summary").textContent = "";
+
+  updateDeliverySection(ticketInfo, selectedScreen);
+}
+
+// 场次配送方式：1 电子票，2 快递纸质票，3 现场取票
+const DELIVERY_TYPE_NAMES = { 1: "电子票", 2: "快递配送", 3: "现场取票" };
+let deliveryAddresses = [];
+
+function needsDelivery(ticketInfo, screen) {
+  return ticketInfo.express_fee > 0 || screen.delivery_type === 2;
+}
+
+// 显示配送方式和快递费，快递配送的场次需要选择收货地址
+function updateDeliverySection(ticketInfo, screen) {
+  const typeName =
+    DELIVERY_TYPE_NAMES[screen.delivery_type] ||
+    `未知配送方式(${screen.delivery_type})`;
+  const fee = (ticketInfo.express_fee / 100).toFixed(2);
+  const needed = needsDelivery(ticketInfo, screen);
+  document.getElementById("delivery-section").style.display = "block";
+  document.getElementById("delivery-summary").textContent = needed
+    ? `${typeName}，快递费 ¥${fee}，下单时使用下方选择的收货地址`
+    : typeName;
+  document.getElementById("address-row").style.display = needed
+    ? "flex"
+    : "none";
+  if (needed && deliveryAddresses.length === 0) {
+    loadAddresses();
+  }
+}
+
+async function loadAddresses() {
+  const select = document.getElementById("address-select");
+  try {
+    deliveryAddresses = await invoke("get_address_list");
+    if (deliveryAddresses.length === 0) {
+      select.innerHTML = '<option value="">账号没有保存收货地址</option>';
+      return;
+    }
+    select.innerHTML = deliveryAddresses
+      .map(
+        (a) =>
+          `<option value="${a.id}" ${a.is_default ? "selected" : ""}>${a.name} ${a.tel} ${a.prov}${a.city}${a.area}${a.addr}${a.is_default ? "（默认）" : ""}</option>`,
+      )
+      .join("");
+  } catch (error) {
+    select.innerHTML = "";
+    showError("加载收货地址失败: " + error);
+  }
+}
+
+function readDeliveryAddress() {
+  if (document.getElementById("address-row").style.display === "none") {
+    return null;
+  }
+  const addressId = document.getElementById("address-select").value;
+  return deliveryAddresses.find((a) => String(a.id) === addressId) || null;
+}
+
+let selectedSeatIds = [];
+
+async function loadSeatMap() {
+  const container = document.getElementById("seat-map");
+  const projectId = window.currentTicketInfo && window.currentTicketInfo.id;
+  const screenId = document.getElementById("screen-select").value;
+  const ticketId = document.getElementById("ticket-select").value;
+  container.innerHTML = '<div class="text-secondary">正在加载座位图...</div>';
+  try {
+    const seatMap = await invoke("get_seat_map", {
+      projectId: String(projectId),
+      screenId: String(screenId),
+      ticketId: String(ticketId),
+    });
+    selectedSeatIds = [];
+    container.innerHTML = seatMap.areas
+      .map(
+        (area) => `
+        <div class="seat-area-title">${area.name || area.id}</div>
+        ${area.rows
+          .map(
+            (row) => `
+          <div class="seat-row">
+            <span class="seat-row-name">${row.name}</span>
+            ${row.seats
+              .map(
+                (seat) =>
+                  `<button class="seat" title="${seat.name}" data-seat-id="${seat.id}" ${seat.available ? "" : "disabled"} onclick="toggleSeat(this)">${seat.col}</button>`,
+              )
+              .join("")}
+          </div>`,
+          )
+          .join("")}`,
+      )
+      .join("");
+    if (seatMap.areas.length === 0) {
+      container.innerHTML = '<div class="text-secondary">座位图为空</div>';
+    }
+    updateSeatSummary();
+  } catch (error) {
+    container.innerHTML = "";
+    showError("加载座位图失败: " + error);
+  }
+}
+
+function toggleSeat(button) {
+  const seatId = button.dataset.seatId;
+  if (selectedSeatIds.includes(seatId)) {
+    selectedSeatIds = selectedSeatIds.filter((id) => id !== seatId);
+    button.classList.remove("selected");
+  } else {
+    selectedSeatIds.push(seatId);
+    button.classList.add("selected");
+  }
+  updateSeatSummary();
+}
+
+function updateSeatSummary() {
+  document.getElementById("seat-summary").textContent =
+    selectedSeatIds.length > 0
+      ? `已选${selectedSeatIds.length}个座位，座位数需与购票人数一致`
+      : "未指定座位，将按区域偏好自动选座";
+}
+
+function readSeatSelection() {
+  if (document.getElementById("seat-section").style.display === "none") {
+    return { seat_ids: [], areas: [] };
+  }
+  return {
+    seat_ids: selectedSeatIds,
+    areas: document
+      .getElementById("seat-areas")
+      .value.split(/[,，]/)
+      .map((area) => area.trim())
+      .filter((area) => area),
+  };
+}
+
+let ticketPreferences = [];
+
+async function loadTicketPreferences() {
+  try {
+    const state = await invoke("get_state");
+    ticketPreferences = state.ticket_preferences || [];
+  } catch (error) {
+    console.error("加载捡漏偏好失败:", error);
+    ticketPreferences = [];
+  }
+  renderTicketPreferences();
+}
+
+function describeTicketPreference(pref) {
+  const ticketInfo = window.currentTicketInfo;
+  const parts = [];
+  if (pref.screen_id !== null && pref.screen_id !== undefined) {
+    const screen = ticketInfo?.screen_list.find((s) => s.id === pref.screen_id);
+    parts.push(screen ? screen.name : `场次${pref.screen_id}`);
+    if (pref.ticket_id !== null && pref.ticket_id !== undefined) {
+      const ticket = screen?.ticket_list.find((t) => t.id === pref.ticket_id);
+      parts.push(ticket ? ticket.desc : `票种${pref.ticket_id}`);
+    }
+  } else {
+    parts.push("任意场次");
+  }
+  if (pref.max_price !== null && pref.max_price !== undefined) {
+    parts.push(`≤¥${(pref.max_price / 100).toFixed(2)}`);
+  }
+  if (pref.keywords && pref.keywords.length > 0) {
+    parts.push(`包含: ${pref.keywords.join(", ")}`);
+  }
+  return parts.join(" · ");
+}
+
+function renderTicketPreferences() {
+  const list = document.getElementById("preference-list");
+  if (ticketPreferences.length === 0) {
+    list.innerHTML =
+      '<li class="text-secondary">未设置偏好，捡漏时按接口顺序尝试全部可售票种</li>';
+    return;
+  }
+  list.innerHTML = ticketPreferences
+    .map(
+      (pref, index) => `
+      <li class="preference-item">
+        <span>${index + 1}. ${describeTicketPreference(pref)}</span>
+        <span>
+          <button class="btn btn-secondary" onclick="moveTicketPreference(${index}, -1)">↑</button>
+          <button class="btn btn-secondary" onclick="moveTicketPreference(${index}, 1)">↓</button>
+          <button class="btn btn-danger" onclick="removeTicketPreference(${index})">删除</button>
+        </span>
+      </li>`,
+    )
+    .join("");
+}
+
+async function saveTicketPreferences() {
+  try {
+    await invoke("set_ticket_preferences", { preferences: ticketPreferences });
+  } catch (error) {
+    showError("保存捡漏偏好失败: " + error);
+  }
+  renderTicketPreferences();
+}
+
+async function addTicketPreference(useSelection) {
+  const maxPriceText = document.getElementById("pref-max-price").value.trim();
+  const keywords = document
+    .getElementById("pref-keywords")
+    .value.split(/[,，]/)
+    .map((w) => w.trim())
+    .filter((w) => w);
+
+  let maxPrice = null;
+  if (maxPriceText) {
+    const value = parseFloat(maxPriceText);
+    if (isNaN(value) || value < 0) {
+      showWarning("请输入有效的单价上限");
+      return;
+    }
+    maxPrice = Math.round(value * 100);
+  }
+
+  const pref = {
+    screen_id: null,
+    ticket_id: null,
+    max_price: maxPrice,
+    keywords: keywords,
+  };
+  if (useSelection) {
+    const screenId = document.getElementById("screen-select").value;
+    const ticketId = document.getElementById("ticket-select").value;
+    if (!screenId || !ticketId) {
+      showWarning("请选择场次和票种");
+      return;
+    }
+    pref.screen_id = parseInt(screenId);
+    pref.ticket_id = parseInt(ticketId);
+  } else if (maxPrice === null && keywords.length === 0) {
+    showWarning("请填写单价上限或关键词");
+    return;
+  }
+
+  ticketPreferences.push(pref);
+  document.getElementById("pref-max-price").value = "";
+  document.getElementById("pref-keywords").value = "";
+  await saveTicketPreferences();
+}
+
+async function moveTicketPreference(index, offset) {
+  const target = index + offset;
+  if (target < 0 || target >= ticketPreferences.length) return;
+  const [pref] = ticketPreferences.splice(index, 1);
+  ticketPreferences.splice(target, 0, pref);
+  await saveTicketPreferences();
+}
+
+async function removeTicketPreference(index) {
+  ticketPreferences.splice(index, 1);
+  await saveTicketPreferences();
 }
 
 async function confirmScreenTicketSelection() {
@@ -773,6 +1452,15 @@ async function confirmScreenTicketSelection() {
       }
     }
 
+    const deliveryAddress = readDeliveryAddress();
+    if (
+      document.getElementById("address-row").style.display !== "none" &&
+      !deliveryAddress
+    ) {
+      showWarning("该场次需快递配送，请选择收货地址");
+      return;
+    }
+
     await invoke("set_selected_screen", {
       index: null,
       id: parseInt(screenId),
@@ -780,6 +1468,11 @@ async function confirmScreenTicketSelection() {
     await invoke("set_selected_ticket", {
       id: parseInt(ticketId),
     });
+    await invoke("set_max_total_price", {
+      price: document.getElementById("max-total-price").value.trim() || null,
+    });
+    await invoke("set_seat_selection", { selection: readSeatSelection() });
+    await invoke("set_delivery_address", { address: deliveryAddress });
 
     await invoke("set_buyer_type", { buyerType: parseInt(buyerType) });
 
@@ -1035,6 +1728,9 @@ async function startGrab() {
     }
 
     await invoke("set_grab_mode", { mode: 1 });
+    if (!(await validateBeforeGrab())) {
+      return;
+    }
     const taskId = await invoke("start_grab_ticket");
 
     currentTaskId = taskId;
@@ -1051,6 +1747,163 @@ async function startGrab() {
   }
 }
 
+// 提交前检查配置：有错误时阻止提交，只有警告时由用户确认
+async function validateBeforeGrab() {
+  const container = document.getElementById("validation-result");
+  const report = await invoke("validate_grab_ticket");
+  const errors = report.issues.filter((i) => i.level === "error");
+  const warnings = report.issues.filter((i) => i.level === "warning");
+
+  if (report.issues.length === 0) {
+    container.innerHTML = "";
+    return true;
+  }
+
+  container.innerHTML = `
+    <div class="dry-run-summary ${errors.length > 0 ? "color-error" : "color-warning"}">
+      ${errors.length > 0 ? `检查发现${errors.length}个错误，无法开始抢票` : `检查发现${warnings.length}个警告`}
+    </div>
+    <ul class="validation-issues">
+      ${[...errors, ...warnings]
+        .map(
+          (i) =>
+            `<li class="${i.level === "error" ? "color-error" : "color-warning"}">${i.level === "error" ? "✘" : "⚠"} ${i.message}</li>`,
+        )
+        .join("")}
+    </ul>`;
+
+  if (errors.length > 0) {
+    showError("抢票配置有误，请根据检查结果修改");
+    return false;
+  }
+  return confirm(
+    "检查发现以下问题：\n" +
+      warnings.map((w) => "- " + w.message).join("\n") +
+      "\n\n仍要开始抢票吗？",
+  );
+}
+
+async function dryRunGrab(profileId = null) {
+  const container = document.getElementById("dry-run-result");
+  try {
+    if (!invoke) {
+      throw new Error("Tauri invoke function not available");
+    }
+
+    container.innerHTML =
+      '<div class="text-secondary">试运行中，不会下单...</div>';
+    const taskId = await invoke("dry_run_grab_ticket", { profileId });
+    const result = await pollForDryRunResult(taskId);
+
+    container.innerHTML = `
+      <div class="dry-run-summary ${result.success ? "color-success" : "color-error"}">
+        ${result.success ? "试运行通过，配置可用" : "试运行未通过，请检查以下失败项"}
+      </div>
+      <ul class="dry-run-checks">
+        ${result.checks
+          .map(
+            (c) =>
+              `<li class="${c.passed ? "color-success" : "color-error"}">${c.passed ? "✔" : "✘"} ${c.name}：${c.message}</li>`,
+          )
+          .join("")}
+      </ul>`;
+  } catch (error) {
+    console.error("试运行失败:", error);
+    container.innerHTML = "";
+    showError("试运行失败: " + error);
+  }
+}
+
+async function loadGrabProfiles() {
+  const list = document.getElementById("grab-profiles-list");
+  try {
+    const profiles = await invoke("get_grab_profiles");
+    if (profiles.length === 0) {
+      list.innerHTML =
+        '<li style="padding: 20px; text-align: center; color: var(--text-secondary);">暂无抢票配置</li>';
+      return;
+    }
+    const modes = ["普通模式", "快速模式", "极速模式"];
+    list.innerHTML = profiles
+      .map(
+        (p) => `
+        <li class="account-item">
+          <div class="account-info">
+            <div class="account-name">${p.name}</div>
+            <div class="account-meta">账号: ${p.uid || "启用的账号"} | 项目: ${p.project_id} | 场次: ${p.screen_id || "-"} | 票种: ${p.ticket_id || "-"} | ${modes[p.grab_mode] || p.grab_mode}${p.max_total_price != null ? " | 预算: " + formatFen(p.max_total_price) : ""}</div>
+          </div>
+          <div>
+            <button class="btn btn-success" onclick="startProfileGrab('${p.id}')">开始</button>
+            <button class="btn btn-warning" onclick="dryRunGrab('${p.id}')">试运行</button>
+            <button class="btn btn-danger" onclick="deleteGrabProfile('${p.id}')">删除</button>
+          </div>
+        </li>`,
+      )
+      .join("");
+  } catch (error) {
+    list.innerHTML = `<li style="padding: 20px; text-align: center; color: var(--error-color);">加载失败: ${error}</li>`;
+  }
+}
+
+async function saveGrabProfile() {
+  const name = document.getElementById("grab-profile-name").value.trim();
+  if (!name) {
+    showWarning("请输入配置名称");
+    return;
+  }
+  try {
+    await invoke("save_current_grab_profile", { name });
+    document.getElementById("grab-profile-name").value = "";
+    showSuccess("抢票配置已保存");
+    await loadGrabProfiles();
+  } catch (error) {
+    showError("保存抢票配置失败: " + error);
+  }
+}
+
+async function deleteGrabProfile(id) {
+  if (!confirm("确定要删除此抢票配置吗？")) return;
+  try {
+    await invoke("delete_grab_profile", { id });
+    await loadGrabProfiles();
+  } catch (error) {
+    showError("删除失败: " + error);
+  }
+}
+
+// 按配置提交，提交前的配置检查在后端完成
+async function startProfileGrab(profileId) {
+  try {
+    const taskId = await invoke("start_grab_ticket", { profileId });
+    currentTaskId = taskId;
+    document.getElementById("monitor-status").textContent = "运行中";
+    document.getElementById("monitor-status").style.color =
+      "var(--success-color)";
+    showSuccess("开始抢票！任务ID: " + taskId);
+    await refreshMonitor();
+  } catch (error) {
+    console.error("启动抢票失败:", error);
+    showError("启动失败: " + error);
+  }
+}
+
+async function pollForDryRunResult(taskId) {
+  const maxAttempts = 60;
+
+  for (let i = 0; i < maxAttempts; i++) {
+    const results = await invoke("poll_task_results");
+    const result = results.find(
+      (r) => r.type === "DryRunResult" && r.task_id === taskId,
+    );
+    if (result) {
+      return result;
+    }
+    await new Promise((resolve) => setTimeout(resolve, 500));
+  }
+
+  throw new Error("试运行超时");
+}
+
 async function stopGrab() {
   try {
     if (!invoke) {
@@ -1089,22 +1942,17 @@ async function refreshMonitor() {
     document.getElementById("monitor-status").textContent =
       state.running_status;
 
+    // 状态文本随界面语言变化，颜色按后端的 running 标志判断
+    const stats = await invoke("get_monitor_stats");
     const statusElem = document.getElementById("monitor-status");
-    if (
-      state.running_status.includes("运行") ||
-      state.running_status.includes("抢票")
-    ) {
+    if (stats && stats.running) {
       statusElem.style.color = "var(--success-color)";
       statusElem.style.fontWeight = "bold";
-    } else if (state.running_status.includes("停止")) {
-      statusElem.style.color = "var(--error-color)";
-      statusElem.style.fontWeight = "normal";
     } else {
       statusElem.style.color = "var(--text-primary)";
       statusElem.style.fontWeight = "normal";
     }
 
-    const stats = await invoke("get_monitor_stats");
     if (stats) {
       monitorStats.attempts = stats.attempts || 0;
       monitorStats.success = stats.success || 0;
@@ -1125,8 +1973,11 @@ async function loadSettings() {
     const state = await invoke("get_state");
     document.getElementById("grab-mode").value = state.grab_mode || "0";
     document.getElementById("delay-time").value = state.status_delay || "2";
-    document.getElementById("max-attempts").value =
-      state.config?.max_attempts || "100";
+    loadRetryPolicy(state.retry_policy);
+    loadRequestPacing(state.request_pacing);
+    document.getElementById("language").value = state.language || "zh-CN";
+    document.getElementById("http-recording").checked =
+      !!state.http_record_session;
 
     if (state.custom_config && state.custom_config.open_custom_ua) {
       document.getElementById("custom-ua").checked = true;
@@ -1164,6 +2015,8 @@ async function loadSettings() {
           state.push_config.enabled_methods.includes("smtp");
         document.getElementById("push-method-gotify").checked =
           state.push_config.enabled_methods.includes("gotify");
+        document.getElementById("push-method-desktop").checked =
+          state.push_config.enabled_methods.includes("desktop");
       } else {
         // 如果没有enabled_methods，默认全选
         document.getElementById("push-method-bark").checked = true;
@@ -1199,12 +2052,304 @@ async function loadSettings() {
         document.getElementById("smtp-to").value =
           state.push_config.smtp_config.smtp_to || "";
       }
+      loadNotifyRules(state.push_config);
     }
+    loadCommandHooks(state.command_hooks || []);
   } catch (error) {
     console.error("加载设置失败:", error);
   }
 }
 
+const NOTIFY_EVENTS = [
+  { id: "grab_success", name: "抢票成功", byDefault: true },
+  { id: "grab_failed", name: "抢票失败", byDefault: true },
+  { id: "session_expired", name: "登录失效", byDefault: true },
+  { id: "task_started", name: "任务开始", byDefault: false },
+  { id: "task_stopped", name: "任务停止", byDefault: false },
+  { id: "unpaid_order", name: "待支付提醒", byDefault: true },
+  { id: "watcher_change", name: "回流变化", byDefault: false },
+  { id: "sale_reminder", name: "开售提醒", byDefault: true },
+];
+
+const NOTIFY_CHANNELS = [
+  { id: "bark", name: "Bark" },
+  { id: "pushplus", name: "PushPlus" },
+  { id: "fangtang", name: "方糖" },
+  { id: "dingtalk", name: "钉钉" },
+  { id: "wechat", name: "企业微信" },
+  { id: "smtp", name: "SMTP" },
+  { id: "gotify", name: "Gotify" },
+  { id: "desktop", name: "桌面通知" },
+];
+
+const NOTIFY_PRIORITIES = [
+  { id: "passive", name: "静默" },
+  { id: "active", name: "普通" },
+  { id: "time_sensitive", name: "时效性" },
+];
+
+let notifyRules = [];
+
+function loadNotifyRules(pushConfig) {
+  notifyRules = pushConfig.routing_rules || [];
+  document.getElementById("custom-notify-rules").checked =
+    notifyRules.length > 0;
+
+  const quiet = pushConfig.quiet_hours || {};
+  document.getElementById("quiet-hours-enabled").checked =
+    quiet.enabled || false;
+  document.getElementById("quiet-hours-start").value = quiet.start || "23:00";
+  document.getElementById("quiet-hours-end").value = quiet.end || "08:00";
+  const bypass = quiet.bypass_events || ["grab_success"];
+  document.getElementById("quiet-hours-bypass").innerHTML =
+    "<span>免打扰时段仍推送：</span>" +
+    NOTIFY_EVENTS.map(
+      (e) =>
+        `<label><input type="checkbox" id="quiet-bypass-${e.id}" ${bypass.includes(e.id) ? "checked" : ""}/> ${e.name}</label>`,
+    ).join("");
+
+  renderNotifyRules();
+}
+
+// 渠道 × 事件 的勾选表，未保存过规则时按默认行为预填
+function renderNotifyRules() {
+  const container = document.getElementById("notify-rules-table");
+  if (!document.getElementById("custom-notify-rules").checked) {
+    container.innerHTML = "";
+    return;
+  }
+
+  const ruleOf = (channel) => notifyRules.find((r) => r.channel === channel);
+  container.innerHTML = `
+    <table class="notify-rules-table">
+      <tr>
+        <th>渠道</th>
+        <th>优先级</th>
+        ${NOTIFY_EVENTS.map((e) => `<th>${e.name}</th>`).join("")}
+      </tr>
+      ${NOTIFY_CHANNELS.map((c) => {
+        const rule = ruleOf(c.id);
+        const priority = rule ? rule.priority : "active";
+        return `
+          <tr>
+            <td>${c.name}</td>
+            <td>
+              <select class="select-input" id="notify-priority-${c.id}">
+                ${NOTIFY_PRIORITIES.map(
+                  (p) =>
+                    `<option value="${p.id}" ${p.id === priority ? "selected" : ""}>${p.name}</option>`,
+                ).join("")}
+              </select>
+            </td>
+            ${NOTIFY_EVENTS.map((e) => {
+              const checked = rule
+                ? rule.events.includes(e.id)
+                : notifyRules.length === 0 && e.byDefault;
+              return `<td><input type="checkbox" id="notify-${c.id}-${e.id}" ${checked ? "checked" : ""}/></td>`;
+            }).join("")}
+          </tr>`;
+      }).join("")}
+    </table>`;
+}
+
+async function saveNotificationRules() {
+  try {
+    if (!invoke) {
+      throw new Error("Tauri invoke function not available");
+    }
+
+    const custom = document.getElementById("custom-notify-rules").checked;
+    const routingRules = custom
+      ? NOTIFY_CHANNELS.map((c) => ({
+          channel: c.id,
+          priority: document.getElementById(`notify-priority-${c.id}`).value,
+          events: NOTIFY_EVENTS.filter(
+            (e) => document.getElementById(`notify-${c.id}-${e.id}`).checked,
+          ).map((e) => e.id),
+        })).filter((r) => r.events.length > 0)
+      : [];
+
+    if (custom && routingRules.length === 0) {
+      showError("自定义通知规则时，至少为一个渠道勾选一个事件");
+      return;
+    }
+
+    const quietHours = {
+      enabled: document.getElementById("quiet-hours-enabled").checked,
+      start: document.getElementById("quiet-hours-start").value,
+      end: document.getElementById("quiet-hours-end").value,
+      bypass_events: NOTIFY_EVENTS.filter(
+        (e) => document.getElementById(`quiet-bypass-${e.id}`).checked,
+      ).map((e) => e.id),
+    };
+
+    await invoke("set_notification_rules", { routingRules, quietHours });
+    notifyRules = routingRules;
+    showSuccess("通知规则已保存");
+  } catch (error) {
+    showError("保存通知规则失败: " + error);
+  }
+}
+
+const HOOK_EVENTS = NOTIFY_EVENTS.filter((e) =>
+  ["grab_success", "grab_failed", "session_expired"].includes(e.id),
+);
+
+let commandHooks = [];
+
+function loadCommandHooks(hooks) {
+  commandHooks = hooks;
+  renderCommandHooks();
+}
+
+// 先从表格读回当前输入，避免增删行时丢失未保存的修改
+function readCommandHooks() {
+  return commandHooks.map((hook, i) => ({
+    name: document.getElementById(`hook-name-${i}`).value.trim(),
+    enabled: document.getElementById(`hook-enabled-${i}`).checked,
+    command: document.getElementById(`hook-command-${i}`).value.trim(),
+    args: document
+      .getElementById(`hook-args-${i}`)
+      .value.split(/\s+/)
+      .filter((arg) => arg),
+    events: HOOK_EVENTS.filter(
+      (e) => document.getElementById(`hook-${i}-${e.id}`).checked,
+    ).map((e) => e.id),
+    timeout_secs:
+      parseInt(document.getElementById(`hook-timeout-${i}`).value, 10) || 10,
+  }));
+}
+
+function renderCommandHooks() {
+  const container = document.getElementById("command-hooks-table");
+  if (commandHooks.length === 0) {
+    container.innerHTML = "";
+    return;
+  }
+  container.innerHTML = `
+    <table class="notify-rules-table">
+      <tr>
+        <th>启用</th>
+        <th>名称</th>
+        <th>程序</th>
+        <th>参数（空格分隔）</th>
+        ${HOOK_EVENTS.map((e) => `<th>${e.name}</th>`).join("")}
+        <th>超时(秒)</th>
+        <th></th>
+      </tr>
+      ${commandHooks
+        .map(
+          (hook, i) => `
+          <tr>
+            <td><input type="checkbox" id="hook-enabled-${i}" ${hook.enabled !== false ? "checked" : ""}/></td>
+            <td><input type="text" class="form-input" id="hook-name-${i}" style="width: 100px"/></td>
+            <td><input type="text" class="form-input" id="hook-command-${i}" placeholder="/path/to/script"/></td>
+            <td><input type="text" class="form-input" id="hook-args-${i}"/></td>
+            ${HOOK_EVENTS.map((e) => {
+              const events = hook.events || [];
+              const checked = events.length === 0 || events.includes(e.id);
+              return `<td><input type="checkbox" id="hook-${i}-${e.id}" ${checked ? "checked" : ""}/></td>`;
+            }).join("")}
+            <td><input type="number" class="form-input" id="hook-timeout-${i}" min="1" style="width: 60px"/></td>
+            <td>
+              <button class="btn btn-secondary btn-small" onclick="testCommandHook(${i})">测试</button>
+              <button class="btn btn-danger btn-small" onclick="removeCommandHook(${i})">删除</button>
+            </td>
+          </tr>`,
+        )
+        .join("")}
+    </table>`;
+  // 用户输入不拼进HTML，直接写入输入框
+  commandHooks.forEach((hook, i) => {
+    document.getElementById(`hook-name-${i}`).value = hook.name || "";
+    document.getElementById(`hook-command-${i}`).value = hook.command || "";
+    document.getElementById(`hook-args-${i}`).value = (hook.args || []).join(
+      " ",
+    );
+    document.getElementById(`hook-timeout-${i}`).value =
+      hook.timeout_secs || 10;
+  });
+}
+
+function addCommandHook() {
+  commandHooks = readCommandHooks();
+  commandHooks.push({
+    name: "",
+    enabled: true,
+    command: "",
+    args: [],
+    events: [],
+    timeout_secs: 10,
+  });
+  renderCommandHooks();
+}
+
+function removeCommandHook(index) {
+  commandHooks = readCommandHooks();
+  commandHooks.splice(index, 1);
+  renderCommandHooks();
+}
+
+async function saveCommandHooks() {
+  try {
+    const hooks = readCommandHooks();
+    if (hooks.some((hook) => !hook.command)) {
+      showError("请填写钩子要运行的程序");
+      return;
+    }
+    if (hooks.some((hook) => hook.events.length === 0)) {
+      showError("每个钩子至少勾选一个事件");
+      return;
+    }
+    await invoke("set_command_hooks", { hooks });
+    commandHooks = hooks;
+    showSuccess("钩子已保存");
+  } catch (error) {
+    showError("保存钩子失败: " + error);
+  }
+}
+
+async function testCommandHook(index) {
+  const hook = readCommandHooks()[index];
+  if (!hook.command) {
+    showError("请填写钩子要运行的程序");
+    return;
+  }
+  try {
+    await invoke("test_command_hook", { hook });
+    showSuccess("钩子已运行，退出状态和输出见日志");
+  } catch (error) {
+    showError("运行钩子失败: " + error);
+  }
+}
+
+async function saveLanguage() {
+  const lang = document.getElementById("language").value;
+  try {
+    await invoke("set_language", { lang });
+    showSuccess(lang === "en" ? "Language switched" : "语言已切换");
+  } catch (error) {
+    showError("切换语言失败: " + error);
+  }
+}
+
+async function toggleHttpRecording() {
+  const checkbox = document.getElementById("http-recording");
+  try {
+    const path = await invoke("set_http_recording", {
+      enabled: checkbox.checked,
+    });
+    if (path) {
+      showSuccess("正在录制HTTP请求: " + path);
+    } else {
+      showSuccess("已停止录制HTTP请求");
+    }
+  } catch (error) {
+    checkbox.checked = !checkbox.checked;
+    showError("切换HTTP录制失败: " + error);
+  }
+}
+
 async function saveSettings() {
   try {
     if (!invoke) {
@@ -1253,6 +2398,9 @@ async function saveSettings() {
     if (document.getElementById("push-method-gotify").checked) {
       enabledMethods.push("gotify");
     }
+    if (document.getElementById("push-method-desktop").checked) {
+      enabledMethods.push("desktop");
+    }
 
     if (enablePush && enabledMethods.length === 0) {
       showError("启用推送时，必须至少选择一个推送渠道");
@@ -1264,8 +2412,20 @@ async function saveSettings() {
       return;
     }
 
-    if (maxAttempts < 1 || maxAttempts > 1000) {
-      showError("最大尝试次数必须在1-1000之间");
+    if (maxAttempts < 0 || maxAttempts > 1000) {
+      showError("最大尝试次数必须在0-1000之间");
+      return;
+    }
+
+    const retryPolicy = readRetryPolicy(
+      parseInt(delayTime),
+      parseInt(maxAttempts),
+    );
+    if (!retryPolicy) {
+      return;
+    }
+    const requestPacing = readRequestPacing();
+    if (!requestPacing) {
       return;
     }
 
@@ -1288,6 +2448,8 @@ async function saveSettings() {
       grabMode: parseInt(grabMode),
       delayTime: parseInt(delayTime),
       maxAttempts: parseInt(maxAttempts),
+      retryPolicy: retryPolicy,
+      requestPacing: requestPacing,
       enablePush: enablePush,
       enabledMethods: enabledMethods,
       barkToken: barkToken,
@@ -1314,11 +2476,107 @@ async function saveSettings() {
   }
 }
 
+// 未在设置页展示的字段（各阶段基础延迟等）保留已保存的值
+let currentRetryPolicy = null;
+
+function loadRetryPolicy(policy) {
+  if (!policy) {
+    return;
+  }
+  currentRetryPolicy = policy;
+  document.getElementById("max-attempts").value = policy.order.max_attempts;
+  document.getElementById("retry-token-attempts").value =
+    policy.token.max_attempts;
+  document.getElementById("retry-confirm-attempts").value =
+    policy.confirm.max_attempts;
+  document.getElementById("retry-backoff").value = policy.backoff;
+  document.getElementById("retry-jitter").value = Math.round(
+    policy.jitter * 100,
+  );
+  document.getElementById("retry-deadline").value = policy.deadline_secs;
+}
+
+function readRetryPolicy(delayTime, maxAttempts) {
+  const number = (id) => parseInt(document.getElementById(id).value);
+  const tokenAttempts = number("retry-token-attempts");
+  const confirmAttempts = number("retry-confirm-attempts");
+  const jitter = number("retry-jitter");
+  const deadline = number("retry-deadline");
+  if (!(tokenAttempts >= 1) || !(confirmAttempts >= 1)) {
+    showError("重试次数必须大于0");
+    return null;
+  }
+  if (!(jitter >= 0 && jitter <= 100)) {
+    showError("随机抖动必须在0-100之间");
+    return null;
+  }
+  if (!(deadline >= 0)) {
+    showError("抢票总时限不能为负数");
+    return null;
+  }
+  const base = currentRetryPolicy || {
+    token: { delay_ms: 1000 },
+    confirm: { delay_ms: 300 },
+    order: { delay_ms: 400 },
+    max_delay_ms: 5000,
+  };
+  return {
+    token: { max_attempts: tokenAttempts, delay_ms: base.token.delay_ms },
+    confirm: { max_attempts: confirmAttempts, delay_ms: base.confirm.delay_ms },
+    order: { max_attempts: maxAttempts, delay_ms: base.order.delay_ms },
+    poll_interval_ms: delayTime * 1000,
+    backoff: document.getElementById("retry-backoff").value,
+    max_delay_ms: base.max_delay_ms,
+    jitter: jitter / 100,
+    deadline_secs: deadline,
+  };
+}
+
+// 退避时间等未在设置页展示的字段保留已保存的值
+let currentRequestPacing = null;
+
+function loadRequestPacing(pacing) {
+  if (!pacing) {
+    return;
+  }
+  currentRequestPacing = pacing;
+  document.getElementById("pacing-enabled").checked = pacing.enabled;
+  document.getElementById("pacing-min-interval").value =
+    pacing.min_interval_ms;
+}
+
+function readRequestPacing() {
+  const minInterval = parseInt(
+    document.getElementById("pacing-min-interval").value,
+  );
+  if (!(minInterval >= 0 && minInterval <= 10000)) {
+    showError("请求最小间隔必须在0-10000毫秒之间");
+    return null;
+  }
+  const base = currentRequestPacing || {
+    backoff_base_ms: 1000,
+    backoff_max_ms: 30000,
+  };
+  return {
+    enabled: document.getElementById("pacing-enabled").checked,
+    min_interval_ms: minInterval,
+    backoff_base_ms: base.backoff_base_ms,
+    backoff_max_ms: base.backoff_max_ms,
+  };
+}
+
 function resetSettings() {
   if (confirm("确定要恢复默认设置吗？")) {
     document.getElementById("grab-mode").value = "0";
     document.getElementById("delay-time").value = "2";
-    document.getElementById("max-attempts").value = "100";
+    document.getElementById("max-attempts").value = "0";
+    document.getElementById("retry-token-attempts").value = "5";
+    document.getElementById("retry-confirm-attempts").value = "4";
+    document.getElementById("retry-backoff").value = "fixed";
+    document.getElementById("retry-jitter").value = "0";
+    document.getElementById("retry-deadline").value = "0";
+    document.getElementById("pacing-enabled").checked = false;
+    document.getElementById("pacing-min-interval").value = "200";
     document.getElementById("enable-push").checked = false;
     document.getElementById("bark-token").value = "";
     document.getElementById("pushplus-token").value = "";
@@ -1380,6 +2638,18 @@ async function clearLogs() {
   }
 }
 
+async function exportDiagnostics() {
+  try {
+    if (!invoke) {
+      throw new Error("Tauri invoke function not available");
+    }
+    const path = await invoke("export_diagnostics");
+    showSuccess("诊断包已生成（已脱敏，可直接附在问题反馈中）: " + path);
+  } catch (error) {
+    showError("生成诊断包失败: " + error);
+  }
+}
+
 async function exportLogs() {
   try {
     if (!invoke) {
@@ -1537,6 +2807,7 @@ function switchTab(tabName) {
       if (typeof refreshMonitor === "function") {
         refreshMonitor();
       }
+      loadGrabProfiles();
       if (typeof resetMonitorStats === "function") {
         resetMonitorStats();
       }
@@ -1558,21 +2829,100 @@ function switchTab(tabName) {
   }
 }
 
+// 按设置页当前填写的内容组装推送配置（未保存也可以测试）
+function collectPushConfig() {
+  const value = (id) => document.getElementById(id).value;
+  return {
+    enabled: document.getElementById("enable-push").checked,
+    enabled_methods: [
+      "bark",
+      "pushplus",
+      "fangtang",
+      "dingtalk",
+      "wechat",
+      "smtp",
+      "gotify",
+      "desktop",
+    ].filter((m) => document.getElementById(`push-method-${m}`).checked),
+    bark_token: value("bark-token"),
+    pushplus_token: value("pushplus-token"),
+    fangtang_token: value("fangtang-token"),
+    dingtalk_token: value("dingtalk-token"),
+    wechat_token: value("wechat-token"),
+    gotify_config: {
+      gotify_url: value("gotify-url"),
+      gotify_token: value("gotify-token"),
+    },
+    smtp_config: {
+      smtp_server: value("smtp-server"),
+      smtp_port: value("smtp-port"),
+      smtp_username: value("smtp-username"),
+      smtp_password: value("smtp-password"),
+      smtp_from: value("smtp-from"),
+      smtp_to: value("smtp-to"),
+    },
+  };
+}
+
+function renderPushResults(results) {
+  const container = document.getElementById("push-test-result");
+  container.innerHTML = `
+    <ul class="push-test-results">
+      ${results
+        .map(
+          (r) =>
+            `<li class="${r.success ? "color-success" : "color-error"}">${r.success ? "✔" : "✘"} ${r.channel}${r.status_code ? `（HTTP ${r.status_code}）` : ""}：${r.message}</li>`,
+        )
+        .join("")}
+    </ul>`;
+}
+
 async function testPush() {
   if (!invoke) {
     showError("Tauri API不可用，无法测试推送");
     return;
   }
 
-  if (!confirm("确定要发送测试推送吗？")) {
+  if (!confirm("确定要向所有已启用渠道发送测试推送吗？")) {
     return;
   }
 
   try {
-    const result = await invoke("push_test");
-    showSuccess("测试推送已发送：" + result);
+    const results = await invoke("push_test", {
+      pushConfig: collectPushConfig(),
+    });
+    renderPushResults(results);
+    const failed = results.filter((r) => !r.success).length;
+    if (failed === 0) {
+      showSuccess(`测试推送已发送到${results.length}个渠道`);
+    } else {
+      showError(`${failed}个渠道推送失败，详见下方结果`);
+    }
   } catch (error) {
-    showError("设置失败: " + error);
+    showError("测试推送失败: " + error);
+  }
+}
+
+async function testPushChannel(channel) {
+  if (!invoke) {
+    showError("Tauri API不可用，无法测试推送");
+    return;
+  }
+
+  try {
+    const results = await invoke("push_test", {
+      pushType: channel,
+      pushConfig: collectPushConfig(),
+    });
+    renderPushResults(results);
+    const result = results[0];
+    if (result && result.success) {
+      showSuccess(`${channel} 测试推送成功`);
+    } else {
+      showError(`${channel} 测试推送失败: ${result ? result.message : "无结果"}`);
+    }
+  } catch (error) {
+    showError("测试推送失败: " + error);
   }
 }
 
@@ -1745,6 +3095,7 @@ function updatePushSettingsVisibility() {
     "wechat",
     "smtp",
     "gotify",
+    "desktop",
   ];
   methods.forEach((method) => {
     const checkbox = document.getElementById(`push-method-${method}`);
@@ -1764,6 +3115,7 @@ function setupPushSettingsEventListeners() {
     "wechat",
     "smtp",
     "gotify",
+    "desktop",
   ];
   methods.forEach((method) => {
     const checkbox = document.getElementById(`push-method-${method}`);